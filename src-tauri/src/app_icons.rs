@@ -0,0 +1,126 @@
+//! Application icons for the "copied from" badge on list items.
+//!
+//! Icons are looked up by bundle id, rendered to a small PNG once and kept in
+//! the app data dir, so rendering the list never has to go back to the
+//! system. Lookups that fail are remembered too, since apps without a
+//! readable icon would otherwise be retried on every render.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Edge length of the cached PNG; plenty for a list badge on retina displays
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+const ICON_SIZE: u32 = 64;
+
+pub struct AppIconCache {
+    dir: PathBuf,
+    /// `None` records a bundle id whose icon could not be found
+    memo: Mutex<HashMap<String, Option<String>>>,
+}
+
+impl AppIconCache {
+    pub fn new(app_data_dir: PathBuf) -> Self {
+        let dir = app_data_dir.join("app-icons");
+        std::fs::create_dir_all(&dir).ok();
+        Self {
+            dir,
+            memo: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Icon for `bundle_id` as a PNG data URL
+    pub fn get(&self, bundle_id: &str) -> Option<String> {
+        if let Some(cached) = self.memo.lock().unwrap().get(bundle_id) {
+            return cached.clone();
+        }
+
+        let icon = self.load(bundle_id);
+        self.memo
+            .lock()
+            .unwrap()
+            .insert(bundle_id.to_string(), icon.clone());
+        icon
+    }
+
+    fn load(&self, bundle_id: &str) -> Option<String> {
+        // Bundle ids are reverse-DNS; anything else would be unsafe as a file name
+        if bundle_id.is_empty()
+            || !bundle_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_')
+        {
+            return None;
+        }
+
+        let path = self.dir.join(format!("{}.png", bundle_id));
+        if !path.exists() {
+            render_icon(bundle_id, &path)?;
+        }
+
+        let bytes = std::fs::read(&path).ok()?;
+        Some(format!("data:image/png;base64,{}", STANDARD.encode(bytes)))
+    }
+}
+
+/// Find the app with `bundle_id` and write its icon to `out` as a PNG
+#[cfg(target_os = "macos")]
+fn render_icon(bundle_id: &str, out: &std::path::Path) -> Option<()> {
+    use std::process::Command;
+
+    let output = Command::new("mdfind")
+        .arg(format!("kMDItemCFBundleIdentifier == '{}'", bundle_id))
+        .output()
+        .ok()?;
+    let app_path = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.ends_with(".app"))?
+        .to_string();
+
+    let output = Command::new("defaults")
+        .arg("read")
+        .arg(format!("{}/Contents/Info", app_path))
+        .arg("CFBundleIconFile")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let mut icon_file = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if icon_file.is_empty() {
+        return None;
+    }
+    if !icon_file.ends_with(".icns") {
+        icon_file.push_str(".icns");
+    }
+
+    let icns = format!("{}/Contents/Resources/{}", app_path, icon_file);
+    let status = Command::new("sips")
+        .args(["-s", "format", "png", "-Z", &ICON_SIZE.to_string()])
+        .arg(&icns)
+        .arg("--out")
+        .arg(out)
+        .output()
+        .ok()?
+        .status;
+
+    (status.success() && out.exists()).then_some(())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn render_icon(_bundle_id: &str, _out: &std::path::Path) -> Option<()> {
+    None
+}
+
+/// Get the icon of the app with `bundle_id` as a PNG data URL, or `None` if
+/// it has no icon we can read
+#[tauri::command]
+pub async fn get_app_icon(
+    cache: tauri::State<'_, AppIconCache>,
+    bundle_id: String,
+) -> Result<Option<String>, String> {
+    let cache = cache.inner();
+    // The first lookup shells out, so keep it off the async runtime's workers
+    Ok(tokio::task::block_in_place(|| cache.get(&bundle_id)))
+}
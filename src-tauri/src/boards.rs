@@ -0,0 +1,94 @@
+use crate::database::{Board, ClipboardItem, Database};
+use crate::hotkey::HotkeyManager;
+use chrono::Utc;
+use tauri::{AppHandle, Runtime};
+use uuid::Uuid;
+
+/// Re-register board hotkeys after any change to the set of boards
+fn sync_board_hotkeys<R: Runtime>(
+    app: &AppHandle<R>,
+    db: &Database,
+    hotkey_manager: &HotkeyManager,
+) -> Result<(), String> {
+    let boards = db.get_boards().map_err(|e| e.to_string())?;
+    hotkey_manager.register_board_hotkeys(app, &boards)
+}
+
+#[tauri::command]
+pub async fn create_board<R: Runtime>(
+    app: AppHandle<R>,
+    db: tauri::State<'_, Database>,
+    hotkey_manager: tauri::State<'_, HotkeyManager>,
+    name: String,
+    hotkey: Option<String>,
+) -> Result<Board, String> {
+    let board = Board {
+        id: Uuid::new_v4().to_string(),
+        name,
+        hotkey,
+        created_at: Utc::now(),
+    };
+
+    db.create_board(&board).map_err(|e| e.to_string())?;
+    sync_board_hotkeys(&app, &db, &hotkey_manager)?;
+
+    Ok(board)
+}
+
+#[tauri::command]
+pub async fn get_boards(db: tauri::State<'_, Database>) -> Result<Vec<Board>, String> {
+    db.get_boards().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_board<R: Runtime>(
+    app: AppHandle<R>,
+    db: tauri::State<'_, Database>,
+    hotkey_manager: tauri::State<'_, HotkeyManager>,
+    id: String,
+    name: String,
+    hotkey: Option<String>,
+) -> Result<(), String> {
+    db.update_board(&id, &name, hotkey.as_deref())
+        .map_err(|e| e.to_string())?;
+    sync_board_hotkeys(&app, &db, &hotkey_manager)
+}
+
+#[tauri::command]
+pub async fn delete_board<R: Runtime>(
+    app: AppHandle<R>,
+    db: tauri::State<'_, Database>,
+    hotkey_manager: tauri::State<'_, HotkeyManager>,
+    id: String,
+) -> Result<(), String> {
+    db.delete_board(&id).map_err(|e| e.to_string())?;
+    sync_board_hotkeys(&app, &db, &hotkey_manager)
+}
+
+#[tauri::command]
+pub async fn add_item_to_board(
+    db: tauri::State<'_, Database>,
+    board_id: String,
+    item_id: String,
+) -> Result<(), String> {
+    db.add_item_to_board(&board_id, &item_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn remove_item_from_board(
+    db: tauri::State<'_, Database>,
+    board_id: String,
+    item_id: String,
+) -> Result<(), String> {
+    db.remove_item_from_board(&board_id, &item_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_board_items(
+    db: tauri::State<'_, Database>,
+    board_id: String,
+) -> Result<Vec<ClipboardItem>, String> {
+    db.get_board_items(&board_id).map_err(|e| e.to_string())
+}
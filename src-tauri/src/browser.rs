@@ -0,0 +1,217 @@
+//! Companion endpoint for the Yoink browser extension.
+//!
+//! The extension POSTs the page URL, title, selection and favicon alongside a
+//! copy so browser-originated items carry more than the pasteboard provides.
+//! The server only listens on 127.0.0.1 and requires the shared token from
+//! settings in an `X-Yoink-Token` header.
+
+use crate::clipboard::{self, ClipboardMonitor};
+use crate::database::ClipboardItem;
+use crate::session;
+use crate::settings::SettingsManager;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Favicons arrive as data URLs; anything larger than this is rejected
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+struct BrowserClip {
+    url: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    selection: Option<String>,
+    #[serde(default)]
+    favicon: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompanionInfo {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+}
+
+/// Start the companion server on a background thread if enabled in settings
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    let Some(settings_manager) = app.try_state::<SettingsManager>() else {
+        return;
+    };
+    let settings = settings_manager.get();
+    if !settings.browser_extension_enabled {
+        return;
+    }
+
+    let token = match ensure_token(&settings_manager) {
+        Ok(token) => token,
+        Err(e) => {
+            log::warn!("Failed to create browser extension token: {}", e);
+            return;
+        }
+    };
+
+    let listener = match TcpListener::bind(("127.0.0.1", settings.browser_extension_port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::warn!(
+                "Failed to bind browser extension endpoint on port {}: {}",
+                settings.browser_extension_port,
+                e
+            );
+            return;
+        }
+    };
+
+    log::info!(
+        "Browser extension endpoint listening on 127.0.0.1:{}",
+        settings.browser_extension_port
+    );
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Err(e) = handle_connection(&app, stream, &token) {
+                log::warn!("Browser extension request failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Return the shared token, generating and persisting one on first use
+fn ensure_token(settings_manager: &SettingsManager) -> Result<String, String> {
+    let existing = settings_manager.get().browser_extension_token;
+    if !existing.is_empty() {
+        return Ok(existing);
+    }
+
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    let settings = settings_manager.update_field(|s| s.browser_extension_token = token)?;
+    Ok(settings.browser_extension_token)
+}
+
+fn handle_connection<R: Runtime>(
+    app: &AppHandle<R>,
+    stream: TcpStream,
+    token: &str,
+) -> Result<(), String> {
+    stream
+        .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+        .map_err(|e| e.to_string())?;
+
+    let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+    let mut stream = stream;
+
+    let mut request_line = String::new();
+    reader
+        .read_line(&mut request_line)
+        .map_err(|e| e.to_string())?;
+
+    let mut content_length = 0usize;
+    let mut request_token = None;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).map_err(|e| e.to_string())?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-length" => content_length = value.parse().unwrap_or(0),
+                "x-yoink-token" => request_token = Some(value.to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    if !request_line.starts_with("POST /clip ") {
+        return respond(&mut stream, "404 Not Found", "not found");
+    }
+    if request_token.as_deref() != Some(token) {
+        return respond(&mut stream, "401 Unauthorized", "invalid token");
+    }
+    if content_length == 0 || content_length > MAX_BODY_BYTES {
+        return respond(&mut stream, "413 Payload Too Large", "invalid body size");
+    }
+
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).map_err(|e| e.to_string())?;
+
+    let clip: BrowserClip = match serde_json::from_slice(&body) {
+        Ok(clip) => clip,
+        Err(e) => return respond(&mut stream, "400 Bad Request", &e.to_string()),
+    };
+
+    // Pausing capture (safe mode starts paused) or switching users stops
+    // browser clips too, like clipboard polling
+    let paused = app
+        .try_state::<ClipboardMonitor>()
+        .map_or(true, |monitor| monitor.is_paused());
+    if paused || !session::is_session_active(app) {
+        return respond(&mut stream, "423 Locked", "capture paused");
+    }
+
+    match record_browser_clip(app, clip) {
+        Ok(()) => respond(&mut stream, "200 OK", "ok"),
+        Err(e) => respond(&mut stream, "500 Internal Server Error", &e),
+    }
+}
+
+fn record_browser_clip<R: Runtime>(app: &AppHandle<R>, clip: BrowserClip) -> Result<(), String> {
+    let content = clip
+        .selection
+        .clone()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| clip.url.clone());
+
+    let content_type = clipboard::detect_content_type(&content);
+    let mut metadata = clipboard::text_metadata(&content_type, &content)
+        .unwrap_or_else(|| serde_json::json!({}));
+    metadata["source"] = "browser".into();
+    metadata["url"] = clip.url.into();
+    metadata["title"] = clip.title.into();
+    metadata["favicon"] = clip.favicon.into();
+
+    let mut item = ClipboardItem::new(
+        content_type,
+        content.clone(),
+        clipboard::create_text_preview(&content),
+        clipboard::compute_hash(&content),
+    );
+    item.metadata = Some(metadata);
+
+    let monitor = app.state::<ClipboardMonitor>();
+    clipboard::record_capture(app, &monitor, item)?;
+
+    Ok(())
+}
+
+fn respond(stream: &mut TcpStream, status: &str, body: &str) -> Result<(), String> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream
+        .write_all(response.as_bytes())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_browser_companion_info(
+    settings_manager: tauri::State<'_, SettingsManager>,
+) -> Result<CompanionInfo, String> {
+    let token = ensure_token(&settings_manager)?;
+    let settings = settings_manager.get();
+
+    Ok(CompanionInfo {
+        enabled: settings.browser_extension_enabled,
+        port: settings.browser_extension_port,
+        token,
+    })
+}
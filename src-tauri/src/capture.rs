@@ -0,0 +1,31 @@
+/// Interactive screen capture
+use std::path::PathBuf;
+
+/// Let the user select a screen region and save it as a PNG in the temp dir.
+/// Returns `Ok(None)` if the selection was cancelled (e.g. with Escape).
+#[cfg(target_os = "macos")]
+pub fn capture_region() -> Result<Option<PathBuf>, String> {
+    use std::process::Command;
+
+    let path = std::env::temp_dir().join(format!("yoink-capture-{}.png", uuid::Uuid::new_v4()));
+
+    // -i: interactive selection, -x: no shutter sound
+    let status = Command::new("screencapture")
+        .arg("-i")
+        .arg("-x")
+        .arg(&path)
+        .status()
+        .map_err(|e| e.to_string())?;
+
+    if !status.success() {
+        return Err(format!("screencapture exited with {}", status));
+    }
+
+    // screencapture exits successfully without writing a file when cancelled
+    Ok(path.exists().then_some(path))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn capture_region() -> Result<Option<PathBuf>, String> {
+    Err("Screen capture not implemented for this platform".to_string())
+}
@@ -1,22 +1,96 @@
-use crate::database::{ClipboardItem, Database};
+use crate::color;
+use crate::contact;
+use crate::daily_notes;
+use crate::enrich;
+use crate::database::{
+    ClipboardItem, Database, DedupeReport, ItemCursor, ItemMeta, ItemPage, ItemSort, ItemVersion,
+    Provenance,
+};
+use crate::exclusions;
+use crate::lint::{self, PasteConfirmationRequest, PasteConfirmationState};
 use crate::keyboard;
+use crate::palette;
+use crate::paste_helper::{self, PasteOptions};
+use crate::pasteboard::{self, RichText};
+use crate::perf::{self, PerfMonitor};
+use crate::queue;
+use crate::search::{self, ItemFilter, SearchOptions};
+use crate::session;
+use crate::settings::{PasteOverride, Settings, SettingsManager};
+use crate::stack;
+use crate::symbols;
+use crate::templates::{self, TemplateValuesRequest};
+use crate::titles;
+use crate::transforms;
 use base64::{engine::general_purpose::STANDARD, Engine};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Mutex;
+use std::time::Instant;
 use tauri::{AppHandle, Emitter, Manager, Runtime};
 use crate::window::HotkeyModeState;
 use tauri_plugin_clipboard_manager::ClipboardExt;
-use uuid::Uuid;
+
+/// How long concealed (password manager) copies are kept in "expire" mode
+const CONCEALED_TTL_SECS: i64 = 60;
+
+/// Captures arriving within this window are written in one transaction
+const CAPTURE_BATCH_WINDOW_MS: u64 = 250;
+
+/// How long a transient paste leaves the item on the clipboard, giving the
+/// target app time to read it, before the previous contents go back
+const TRANSIENT_RESTORE_DELAY_MS: u64 = 500;
+
+/// Pause between a paste and the Return of a paste-and-submit, so the target
+/// app has inserted the text before it's submitted
+const SUBMIT_DELAY_MS: u64 = 80;
 
 pub struct ClipboardMonitor {
     last_hash: Mutex<Option<String>>,
+    /// Captures waiting for the next batched write
+    pending: Mutex<Vec<ClipboardItem>>,
+    flush_scheduled: AtomicBool,
+    paused: AtomicBool,
+    /// Bumped on every pause/resume so stale auto-resume timers do nothing
+    pause_generation: AtomicU64,
+    resume_at: Mutex<Option<DateTime<Utc>>>,
+    append_mode: AtomicBool,
+    /// Item that copies are being appended to while append mode is on
+    append_target: Mutex<Option<String>>,
+    /// Hash of text we are about to write ourselves, and where it came from
+    expected_derived: Mutex<Option<(String, Provenance)>>,
+    /// PRIMARY selection seen on the previous poll; captured once it stops changing
+    #[cfg(target_os = "linux")]
+    primary_candidate: Mutex<Option<String>>,
+    #[cfg(target_os = "linux")]
+    last_primary_hash: Mutex<Option<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitoringState {
+    pub paused: bool,
+    pub resume_at: Option<DateTime<Utc>>,
 }
 
 impl ClipboardMonitor {
     pub fn new() -> Self {
         Self {
             last_hash: Mutex::new(None),
+            pending: Mutex::new(Vec::new()),
+            flush_scheduled: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
+            pause_generation: AtomicU64::new(0),
+            resume_at: Mutex::new(None),
+            append_mode: AtomicBool::new(false),
+            append_target: Mutex::new(None),
+            expected_derived: Mutex::new(None),
+            #[cfg(target_os = "linux")]
+            primary_candidate: Mutex::new(None),
+            #[cfg(target_os = "linux")]
+            last_primary_hash: Mutex::new(None),
         }
     }
 
@@ -25,19 +99,116 @@ impl ClipboardMonitor {
             *self.last_hash.lock().unwrap() = hash;
         }
     }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Pause capture, returning the generation to hand to an auto-resume timer
+    pub fn pause(&self, resume_at: Option<DateTime<Utc>>) -> u64 {
+        self.paused.store(true, Ordering::SeqCst);
+        *self.resume_at.lock().unwrap() = resume_at;
+        self.pause_generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+        *self.resume_at.lock().unwrap() = None;
+        self.pause_generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn is_appending(&self) -> bool {
+        self.append_mode.load(Ordering::SeqCst)
+    }
+
+    /// Flip append mode. Each time it's turned on, the next copy starts a new item.
+    pub fn toggle_append_mode(&self) -> bool {
+        *self.append_target.lock().unwrap() = None;
+        !self.append_mode.fetch_xor(true, Ordering::SeqCst)
+    }
+
+    /// Note that `text` is about to be written to the clipboard as a
+    /// derivative of another item, so its capture records the provenance
+    pub fn expect_derived(&self, text: &str, provenance: Provenance) {
+        *self.expected_derived.lock().unwrap() = Some((compute_hash(text), provenance));
+    }
+
+    /// Treat `hash` as already seen, so contents we put back ourselves
+    /// aren't captured again
+    pub fn mark_seen(&self, hash: String) {
+        *self.last_hash.lock().unwrap() = Some(hash);
+    }
+
+    fn take_provenance(&self, hash: &str) -> Option<Provenance> {
+        let mut expected = self.expected_derived.lock().unwrap();
+        match expected.take() {
+            Some((expected_hash, provenance)) if expected_hash == hash => Some(provenance),
+            _ => None,
+        }
+    }
+
+    pub fn state(&self) -> MonitoringState {
+        MonitoringState {
+            paused: self.is_paused(),
+            resume_at: *self.resume_at.lock().unwrap(),
+        }
+    }
 }
 
 // Called from frontend via polling
 #[tauri::command]
 pub async fn check_clipboard<R: Runtime>(
     app: AppHandle<R>,
-    db: tauri::State<'_, Database>,
     monitor: tauri::State<'_, ClipboardMonitor>,
-) -> Result<Option<ClipboardItem>, String> {
+    settings_manager: tauri::State<'_, SettingsManager>,
+) -> Result<bool, String> {
+    // Only polls that captured something are timed; idle polls would drown them out
+    let started = Instant::now();
+    let result = capture_clipboard(&app, monitor, settings_manager);
+    if matches!(result, Ok(true)) {
+        perf::record_since(&app, perf::CAPTURE, started);
+    }
+    result
+}
+
+/// Capture whatever is new on the clipboard. Returns whether anything was
+/// recorded; the rows themselves are written by the next batch.
+fn capture_clipboard<R: Runtime>(
+    app: &AppHandle<R>,
+    monitor: tauri::State<'_, ClipboardMonitor>,
+    settings_manager: tauri::State<'_, SettingsManager>,
+) -> Result<bool, String> {
+    // The webview keeps polling while another user is on the console
+    if !session::is_session_active(app) {
+        return Ok(false);
+    }
+
     let clipboard = app.clipboard();
 
-    // Try to read text content
-    if let Ok(text) = clipboard.read_text() {
+    // Password managers flag their copies; never store transient ones, and
+    // either skip or quickly expire concealed ones depending on settings
+    let settings = settings_manager.get();
+    let markers = pasteboard::markers();
+    let expire_concealed = settings.concealed_copy_mode == "expire";
+    let skip_capture = monitor.is_paused()
+        || markers.transient
+        || (markers.concealed && !expire_concealed)
+        || (markers.remote && !settings.capture_handoff);
+
+    // On X11 the PRIMARY selection is a second clipboard worth recording
+    #[cfg(target_os = "linux")]
+    if settings.capture_primary_selection && !skip_capture {
+        if capture_primary_selection(app, &monitor)? {
+            return Ok(true);
+        }
+    }
+
+    // Try to read text content, or a native file list where files aren't
+    // also offered as text
+    let text = pasteboard::read_file_list()
+        .map(|paths| paths.join("\n"))
+        .or_else(|| clipboard.read_text().ok());
+    if let Some(text) = text {
         if !text.is_empty() {
             let hash = compute_hash(&text);
 
@@ -45,38 +216,63 @@ pub async fn check_clipboard<R: Runtime>(
             {
                 let last_hash = monitor.last_hash.lock().unwrap();
                 if last_hash.as_ref() == Some(&hash) {
-                    return Ok(None);
+                    return Ok(false);
                 }
             }
 
-            // Create clipboard item
-            let preview = create_text_preview(&text);
-            let item = ClipboardItem {
-                id: Uuid::new_v4().to_string(),
-                content_type: detect_content_type(&text),
-                content: text,
-                preview,
-                hash: hash.clone(),
-                is_pinned: false,
-                collection_id: None,
-                created_at: Utc::now(),
-                expires_at: None,
-            };
+            // Remember skipped content so it isn't captured later (e.g. on resume)
+            if skip_capture {
+                *monitor.last_hash.lock().unwrap() = Some(hash);
+                return Ok(false);
+            }
 
-            // Store in database
-            db.insert_item(&item).map_err(|e| e.to_string())?;
-            db.enforce_limit(100).map_err(|e| e.to_string())?;
+            if monitor.is_appending() && !markers.concealed {
+                append_capture(app, &monitor, &settings.append_separator, text, hash)?;
+                return Ok(true);
+            }
 
-            *monitor.last_hash.lock().unwrap() = Some(hash);
+            let content_type = detect_content_type(&text);
+            if !settings.capture_files && (content_type == "file" || content_type == "files") {
+                *monitor.last_hash.lock().unwrap() = Some(hash);
+                return Ok(false);
+            }
 
-            // Emit event to frontend
-            let _ = app.emit("clipboard-changed", &item);
+            let preview = create_text_preview(&text);
+            let mut metadata = text_metadata(&content_type, &text);
+            // Keep the rich flavors so pasting can restore the formatting
+            let rich = pasteboard::read_rich_text();
+            if !rich.is_empty() {
+                let metadata = metadata.get_or_insert_with(|| serde_json::json!({}));
+                if let Some(html) = rich.html {
+                    metadata["html"] = html.into();
+                }
+                if let Some(rtf) = rich.rtf {
+                    metadata["rtf"] = rtf.into();
+                }
+            }
+            let provenance = monitor.take_provenance(&hash);
+            let mut item = ClipboardItem::new(content_type, text, preview, hash);
+            item.metadata = metadata;
+            item.provenance = provenance;
+            mark_origin(&mut item, &markers);
+            if markers.concealed {
+                item.expires_at = Some(Utc::now() + chrono::Duration::seconds(CONCEALED_TTL_SECS));
+            }
 
-            return Ok(Some(item));
+            record_capture(app, &monitor, item)?;
+            if markers.concealed {
+                schedule_expiry(app, CONCEALED_TTL_SECS as u64);
+            }
+            return Ok(true);
         }
     }
 
-    // Try to read image content
+    // Try to read image content (skipped entirely when image capture is off,
+    // since decoding large screenshots is the expensive part)
+    if !settings.capture_images {
+        return Ok(false);
+    }
+
     if let Ok(image) = clipboard.read_image() {
         let rgba = image.rgba();
         if !rgba.is_empty() {
@@ -85,50 +281,464 @@ pub async fn check_clipboard<R: Runtime>(
             {
                 let last_hash = monitor.last_hash.lock().unwrap();
                 if last_hash.as_ref() == Some(&hash) {
-                    return Ok(None);
+                    return Ok(false);
                 }
             }
 
-            let base64_content = STANDARD.encode(&rgba);
+            if skip_capture {
+                *monitor.last_hash.lock().unwrap() = Some(hash);
+                return Ok(false);
+            }
+
+            let mut item = image_item(&rgba, image.width(), image.height(), hash);
+            mark_origin(&mut item, &markers);
+
+            record_capture(app, &monitor, item)?;
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Record the PRIMARY selection as an item flagged `selection: "primary"`.
+/// Selections change continuously while the mouse drags, so a value is only
+/// taken once two consecutive polls agree.
+#[cfg(target_os = "linux")]
+fn capture_primary_selection<R: Runtime>(
+    app: &AppHandle<R>,
+    monitor: &ClipboardMonitor,
+) -> Result<bool, String> {
+    let Some(text) = pasteboard::read_primary_selection().filter(|t| !t.trim().is_empty()) else {
+        return Ok(false);
+    };
+    let hash = compute_hash(&text);
+
+    {
+        let mut candidate = monitor.primary_candidate.lock().unwrap();
+        let stable = candidate.as_ref() == Some(&hash);
+        *candidate = Some(hash.clone());
+        if !stable {
+            return Ok(false);
+        }
+    }
+
+    {
+        let mut last_primary = monitor.last_primary_hash.lock().unwrap();
+        if last_primary.as_ref() == Some(&hash) {
+            return Ok(false);
+        }
+        *last_primary = Some(hash.clone());
+    }
+
+    // Selecting text that was just copied shouldn't record it twice
+    let clipboard_hash = monitor.last_hash.lock().unwrap().clone();
+    if clipboard_hash.as_ref() == Some(&hash) {
+        return Ok(false);
+    }
+
+    let content_type = detect_content_type(&text);
+    let preview = create_text_preview(&text);
+    let mut metadata = text_metadata(&content_type, &text).unwrap_or_else(|| serde_json::json!({}));
+    metadata["selection"] = "primary".into();
+    let mut item = ClipboardItem::new(content_type, text, preview, hash);
+    item.metadata = Some(metadata);
+
+    // record_capture tracks the regular clipboard's hash; keep that intact so
+    // the current clipboard contents aren't re-captured on the next poll
+    let recorded = record_capture(app, monitor, item);
+    *monitor.last_hash.lock().unwrap() = clipboard_hash;
+    recorded.map(|()| true)
+}
+
+/// Clipboard contents saved before a transient paste
+enum ClipboardSnapshot {
+    Text { text: String, rich: RichText },
+    Files(Vec<String>),
+    Image { rgba: Vec<u8>, width: u32, height: u32 },
+}
+
+impl ClipboardSnapshot {
+    /// Read the current contents, in the same order capture checks them
+    fn take<R: Runtime>(app: &AppHandle<R>) -> Option<Self> {
+        let clipboard = app.clipboard();
+
+        if let Some(paths) = pasteboard::read_file_list() {
+            return Some(Self::Files(paths));
+        }
+        if let Ok(text) = clipboard.read_text() {
+            if !text.is_empty() {
+                let rich = pasteboard::read_rich_text();
+                return Some(Self::Text { text, rich });
+            }
+        }
+        let image = clipboard.read_image().ok()?;
+        (!image.rgba().is_empty()).then(|| Self::Image {
+            rgba: image.rgba().to_vec(),
+            width: image.width(),
+            height: image.height(),
+        })
+    }
+
+    /// Hash capture computes for these contents
+    fn hash(&self) -> String {
+        match self {
+            Self::Text { text, .. } => compute_hash(text),
+            Self::Files(paths) => compute_hash(&paths.join("\n")),
+            Self::Image { rgba, .. } => compute_hash_bytes(rgba),
+        }
+    }
 
-            let item = ClipboardItem {
-                id: Uuid::new_v4().to_string(),
-                content_type: "image".to_string(),
-                content: base64_content,
-                preview: format!("Image ({}x{})", image.width(), image.height()),
-                hash: hash.clone(),
-                is_pinned: false,
-                collection_id: None,
-                created_at: Utc::now(),
-                expires_at: None,
-            };
+    fn restore<R: Runtime>(&self, app: &AppHandle<R>) -> Result<(), String> {
+        let clipboard = app.clipboard();
 
-            db.insert_item(&item).map_err(|e| e.to_string())?;
-            db.enforce_limit(100).map_err(|e| e.to_string())?;
+        match self {
+            Self::Text { text, rich } => {
+                clipboard.write_text(text).map_err(|e| e.to_string())?;
+                pasteboard::add_rich_text(rich)?;
+            }
+            Self::Files(paths) => write_files(app, paths)?,
+            Self::Image { rgba, width, height } => {
+                let image = tauri::image::Image::new(rgba, *width, *height);
+                clipboard.write_image(&image).map_err(|e| e.to_string())?;
+            }
+        }
+        Ok(())
+    }
+}
 
-            *monitor.last_hash.lock().unwrap() = Some(hash);
-            let _ = app.emit("clipboard-changed", &item);
+/// Tag items synced from another device with `origin: "handoff"`, and
+/// password manager copies with `concealed: true`, in metadata
+fn mark_origin(item: &mut ClipboardItem, markers: &pasteboard::PasteboardMarkers) {
+    if !markers.remote && !markers.concealed {
+        return;
+    }
+    let metadata = item.metadata.get_or_insert_with(|| serde_json::json!({}));
+    if let Some(fields) = metadata.as_object_mut() {
+        if markers.remote {
+            fields.insert("origin".to_string(), serde_json::json!("handoff"));
+        }
+        // See `ClipboardItem::is_concealed`
+        if markers.concealed {
+            fields.insert("concealed".to_string(), serde_json::json!(true));
+        }
+    }
+}
 
-            return Ok(Some(item));
+/// Delete an item once its expiry passes, for items that shouldn't linger
+/// until the next startup cleanup
+fn schedule_expiry<R: Runtime>(app: &AppHandle<R>, after_secs: u64) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_secs(after_secs)).await;
+        if let Some(db) = app.try_state::<Database>() {
+            if let Err(e) = db.cleanup_expired() {
+                log::warn!("Failed to clean up expired items: {}", e);
+            }
         }
+        let _ = app.emit("clipboard-changed", ());
+    });
+}
+
+/// Queue a freshly captured item for storage. Bursts of captures (automations
+/// copying many values per second) are coalesced and written in a single
+/// transaction shortly after the first one arrives; if the same content is
+/// already in history, that row is moved back to the top instead of inserting
+/// a duplicate.
+pub(crate) fn record_capture<R: Runtime>(
+    app: &AppHandle<R>,
+    monitor: &ClipboardMonitor,
+    item: ClipboardItem,
+) -> Result<(), String> {
+    let item = prepare_capture(app, item);
+    *monitor.last_hash.lock().unwrap() = Some(item.hash.clone());
+    monitor.pending.lock().unwrap().push(item);
+
+    if !monitor.flush_scheduled.swap(true, Ordering::SeqCst) {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(tokio::time::Duration::from_millis(CAPTURE_BATCH_WINDOW_MS)).await;
+            if let Err(e) = flush_captures(&app, None) {
+                log::warn!("Failed to store clipboard captures: {}", e);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Store a captured item right away, after any captures still queued, for
+/// callers that need the row it became. A re-copy is promoted rather than
+/// inserted, so its id is the existing row's, not the one it was built with.
+pub(crate) fn store_capture<R: Runtime>(
+    app: &AppHandle<R>,
+    monitor: &ClipboardMonitor,
+    item: ClipboardItem,
+) -> Result<ClipboardItem, String> {
+    let item = prepare_capture(app, item);
+    let hash = item.hash.clone();
+    *monitor.last_hash.lock().unwrap() = Some(hash.clone());
+
+    flush_captures(app, Some(item))?
+        .into_iter()
+        .rfind(|stored| stored.hash == hash)
+        .ok_or_else(|| "Capture was not stored".to_string())
+}
+
+/// Write captures still waiting for their batch, so quitting right after a
+/// copy doesn't lose it
+pub(crate) fn flush_pending_captures<R: Runtime>(app: &AppHandle<R>) {
+    if app.try_state::<ClipboardMonitor>().is_none() {
+        return;
+    }
+    if let Err(e) = flush_captures(app, None) {
+        log::warn!("Failed to store clipboard captures: {}", e);
     }
+}
 
-    Ok(None)
+/// Fill in what capture derives rather than reads: the auto title and the
+/// source app
+fn prepare_capture<R: Runtime>(app: &AppHandle<R>, mut item: ClipboardItem) -> ClipboardItem {
+    let auto_titles = app
+        .try_state::<SettingsManager>()
+        .is_some_and(|s| s.get().auto_titles);
+    if auto_titles && item.title.is_none() && item.content_type != "image" {
+        item.title = titles::summarizer().title(&item.content);
+    }
+    if item.source_app.is_none() {
+        item.source_app = exclusions::get_frontmost_app();
+    }
+    item
 }
 
-fn compute_hash(content: &str) -> String {
+/// Append mode: grow the current target item with the copied text, or start
+/// a new target. Written immediately rather than batched, since the next copy
+/// needs to know which row it extends.
+fn append_capture<R: Runtime>(
+    app: &AppHandle<R>,
+    monitor: &ClipboardMonitor,
+    separator: &str,
+    text: String,
+    hash: String,
+) -> Result<(), String> {
+    let db = app.state::<Database>();
+    *monitor.last_hash.lock().unwrap() = Some(hash.clone());
+
+    let mut target = monitor.append_target.lock().unwrap();
+    let existing = match target.as_deref() {
+        Some(id) => db.get_item(id).map_err(|e| e.to_string())?,
+        None => None,
+    };
+
+    let stored = match existing {
+        Some(mut item) => {
+            item.content = format!("{}{}{}", item.content, separator, text);
+            item.preview = create_text_preview(&item.content);
+            item.hash = compute_hash(&item.content);
+            item.refresh_stats();
+            db.update_item_content(&item)
+                .map_err(|e| e.to_string())?;
+            item
+        }
+        None => {
+            let preview = create_text_preview(&text);
+            let item = ClipboardItem::new("text".to_string(), text, preview, hash);
+            let stored = db
+                .store_captures(std::slice::from_ref(&item))
+                .map_err(|e| e.to_string())?;
+            stored.into_iter().next().unwrap_or(item)
+        }
+    };
+
+    *target = Some(stored.id.clone());
+    let _ = app.emit("clipboard-changed", &stored);
+    Ok(())
+}
+
+/// Flip append mode and tell the frontend
+pub(crate) fn toggle_append_mode<R: Runtime>(app: &AppHandle<R>) -> bool {
+    let Some(monitor) = app.try_state::<ClipboardMonitor>() else {
+        return false;
+    };
+    let enabled = monitor.toggle_append_mode();
+    let _ = app.emit("append-mode-changed", enabled);
+    enabled
+}
+
+/// Write all pending captures, followed by `next` if given, and notify the
+/// frontend once. Returns the stored rows in capture order.
+fn flush_captures<R: Runtime>(
+    app: &AppHandle<R>,
+    next: Option<ClipboardItem>,
+) -> Result<Vec<ClipboardItem>, String> {
+    let monitor = app.state::<ClipboardMonitor>();
+    let db = app.state::<Database>();
+
+    let pending = {
+        let mut pending = monitor.pending.lock().unwrap();
+        monitor.flush_scheduled.store(false, Ordering::SeqCst);
+        let mut pending = std::mem::take(&mut *pending);
+        pending.extend(next);
+        pending
+    };
+
+    // Within a burst, only the latest copy of the same content matters
+    let mut batch: Vec<ClipboardItem> = Vec::with_capacity(pending.len());
+    for item in pending {
+        batch.retain(|queued| queued.hash != item.hash);
+        batch.push(item);
+    }
+    if batch.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let started = Instant::now();
+    let stored = db.store_captures(&batch).map_err(|e| e.to_string())?;
+    let settings = app
+        .try_state::<SettingsManager>()
+        .map(|manager| manager.get())
+        .unwrap_or_default();
+    if settings.history_limit > 0 {
+        db.enforce_limit(settings.history_limit, settings.archive_evicted_items)
+            .map_err(|e| e.to_string())?;
+    }
+    if settings.max_history_size_mb > 0 {
+        db.enforce_size_limit(
+            u64::from(settings.max_history_size_mb) * 1024 * 1024,
+            settings.archive_evicted_items,
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    perf::record_since(app, perf::DB_WRITE, started);
+
+    // Only newly inserted rows keep the id they were queued with; promoted
+    // re-copies were already handled the first time
+    let inserted: Vec<ClipboardItem> = stored
+        .iter()
+        .zip(&batch)
+        .filter(|(stored, queued)| stored.id == queued.id)
+        .map(|(stored, _)| stored.clone())
+        .collect();
+
+    if let Some(settings_manager) = app.try_state::<SettingsManager>() {
+        let settings = settings_manager.get();
+        if let Err(e) = daily_notes::append(&settings, &inserted) {
+            log::warn!("Failed to write daily note: {}", e);
+        }
+        if settings.enrich_urls {
+            // Concealed links may carry credentials; never send them anywhere
+            let links = inserted
+                .into_iter()
+                .filter(|i| i.content_type == "url" && !i.is_concealed());
+            for item in links {
+                enrich::spawn(app, item);
+            }
+        }
+    }
+
+    queue::enqueue(app, &stored);
+    stack::push_captures(app, &stored);
+
+    if let Some(latest) = stored.last() {
+        let _ = app.emit("clipboard-changed", latest);
+    }
+
+    Ok(stored)
+}
+
+/// Build an image item from raw RGBA pixels
+pub(crate) fn image_item(rgba: &[u8], width: u32, height: u32, hash: String) -> ClipboardItem {
+    let preview = format!("Image ({}x{})", width, height);
+    let mut item = ClipboardItem::new("image".to_string(), STANDARD.encode(rgba), preview, hash);
+    item.metadata = Some(serde_json::json!({
+        "width": width,
+        "height": height,
+        "palette": palette::extract_palette(rgba, width, height),
+    }));
+    item
+}
+
+/// Pixel size of an image item, from its metadata or (for older items) its preview
+pub(crate) fn image_dimensions(item: &ClipboardItem) -> Option<(u32, u32)> {
+    if let Some(metadata) = &item.metadata {
+        let width = metadata.get("width").and_then(|w| w.as_u64());
+        let height = metadata.get("height").and_then(|h| h.as_u64());
+        if let (Some(width), Some(height)) = (width, height) {
+            return Some((width as u32, height as u32));
+        }
+    }
+
+    // Preview format: "Image (WxH)"
+    let size = item.preview.strip_prefix("Image (")?.strip_suffix(')')?;
+    let (width, height) = size.split_once('x')?;
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+/// Add the formatted flavors captured with `item` to the text just written
+fn add_stored_rich_text(item: &ClipboardItem) {
+    let flavor = |key: &str| {
+        item.metadata
+            .as_ref()
+            .and_then(|m| m.get(key))
+            .and_then(|v| v.as_str())
+            .map(String::from)
+    };
+    let rich = RichText {
+        html: flavor("html"),
+        rtf: flavor("rtf"),
+    };
+    if rich.is_empty() {
+        return;
+    }
+    if let Err(e) = pasteboard::add_rich_text(&rich) {
+        log::warn!("Failed to write formatted flavors: {}", e);
+    }
+}
+
+/// Put an image item back on the clipboard as an image. The clipboard
+/// plugin offers it in the platform's native formats (PNG and TIFF on macOS).
+fn write_image_item<R: Runtime>(app: &AppHandle<R>, item: &ClipboardItem) -> Result<(), String> {
+    let (width, height) =
+        image_dimensions(item).ok_or_else(|| "Unknown image size".to_string())?;
+    let rgba = STANDARD.decode(&item.content).map_err(|e| e.to_string())?;
+    if rgba.len() != width as usize * height as usize * 4 {
+        return Err("Image data doesn't match its size".to_string());
+    }
+
+    let image = tauri::image::Image::new(&rgba, width, height);
+    app.clipboard()
+        .write_image(&image)
+        .map_err(|e| e.to_string())
+}
+
+/// An image item encoded as PNG (items store raw RGBA)
+pub(crate) fn image_png(item: &ClipboardItem) -> Result<Vec<u8>, String> {
+    let (width, height) =
+        image_dimensions(item).ok_or_else(|| "Unknown image size".to_string())?;
+    let rgba = STANDARD.decode(&item.content).map_err(|e| e.to_string())?;
+    let image = image::RgbaImage::from_raw(width, height, rgba)
+        .ok_or_else(|| "Image data doesn't match its size".to_string())?;
+
+    let mut png = std::io::Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, image::ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+    Ok(png.into_inner())
+}
+
+pub(crate) fn compute_hash(content: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(content.as_bytes());
     format!("{:x}", hasher.finalize())
 }
 
-fn compute_hash_bytes(content: &[u8]) -> String {
+pub(crate) fn compute_hash_bytes(content: &[u8]) -> String {
     let mut hasher = Sha256::new();
     hasher.update(content);
     format!("{:x}", hasher.finalize())
 }
 
-fn create_text_preview(text: &str) -> String {
+pub(crate) fn create_text_preview(text: &str) -> String {
     let preview: String = text
         .chars()
         .take(500)
@@ -142,7 +752,7 @@ fn create_text_preview(text: &str) -> String {
     }
 }
 
-fn detect_content_type(text: &str) -> String {
+pub(crate) fn detect_content_type(text: &str) -> String {
     let trimmed = text.trim();
 
     // Check if it's a file path (Unix or Windows)
@@ -162,6 +772,23 @@ fn detect_content_type(text: &str) -> String {
         return "url".to_string();
     }
 
+    // Check if it's a single color value (#hex, rgb(), hsl())
+    if color::parse_color(trimmed).is_some() {
+        return "color".to_string();
+    }
+
+    // Check for contact details before code, since phone numbers and
+    // addresses can trip the code heuristics
+    if contact::parse_email(trimmed).is_some() {
+        return "email".to_string();
+    }
+    if contact::parse_phone(trimmed).is_some() {
+        return "phone".to_string();
+    }
+    if contact::parse_address(trimmed).is_some() {
+        return "address".to_string();
+    }
+
     // Check if it looks like code
     if looks_like_code(trimmed) {
         return "code".to_string();
@@ -170,6 +797,85 @@ fn detect_content_type(text: &str) -> String {
     "text".to_string()
 }
 
+/// Structured metadata derived from text content at capture time
+pub(crate) fn text_metadata(content_type: &str, text: &str) -> Option<serde_json::Value> {
+    match content_type {
+        "color" => {
+            let color = color::parse_color(text)?;
+            Some(serde_json::json!({ "color": color.formats() }))
+        }
+        "file" | "files" => Some(serde_json::json!({ "files": file_entries(text) })),
+        "email" => {
+            let email = contact::parse_email(text)?;
+            Some(serde_json::json!({ "email": email }))
+        }
+        "phone" => {
+            let phone = contact::parse_phone(text)?;
+            Some(serde_json::json!({ "phone": phone }))
+        }
+        "address" => {
+            let address = contact::parse_address(text)?;
+            Some(serde_json::json!({ "address": address }))
+        }
+        _ => None,
+    }
+}
+
+/// One path from a file or multi-file item, as stored in item metadata
+#[derive(Debug, Clone, Serialize)]
+pub struct FileEntry {
+    pub path: String,
+    pub name: String,
+    pub extension: Option<String>,
+    pub exists: bool,
+    pub is_dir: bool,
+    pub size: Option<u64>,
+}
+
+/// The paths of a file item, one per line
+pub(crate) fn file_paths(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Put files on the clipboard as real file references (NSURLs / CF_HDROP),
+/// so Finder and upload fields copy the files rather than a text path. Paths
+/// that no longer exist, and platforms without file references, get text.
+fn write_files<R: Runtime>(app: &AppHandle<R>, paths: &[String]) -> Result<(), String> {
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    if paths.iter().all(|p| std::path::Path::new(p).exists()) {
+        return pasteboard::write_file_urls(paths);
+    }
+
+    app.clipboard()
+        .write_text(paths.join("\n"))
+        .map_err(|e| e.to_string())
+}
+
+pub(crate) fn file_entries(text: &str) -> Vec<FileEntry> {
+    file_paths(text)
+        .into_iter()
+        .map(|path| {
+            let p = std::path::Path::new(&path);
+            let meta = std::fs::metadata(p).ok();
+            FileEntry {
+                name: p
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.clone()),
+                extension: p.extension().map(|e| e.to_string_lossy().to_lowercase()),
+                exists: meta.is_some(),
+                is_dir: meta.as_ref().is_some_and(|m| m.is_dir()),
+                size: meta.as_ref().filter(|m| m.is_file()).map(|m| m.len()),
+                path,
+            }
+        })
+        .collect()
+}
+
 fn looks_like_code(text: &str) -> bool {
     let code_indicators = [
         "function ",
@@ -206,18 +912,206 @@ fn looks_like_code(text: &str) -> bool {
 
 // Tauri commands
 #[tauri::command]
+pub async fn set_append_mode<R: Runtime>(
+    app: AppHandle<R>,
+    monitor: tauri::State<'_, ClipboardMonitor>,
+    enabled: bool,
+) -> Result<bool, String> {
+    if monitor.is_appending() != enabled {
+        toggle_append_mode(&app);
+    }
+    Ok(enabled)
+}
+
+#[tauri::command]
+pub async fn get_append_mode(monitor: tauri::State<'_, ClipboardMonitor>) -> Result<bool, String> {
+    Ok(monitor.is_appending())
+}
+
+#[tauri::command]
+pub async fn pause_monitoring<R: Runtime>(
+    app: AppHandle<R>,
+    monitor: tauri::State<'_, ClipboardMonitor>,
+    duration_minutes: Option<u32>,
+) -> Result<MonitoringState, String> {
+    let resume_at = duration_minutes
+        .map(|minutes| Utc::now() + chrono::Duration::minutes(minutes as i64));
+    let generation = monitor.pause(resume_at);
+
+    if let Some(minutes) = duration_minutes {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(tokio::time::Duration::from_secs(minutes as u64 * 60)).await;
+
+            let monitor = app.state::<ClipboardMonitor>();
+            // Only resume if nobody paused/resumed again in the meantime
+            if monitor.pause_generation.load(Ordering::SeqCst) == generation {
+                monitor.resume();
+                let _ = app.emit("monitoring-state-changed", monitor.state());
+            }
+        });
+    }
+
+    let state = monitor.state();
+    let _ = app.emit("monitoring-state-changed", &state);
+    Ok(state)
+}
+
+#[tauri::command]
+pub async fn resume_monitoring<R: Runtime>(
+    app: AppHandle<R>,
+    monitor: tauri::State<'_, ClipboardMonitor>,
+) -> Result<MonitoringState, String> {
+    monitor.resume();
+
+    let state = monitor.state();
+    let _ = app.emit("monitoring-state-changed", &state);
+    Ok(state)
+}
+
+#[tauri::command]
+pub async fn get_monitoring_state(
+    monitor: tauri::State<'_, ClipboardMonitor>,
+) -> Result<MonitoringState, String> {
+    Ok(monitor.state())
+}
+
+/// The filter for a history query: search box operators plus the explicit
+/// command arguments
+fn history_filter(
+    search: Option<&str>,
+    collection_id: Option<String>,
+    tag_ids: Option<Vec<String>>,
+    content_types: Option<Vec<String>>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    options: SearchOptions,
+) -> ItemFilter {
+    let mut filter = search.map(search::parse).unwrap_or_default();
+    filter.collection_id = collection_id;
+    filter.tag_ids = tag_ids.unwrap_or_default();
+    filter.tab_types = content_types
+        .unwrap_or_default()
+        .iter()
+        .flat_map(|t| search::content_types(t))
+        .collect();
+    // Explicit bounds and `after:`/`before:` operators both apply
+    filter.after = filter.after.max(from);
+    filter.before = match (filter.before, to) {
+        (Some(before), Some(to)) => Some(before.min(to)),
+        (before, to) => before.or(to),
+    };
+    filter.case_sensitive = options.case_sensitive;
+    filter.whole_word = options.whole_word;
+    filter
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn get_clipboard_items(
     db: tauri::State<'_, Database>,
+    perf: tauri::State<'_, PerfMonitor>,
     limit: u32,
-    offset: u32,
+    cursor: Option<ItemCursor>,
     search: Option<String>,
     collection_id: Option<String>,
-) -> Result<Vec<ClipboardItem>, String> {
-    db.get_items(
-        limit,
-        offset,
+    sort_by: Option<ItemSort>,
+    include_archive: Option<bool>,
+    options: Option<SearchOptions>,
+    tag_ids: Option<Vec<String>>,
+    content_types: Option<Vec<String>>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<ItemPage<ClipboardItem>, String> {
+    let filter = history_filter(
         search.as_deref(),
-        collection_id.as_deref(),
+        collection_id,
+        tag_ids,
+        content_types,
+        from,
+        to,
+        options.unwrap_or_default(),
+    );
+
+    let started = Instant::now();
+    let page = db
+        .get_items(
+            limit,
+            cursor.as_ref(),
+            &filter,
+            sort_by.unwrap_or_default(),
+            include_archive.unwrap_or(false),
+        )
+        .map_err(|e| e.to_string());
+    perf.record(perf::QUERY, started.elapsed());
+    page
+}
+
+/// History page without item content; pair with `get_item_content`
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_items_meta(
+    db: tauri::State<'_, Database>,
+    perf: tauri::State<'_, PerfMonitor>,
+    limit: u32,
+    cursor: Option<ItemCursor>,
+    search: Option<String>,
+    collection_id: Option<String>,
+    sort_by: Option<ItemSort>,
+    include_archive: Option<bool>,
+    options: Option<SearchOptions>,
+    tag_ids: Option<Vec<String>>,
+    content_types: Option<Vec<String>>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<ItemPage<ItemMeta>, String> {
+    let filter = history_filter(
+        search.as_deref(),
+        collection_id,
+        tag_ids,
+        content_types,
+        from,
+        to,
+        options.unwrap_or_default(),
+    );
+
+    let started = Instant::now();
+    let page = db
+        .get_items_meta(
+            limit,
+            cursor.as_ref(),
+            &filter,
+            sort_by.unwrap_or_default(),
+            include_archive.unwrap_or(false),
+        )
+        .map_err(|e| e.to_string());
+    perf.record(perf::QUERY, started.elapsed());
+    page
+}
+
+#[tauri::command]
+pub async fn get_item_content(
+    db: tauri::State<'_, Database>,
+    id: String,
+) -> Result<String, String> {
+    db.get_item_content(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())
+}
+
+#[tauri::command]
+pub async fn get_items_by_size(
+    db: tauri::State<'_, Database>,
+    min_bytes: Option<i64>,
+    max_bytes: Option<i64>,
+    largest_first: Option<bool>,
+    limit: Option<u32>,
+) -> Result<Vec<ClipboardItem>, String> {
+    db.get_items_by_size(
+        min_bytes,
+        max_bytes,
+        largest_first.unwrap_or(true),
+        limit.unwrap_or(50),
     )
     .map_err(|e| e.to_string())
 }
@@ -238,13 +1132,137 @@ pub async fn delete_clipboard_item(
 }
 
 #[tauri::command]
-pub async fn pin_item(db: tauri::State<'_, Database>, id: String) -> Result<(), String> {
-    db.pin_item(&id).map_err(|e| e.to_string())
+pub async fn restore_item(
+    db: tauri::State<'_, Database>,
+    id: String,
+) -> Result<ClipboardItem, String> {
+    db.restore_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item is not in the trash".to_string())
+}
+
+/// Bring an archived item back into history, e.g. before pasting it
+#[tauri::command]
+pub async fn unarchive_item(
+    db: tauri::State<'_, Database>,
+    id: String,
+) -> Result<ClipboardItem, String> {
+    db.unarchive_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Archived item not found".to_string())
+}
+
+#[tauri::command]
+pub async fn clear_archive(db: tauri::State<'_, Database>) -> Result<u32, String> {
+    db.purge_archive(None).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_trash(db: tauri::State<'_, Database>) -> Result<Vec<ClipboardItem>, String> {
+    db.get_trash().map_err(|e| e.to_string())
+}
+
+/// Permanently delete everything in the trash, returning how many items went
+#[tauri::command]
+pub async fn empty_trash(db: tauri::State<'_, Database>) -> Result<u32, String> {
+    db.purge_trash(None).map_err(|e| e.to_string())
+}
+
+/// Purge items that have been in the trash longer than the retention setting
+pub fn purge_old_trash(db: &Database, settings: &Settings) {
+    if settings.trash_retention_days == 0 {
+        return;
+    }
+    let cutoff = Utc::now() - chrono::Duration::days(settings.trash_retention_days as i64);
+    match db.purge_trash(Some(cutoff)) {
+        Ok(0) => {}
+        Ok(n) => log::info!("Purged {} items from the trash", n),
+        Err(e) => log::warn!("Failed to purge trash: {}", e),
+    }
+}
+
+/// Evict items older than the `max_age_days` setting
+pub fn evict_aged_items(db: &Database, settings: &Settings) {
+    if settings.max_age_days == 0 {
+        return;
+    }
+    let cutoff = Utc::now() - chrono::Duration::days(settings.max_age_days as i64);
+    match db.enforce_max_age(cutoff, settings.archive_evicted_items) {
+        Ok(0) => {}
+        Ok(n) => log::info!("Evicted {} items older than {} days", n, settings.max_age_days),
+        Err(e) => log::warn!("Failed to evict old items: {}", e),
+    }
+}
+
+#[tauri::command]
+pub async fn pin_item<R: Runtime>(
+    app: AppHandle<R>,
+    db: tauri::State<'_, Database>,
+    id: String,
+) -> Result<(), String> {
+    db.pin_item(&id).map_err(|e| e.to_string())?;
+    crate::pinned_board::notify(&app);
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn unpin_item(db: tauri::State<'_, Database>, id: String) -> Result<(), String> {
-    db.unpin_item(&id).map_err(|e| e.to_string())
+pub async fn unpin_item<R: Runtime>(
+    app: AppHandle<R>,
+    db: tauri::State<'_, Database>,
+    id: String,
+) -> Result<(), String> {
+    db.unpin_item(&id).map_err(|e| e.to_string())?;
+    crate::pinned_board::notify(&app);
+    Ok(())
+}
+
+/// Trash several items at once, returning how many were trashed
+#[tauri::command]
+pub async fn delete_items(
+    db: tauri::State<'_, Database>,
+    ids: Vec<String>,
+) -> Result<u32, String> {
+    db.delete_items(&ids).map_err(|e| e.to_string())
+}
+
+/// Pin or unpin several items at once, returning how many changed
+#[tauri::command]
+pub async fn pin_items<R: Runtime>(
+    app: AppHandle<R>,
+    db: tauri::State<'_, Database>,
+    ids: Vec<String>,
+    pinned: bool,
+) -> Result<u32, String> {
+    let changed = db.pin_items(&ids, pinned).map_err(|e| e.to_string())?;
+    crate::pinned_board::notify(&app);
+    Ok(changed)
+}
+
+/// Merge items with identical content, e.g. ones left over from before
+/// re-copies were detected
+#[tauri::command]
+pub async fn dedupe_history(db: tauri::State<'_, Database>) -> Result<DedupeReport, String> {
+    db.dedupe_history().map_err(|e| e.to_string())
+}
+
+/// Contents item `id` had before it was edited, newest first
+#[tauri::command]
+pub async fn get_item_versions(
+    db: tauri::State<'_, Database>,
+    id: String,
+) -> Result<Vec<ItemVersion>, String> {
+    db.get_item_versions(&id).map_err(|e| e.to_string())
+}
+
+/// Restore an item to an earlier version
+#[tauri::command]
+pub async fn revert_item_version(
+    db: tauri::State<'_, Database>,
+    version_id: i64,
+) -> Result<ClipboardItem, String> {
+    db.revert_item_version(version_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Version not found".to_string())
 }
 
 #[tauri::command]
@@ -264,22 +1282,28 @@ pub async fn paste_item<R: Runtime>(
         let clipboard = app.clipboard();
 
         match item.content_type.as_str() {
-            "image" => {
-                // Decode base64 and write as image
-                if let Ok(_bytes) = STANDARD.decode(&item.content) {
-                    // For now, write as text since image writing needs raw image data
-                    // TODO: Properly handle image pasting
-                    clipboard
-                        .write_text(&item.preview)
-                        .map_err(|e| e.to_string())?;
-                }
+            "image" => write_image_item(&app, &item)?,
+            "file" | "files" => write_files(&app, &file_paths(&item.content))?,
+            _ if item.is_pinned => {
+                let clipboard_text = templates::clipboard_text(&app, &item.content);
+                let text = templates::expand_builtins(&item.content, clipboard_text.as_deref());
+                clipboard.write_text(&text).map_err(|e| e.to_string())?;
             }
             _ => {
                 clipboard
                     .write_text(&item.content)
                     .map_err(|e| e.to_string())?;
+                let plain_text = app
+                    .try_state::<SettingsManager>()
+                    .is_some_and(|s| s.get().paste_plain_text);
+                if !plain_text {
+                    add_stored_rich_text(&item);
+                }
             }
         }
+
+        db.record_usage(&item.id, None).map_err(|e| e.to_string())?;
+        symbols::record_paste(&db, &item);
     }
 
     Ok(())
@@ -295,6 +1319,16 @@ pub async fn move_to_collection(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn move_items_to_collection(
+    db: tauri::State<'_, Database>,
+    ids: Vec<String>,
+    collection_id: Option<String>,
+) -> Result<u32, String> {
+    db.move_items_to_collection(&ids, collection_id.as_deref())
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn set_expiration(
     db: tauri::State<'_, Database>,
@@ -318,6 +1352,7 @@ pub async fn set_expiration(
 pub async fn do_paste_and_simulate<R: Runtime>(
     app: AppHandle<R>,
     id: String,
+    options: PasteOptions,
 ) -> Result<(), String> {
     // Exit hotkey mode immediately to prevent the modifier-release poller
     // from also trying to paste (race condition)
@@ -325,41 +1360,219 @@ pub async fn do_paste_and_simulate<R: Runtime>(
         hotkey_state.exit();
     }
 
+    // A keystroke that would be dropped must not cost the user their clipboard
+    crate::permissions::ensure_accessibility(&app)?;
+
     let item = {
         let db = app.state::<Database>();
         db.get_item(&id).map_err(|e| e.to_string())?
     };
 
-    if let Some(item) = item {
+    if let Some(mut item) = item {
         let clipboard = app.clipboard();
+        let is_text = !matches!(item.content_type.as_str(), "image" | "file" | "files");
 
-        // Write content to clipboard
-        match item.content_type.as_str() {
-            "image" => {
-                // For now, write as text (TODO: handle image properly)
-                if let Ok(_bytes) = STANDARD.decode(&item.content) {
+        // Template items are pasted with their placeholders filled in; ask
+        // the UI for the values first if any are needed
+        let is_template = item.content_type != "image"
+            && app
+                .state::<Database>()
+                .is_template_item(&item.id)
+                .map_err(|e| e.to_string())?;
+        let clipboard_text = templates::clipboard_text(&app, &item.content);
+        if is_template {
+            let variables = templates::prompted_placeholders(&item.content);
+            match &options.template_values {
+                Some(values) => {
+                    item.content =
+                        templates::resolve(&item.content, values, clipboard_text.as_deref())?
+                }
+                None if !variables.is_empty() => {
+                    let _ = app.emit(
+                        "template-values-required",
+                        TemplateValuesRequest {
+                            item_id: item.id.clone(),
+                            variables,
+                        },
+                    );
+                    return Ok(());
+                }
+                None => {
+                    item.content = templates::resolve(
+                        &item.content,
+                        &HashMap::new(),
+                        clipboard_text.as_deref(),
+                    )?
+                }
+            }
+        } else if item.is_pinned && is_text {
+            // Pinned items work as snippets: built-in placeholders expand
+            item.content = templates::expand_builtins(&item.content, clipboard_text.as_deref());
+        }
+
+        if !options.transforms.is_empty() && is_text {
+            item.content = transforms::apply_all(&item.content, &options.transforms)?;
+        }
+
+        // Resolve per-app behavior before hiding, while the target is still known
+        let settings = app
+            .try_state::<SettingsManager>()
+            .map(|m| m.get())
+            .unwrap_or_default();
+        let target_app = paste_helper::target_for(&app, &options);
+
+        // Risky pastes wait for the UI to confirm via `confirm_paste`
+        let approved = app
+            .try_state::<PasteConfirmationState>()
+            .map_or(false, |s| s.take_approval(&item.id));
+        if !approved && item.content_type != "image" {
+            let warnings = lint::lint_paste(&settings, &item.content, target_app.as_deref());
+            if !warnings.is_empty() {
+                let _ = app.emit(
+                    "paste-confirmation-required",
+                    PasteConfirmationRequest {
+                        item_id: item.id.clone(),
+                        target_app,
+                        warnings,
+                    },
+                );
+                return Ok(());
+            }
+        }
+
+        let paste_override = paste_helper::override_for(&settings, target_app.as_deref());
+        let mut text = String::new();
+
+        // Type-out mode never touches the clipboard, so has nothing to restore
+        let snapshot = if settings.transient_paste && !options.type_out {
+            ClipboardSnapshot::take(&app)
+        } else {
+            None
+        };
+
+        // File items paste the whole set, or a single entry when requested
+        let file_paths = match item.content_type.as_str() {
+            "file" | "files" => {
+                let paths = file_paths(&item.content);
+                match options.file_index {
+                    Some(i) => vec![paths
+                        .get(i)
+                        .cloned()
+                        .ok_or_else(|| format!("No file at index {}", i))?],
+                    None => paths,
+                }
+            }
+            _ => Vec::new(),
+        };
+
+        // Write content to clipboard (type-out mode leaves the clipboard untouched)
+        let pasted: Result<(), String> = async {
+            let write_started = Instant::now();
+            match item.content_type.as_str() {
+                _ if options.type_out && !file_paths.is_empty() => {
+                    text = file_paths.join("\n");
+                }
+                _ if options.type_out && item.content_type != "image" => {
+                    text = paste_helper::prepare_text(&item.content, &paste_override);
+                }
+                "file" | "files" => {
+                    // Text is kept for bracketed paste into terminals
+                    text = file_paths.join("\n");
+                    write_files(&app, &file_paths)?;
+                }
+                "image" => write_image_item(&app, &item)?,
+                _ => {
+                    text = paste_helper::prepare_text(&item.content, &paste_override);
                     clipboard
-                        .write_text(&item.preview)
+                        .write_text(&text)
                         .map_err(|e| e.to_string())?;
+
+                    // The captured formatting only matches if the text went out
+                    // unchanged (no template, transform or trimming)
+                    if !paste_helper::plain_text_for(&settings, &options, target_app.as_deref())
+                        && compute_hash(&text) == item.hash
+                    {
+                        add_stored_rich_text(&item);
+                    }
                 }
             }
-            _ => {
-                clipboard
-                    .write_text(&item.content)
-                    .map_err(|e| e.to_string())?;
+
+            perf::record_since(&app, perf::PASTE_WRITE, write_started);
+
+            // Feeds context-aware suggestions and the symbol picker
+            let db = app.state::<Database>();
+            if let Err(e) = db.record_usage(&item.id, target_app.as_deref()) {
+                log::warn!("Failed to record usage for {}: {}", item.id, e);
             }
+            symbols::record_paste(&db, &item);
+
+            send_paste(&app, text, paste_override, &options).await
         }
+        .await;
+        restore_snapshot(&app, snapshot, pasted.is_ok()).await;
+        pasted?;
+    }
+
+    Ok(())
+}
+
+/// Hand focus back to the target app and send the paste: the keystroke for
+/// what's on the clipboard, or `text` typed out
+async fn send_paste<R: Runtime>(
+    app: &AppHandle<R>,
+    text: String,
+    paste_override: PasteOverride,
+    options: &PasteOptions,
+) -> Result<(), String> {
+    // The palette never takes focus, so the target is whatever is frontmost
+    let palette = crate::window::is_palette_mode(app);
+    if palette {
+        paste_helper::capture_target(app);
+    }
+
+    // Hiding the panel hands the target over, so look it up first
+    let target = paste_helper::target_for(app, options);
 
+    if options.keep_open || palette {
+        // Hand focus back to the previous app but leave the panel up
+        crate::window::focus_previous_app(app);
+    } else {
         // Hide window (this also restores focus to the previous app)
         crate::window::hide_window(app.clone()).await?;
+    }
+    if let Some(chosen) = &options.target_app {
+        paste_helper::activate_app(chosen)?;
+    }
+
+    // Wait for focus to fully return to previous app
+    let settings = app
+        .try_state::<SettingsManager>()
+        .map(|s| s.get())
+        .unwrap_or_default();
+    paste_helper::wait_for_target(&settings, target.as_deref()).await?;
 
-        // Wait for focus to fully return to previous app
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    // Simulate the paste on main thread
+    let type_out = options.type_out;
+    let app_handle = app.clone();
+    app.run_on_main_thread(move || {
+        let started = Instant::now();
+        let result = if type_out && !text.is_empty() {
+            keyboard::type_text(&text)
+        } else {
+            paste_helper::simulate_paste(&text, &paste_override)
+        };
+        perf::record_since(&app_handle, perf::PASTE_SIMULATE, started);
+        if let Err(e) = result {
+            log::warn!("Failed to simulate paste: {}", e);
+        }
+    })
+    .map_err(|e| e.to_string())?;
 
-        // Simulate Cmd+V on main thread
+    if options.submit.unwrap_or(settings.paste_and_submit) {
+        tokio::time::sleep(tokio::time::Duration::from_millis(SUBMIT_DELAY_MS)).await;
         app.run_on_main_thread(|| {
-            if let Err(e) = keyboard::simulate_cmd_v() {
-                log::warn!("Failed to simulate Cmd+V: {}", e);
+            if let Err(e) = keyboard::simulate_keystroke(keyboard::Keystroke::submit()) {
+                log::warn!("Failed to submit after paste: {}", e);
             }
         })
         .map_err(|e| e.to_string())?;
@@ -368,11 +1581,237 @@ pub async fn do_paste_and_simulate<R: Runtime>(
     Ok(())
 }
 
+/// Put a transient paste's `snapshot` back on the clipboard, whether or not
+/// the paste went out. After a paste that did, the target gets time to read
+/// the pasted contents first.
+async fn restore_snapshot<R: Runtime>(
+    app: &AppHandle<R>,
+    snapshot: Option<ClipboardSnapshot>,
+    pasted: bool,
+) {
+    let Some(snapshot) = snapshot else {
+        return;
+    };
+    if pasted {
+        let delay = tokio::time::Duration::from_millis(TRANSIENT_RESTORE_DELAY_MS);
+        tokio::time::sleep(delay).await;
+    }
+    // Marked before writing, so a poll in between can't capture it again
+    if let Some(monitor) = app.try_state::<ClipboardMonitor>() {
+        monitor.mark_seen(snapshot.hash());
+    }
+    if let Err(e) = snapshot.restore(app) {
+        log::warn!("Failed to restore clipboard after paste: {}", e);
+    }
+}
+
+/// Join several items with `separator` and paste them as one text. Images
+/// are skipped; file items contribute their paths.
+#[tauri::command]
+pub async fn paste_items<R: Runtime>(
+    app: AppHandle<R>,
+    ids: Vec<String>,
+    separator: String,
+    options: Option<PasteOptions>,
+) -> Result<(), String> {
+    let options = options.unwrap_or_default();
+    if let Some(hotkey_state) = app.try_state::<HotkeyModeState>() {
+        hotkey_state.exit();
+    }
+    crate::permissions::ensure_accessibility(&app)?;
+
+    let db = app.state::<Database>();
+    let mut items = Vec::with_capacity(ids.len());
+    for id in &ids {
+        if let Some(item) = db.get_item(id).map_err(|e| e.to_string())? {
+            if item.content_type != "image" {
+                items.push(item);
+            }
+        }
+    }
+    if items.is_empty() {
+        return Err("Nothing to paste".to_string());
+    }
+
+    let joined = items
+        .iter()
+        .map(|item| match item.content_type.as_str() {
+            "file" | "files" => file_paths(&item.content).join(&separator),
+            _ => item.content.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(&separator);
+
+    paste_text(&app, &joined, &items, &options).await
+}
+
+/// Paste `text` made from `items` as plain text, counting it as a use of
+/// each of them
+async fn paste_text<R: Runtime>(
+    app: &AppHandle<R>,
+    text: &str,
+    items: &[ClipboardItem],
+    options: &PasteOptions,
+) -> Result<(), String> {
+    let settings = app
+        .try_state::<SettingsManager>()
+        .map(|m| m.get())
+        .unwrap_or_default();
+    let target_app = paste_helper::target_for(app, options);
+    let paste_override = paste_helper::override_for(&settings, target_app.as_deref());
+    let text = paste_helper::prepare_text(text, &paste_override);
+
+    let snapshot = if settings.transient_paste && !options.type_out {
+        ClipboardSnapshot::take(app)
+    } else {
+        None
+    };
+    let pasted: Result<(), String> = async {
+        if !options.type_out {
+            let write_started = Instant::now();
+            app.clipboard()
+                .write_text(&text)
+                .map_err(|e| e.to_string())?;
+            perf::record_since(app, perf::PASTE_WRITE, write_started);
+        }
+
+        let db = app.state::<Database>();
+        for item in items {
+            if let Err(e) = db.record_usage(&item.id, target_app.as_deref()) {
+                log::warn!("Failed to record usage for {}: {}", item.id, e);
+            }
+        }
+
+        send_paste(app, text, paste_override, options).await
+    }
+    .await;
+    restore_snapshot(app, snapshot, pasted.is_ok()).await;
+    pasted
+}
+
+/// Paste an item the user confirmed after `paste-confirmation-required`
+#[tauri::command]
+pub async fn confirm_paste<R: Runtime>(
+    app: AppHandle<R>,
+    confirmation_state: tauri::State<'_, PasteConfirmationState>,
+    id: String,
+    options: Option<PasteOptions>,
+) -> Result<(), String> {
+    confirmation_state.approve(id.clone());
+    do_paste_and_simulate(app, id, options.unwrap_or_default()).await
+}
+
 #[tauri::command]
 pub async fn paste_and_simulate<R: Runtime>(
     app: AppHandle<R>,
     _db: tauri::State<'_, Database>,
     id: String,
+    options: Option<PasteOptions>,
+) -> Result<(), String> {
+    do_paste_and_simulate(app, id, options.unwrap_or_default()).await
+}
+
+/// Paste an item with its formatting stripped, whatever the default
+#[tauri::command]
+pub async fn paste_item_plain<R: Runtime>(app: AppHandle<R>, id: String) -> Result<(), String> {
+    let options = PasteOptions {
+        plain_text: Some(true),
+        ..Default::default()
+    };
+    do_paste_and_simulate(app, id, options).await
+}
+
+/// What an item can be pasted as: derived formats like a Markdown link or
+/// a data URI, then the text transforms
+#[tauri::command]
+pub async fn get_paste_variants(
+    db: tauri::State<'_, Database>,
+    id: String,
+) -> Result<Vec<transforms::TransformInfo>, String> {
+    let item = db
+        .get_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+    Ok(transforms::variants(&item))
+}
+
+/// Paste an item as one of its `get_paste_variants`
+#[tauri::command]
+pub async fn paste_variant<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+    variant: String,
+) -> Result<(), String> {
+    if let Some(hotkey_state) = app.try_state::<HotkeyModeState>() {
+        hotkey_state.exit();
+    }
+    crate::permissions::ensure_accessibility(&app)?;
+
+    let item = app
+        .state::<Database>()
+        .get_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+
+    match transforms::derive(&item, &variant)? {
+        Some(text) => paste_text(&app, &text, &[item], &PasteOptions::default()).await,
+        // Anything that isn't a derived format is a text transform
+        None => paste_item_transformed(app, id, vec![variant]).await,
+    }
+}
+
+/// Paste an item and press Return, e.g. to send it in a chat app
+#[tauri::command]
+pub async fn paste_item_and_submit<R: Runtime>(app: AppHandle<R>, id: String) -> Result<(), String> {
+    let options = PasteOptions {
+        submit: Some(true),
+        ..Default::default()
+    };
+    do_paste_and_simulate(app, id, options).await
+}
+
+/// Paste an item into the app with `bundle_id`, bringing it to the front
+/// first, whichever app was focused before
+#[tauri::command]
+pub async fn paste_item_to_app<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+    bundle_id: String,
+) -> Result<(), String> {
+    let options = PasteOptions {
+        target_app: Some(bundle_id),
+        ..Default::default()
+    };
+    do_paste_and_simulate(app, id, options).await
+}
+
+/// Paste an item with `transforms` applied to its text, e.g. from a
+/// "Paste as…" menu
+#[tauri::command]
+pub async fn paste_item_transformed<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+    transforms: Vec<String>,
 ) -> Result<(), String> {
-    do_paste_and_simulate(app, id).await
+    let options = PasteOptions {
+        transforms,
+        ..Default::default()
+    };
+    do_paste_and_simulate(app, id, options).await
+}
+
+/// Paste the most recent item as plain text, for the plain paste hotkey
+pub async fn paste_latest_plain<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    // The latest copy, even when pinned items sort ahead of it in history
+    let latest = app
+        .state::<Database>()
+        .recent_item_ids(1)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .next();
+    paste_helper::capture_target(&app);
+    match latest {
+        Some(id) => paste_item_plain(app, id).await,
+        None => Ok(()),
+    }
 }
@@ -1,4 +1,4 @@
-use crate::database::{Collection, Database, Tag};
+use crate::database::{Collection, Database, RelatedItem, Tag};
 use chrono::Utc;
 use uuid::Uuid;
 
@@ -13,6 +13,8 @@ pub async fn create_collection(
         name,
         color,
         created_at: Utc::now(),
+        protected: false,
+        is_template: false,
     };
 
     db.create_collection(&collection).map_err(|e| e.to_string())?;
@@ -41,6 +43,29 @@ pub async fn update_collection(
         .map_err(|e| e.to_string())
 }
 
+/// Protect (or unprotect) a collection's items from clears, expiry and the
+/// history limit
+#[tauri::command]
+pub async fn set_collection_protected(
+    db: tauri::State<'_, Database>,
+    id: String,
+    protected: bool,
+) -> Result<(), String> {
+    db.set_collection_protected(&id, protected)
+        .map_err(|e| e.to_string())
+}
+
+/// Mark a collection as holding `{placeholder}` templates
+#[tauri::command]
+pub async fn set_collection_template(
+    db: tauri::State<'_, Database>,
+    id: String,
+    is_template: bool,
+) -> Result<(), String> {
+    db.set_collection_template(&id, is_template)
+        .map_err(|e| e.to_string())
+}
+
 // Tag commands
 #[tauri::command]
 pub async fn create_tag(db: tauri::State<'_, Database>, name: String) -> Result<Tag, String> {
@@ -69,6 +94,16 @@ pub async fn add_tag_to_item(
         .map_err(|e| e.to_string())
 }
 
+/// Tag several items at once, returning how many weren't tagged already
+#[tauri::command]
+pub async fn tag_items(
+    db: tauri::State<'_, Database>,
+    ids: Vec<String>,
+    tag_id: String,
+) -> Result<u32, String> {
+    db.tag_items(&ids, &tag_id).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn remove_tag_from_item(
     db: tauri::State<'_, Database>,
@@ -86,3 +121,43 @@ pub async fn get_item_tags(
 ) -> Result<Vec<Tag>, String> {
     db.get_item_tags(&item_id).map_err(|e| e.to_string())
 }
+
+/// Link `source_id` to `target_id`, e.g. a screenshot to its OCR text with
+/// relation "ocr", so derived items can be found from either side
+#[tauri::command]
+pub async fn link_items(
+    db: tauri::State<'_, Database>,
+    source_id: String,
+    target_id: String,
+    relation: String,
+) -> Result<(), String> {
+    if source_id == target_id {
+        return Err("An item can't be linked to itself".to_string());
+    }
+    let relation = relation.trim();
+    if relation.is_empty() {
+        return Err("Link relation can't be empty".to_string());
+    }
+
+    db.link_items(&source_id, &target_id, relation)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn unlink_items(
+    db: tauri::State<'_, Database>,
+    source_id: String,
+    target_id: String,
+    relation: Option<String>,
+) -> Result<(), String> {
+    db.unlink_items(&source_id, &target_id, relation.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_related_items(
+    db: tauri::State<'_, Database>,
+    id: String,
+) -> Result<Vec<RelatedItem>, String> {
+    db.get_related_items(&id).map_err(|e| e.to_string())
+}
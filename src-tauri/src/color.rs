@@ -0,0 +1,258 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::clipboard::ClipboardMonitor;
+use crate::database::{Database, Provenance};
+
+/// A parsed color with channels in 0..=255 and alpha in 0.0..=1.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: f32,
+}
+
+/// The same color in every format the UI offers for copying
+#[derive(Debug, Clone, Serialize)]
+pub struct ColorFormats {
+    pub hex: String,
+    pub rgb: String,
+    pub hsl: String,
+    pub alpha: f32,
+}
+
+impl Color {
+    pub fn to_hex(&self) -> String {
+        if self.a < 1.0 {
+            format!(
+                "#{:02x}{:02x}{:02x}{:02x}",
+                self.r,
+                self.g,
+                self.b,
+                (self.a * 255.0).round() as u8
+            )
+        } else {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        }
+    }
+
+    pub fn to_rgb(&self) -> String {
+        if self.a < 1.0 {
+            format!("rgba({}, {}, {}, {})", self.r, self.g, self.b, round2(self.a))
+        } else {
+            format!("rgb({}, {}, {})", self.r, self.g, self.b)
+        }
+    }
+
+    pub fn to_hsl(&self) -> String {
+        let (h, s, l) = rgb_to_hsl(self.r, self.g, self.b);
+        if self.a < 1.0 {
+            format!("hsla({}, {}%, {}%, {})", h, s, l, round2(self.a))
+        } else {
+            format!("hsl({}, {}%, {}%)", h, s, l)
+        }
+    }
+
+    pub fn formats(&self) -> ColorFormats {
+        ColorFormats {
+            hex: self.to_hex(),
+            rgb: self.to_rgb(),
+            hsl: self.to_hsl(),
+            alpha: self.a,
+        }
+    }
+}
+
+fn round2(value: f32) -> f32 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Parse `#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`, `rgb()/rgba()` and `hsl()/hsla()`
+pub fn parse_color(text: &str) -> Option<Color> {
+    let text = text.trim().to_ascii_lowercase();
+
+    if let Some(hex) = text.strip_prefix('#') {
+        return parse_hex(hex);
+    }
+
+    let (name, args) = text.strip_suffix(')')?.split_once('(')?;
+    let args: Vec<&str> = args
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|a| !a.is_empty())
+        .collect();
+
+    if args.len() < 3 || args.len() > 4 {
+        return None;
+    }
+    let alpha = match args.get(3) {
+        Some(a) => parse_alpha(a)?,
+        None => 1.0,
+    };
+
+    match name.trim() {
+        "rgb" | "rgba" => Some(Color {
+            r: parse_channel(args[0])?,
+            g: parse_channel(args[1])?,
+            b: parse_channel(args[2])?,
+            a: alpha,
+        }),
+        "hsl" | "hsla" => {
+            let h: f32 = args[0].trim_end_matches("deg").parse().ok()?;
+            let s = parse_percent(args[1])?;
+            let l = parse_percent(args[2])?;
+            let (r, g, b) = hsl_to_rgb(h, s, l);
+            Some(Color { r, g, b, a: alpha })
+        }
+        _ => None,
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let expand = |c: char| c.to_digit(16).map(|d| (d * 17) as u8);
+    let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+
+    match hex.len() {
+        3 | 4 => {
+            let chars: Vec<char> = hex.chars().collect();
+            Some(Color {
+                r: expand(chars[0])?,
+                g: expand(chars[1])?,
+                b: expand(chars[2])?,
+                a: match chars.get(3) {
+                    Some(&c) => expand(c)? as f32 / 255.0,
+                    None => 1.0,
+                },
+            })
+        }
+        6 | 8 => Some(Color {
+            r: pair(0)?,
+            g: pair(2)?,
+            b: pair(4)?,
+            a: if hex.len() == 8 { pair(6)? as f32 / 255.0 } else { 1.0 },
+        }),
+        _ => None,
+    }
+}
+
+fn parse_channel(value: &str) -> Option<u8> {
+    if let Some(percent) = value.strip_suffix('%') {
+        let p: f32 = percent.parse().ok()?;
+        return (0.0..=100.0)
+            .contains(&p)
+            .then(|| (p * 2.55).round() as u8);
+    }
+    let v: f32 = value.parse().ok()?;
+    (0.0..=255.0).contains(&v).then(|| v.round() as u8)
+}
+
+fn parse_percent(value: &str) -> Option<f32> {
+    let p: f32 = value.strip_suffix('%')?.parse().ok()?;
+    (0.0..=100.0).contains(&p).then_some(p / 100.0)
+}
+
+fn parse_alpha(value: &str) -> Option<f32> {
+    let a = match value.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok()? / 100.0,
+        None => value.parse().ok()?,
+    };
+    (0.0..=1.0).contains(&a).then_some(a)
+}
+
+/// Returns hue in degrees and saturation/lightness in whole percents
+fn rgb_to_hsl(r: u8, g: u8, b: u8) -> (u32, u32, u32) {
+    let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+
+    if d == 0.0 {
+        return (0, 0, (l * 100.0).round() as u32);
+    }
+
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        60.0 * (((g - b) / d) % 6.0)
+    } else if max == g {
+        60.0 * ((b - r) / d + 2.0)
+    } else {
+        60.0 * ((r - g) / d + 4.0)
+    };
+    let h = if h < 0.0 { h + 360.0 } else { h };
+
+    (
+        h.round() as u32 % 360,
+        (s * 100.0).round() as u32,
+        (l * 100.0).round() as u32,
+    )
+}
+
+fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (u8, u8, u8) {
+    let h = h.rem_euclid(360.0);
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let m = l - c / 2.0;
+
+    let (r, g, b) = match h as u32 / 60 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+
+    (
+        ((r + m) * 255.0).round() as u8,
+        ((g + m) * 255.0).round() as u8,
+        ((b + m) * 255.0).round() as u8,
+    )
+}
+
+/// Convert a color string to `format` ("hex", "rgb" or "hsl")
+#[tauri::command]
+pub async fn convert_color(value: String, format: String) -> Result<String, String> {
+    let color = parse_color(&value).ok_or_else(|| "Not a color value".to_string())?;
+
+    match format.as_str() {
+        "hex" => Ok(color.to_hex()),
+        "rgb" => Ok(color.to_rgb()),
+        "hsl" => Ok(color.to_hsl()),
+        _ => Err(format!("Unknown color format: {}", format)),
+    }
+}
+
+/// Copy a color item to the clipboard in another format
+#[tauri::command]
+pub async fn copy_color_as<R: Runtime>(
+    app: AppHandle<R>,
+    db: tauri::State<'_, Database>,
+    id: String,
+    format: String,
+) -> Result<String, String> {
+    let item = db
+        .get_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+
+    let converted = convert_color(item.content, format.clone()).await?;
+    app.state::<ClipboardMonitor>().expect_derived(
+        &converted,
+        Provenance {
+            parent_id: id,
+            operation: "convert_color".to_string(),
+            options: serde_json::json!({ "format": format }),
+        },
+    );
+    app.clipboard()
+        .write_text(&converted)
+        .map_err(|e| e.to_string())?;
+
+    Ok(converted)
+}
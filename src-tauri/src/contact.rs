@@ -0,0 +1,250 @@
+use serde::Serialize;
+
+/// Parts of a single email address, optionally with a display name
+#[derive(Debug, Clone, Serialize)]
+pub struct EmailParts {
+    pub address: String,
+    pub local: String,
+    pub domain: String,
+    pub name: Option<String>,
+}
+
+/// A phone number normalized to digits, keeping the pieces the UI dials with
+#[derive(Debug, Clone, Serialize)]
+pub struct PhoneParts {
+    /// Dialable form: optional leading `+` followed by digits only
+    pub number: String,
+    pub international: bool,
+    pub extension: Option<String>,
+}
+
+/// A postal address split into the lines we can reliably tell apart
+#[derive(Debug, Clone, Serialize)]
+pub struct AddressParts {
+    pub street: String,
+    pub locality: Option<String>,
+    pub postal_code: String,
+    pub country: Option<String>,
+}
+
+const STREET_SUFFIXES: &[&str] = &[
+    "street", "st", "avenue", "ave", "road", "rd", "boulevard", "blvd", "lane", "ln", "drive",
+    "dr", "way", "court", "ct", "place", "pl", "terrace", "parkway", "pkwy", "highway", "hwy",
+    "square", "sq", "circle", "cir", "strasse", "straße", "rue", "via", "close", "crescent",
+];
+
+/// Parse `a@b.com`, `mailto:a@b.com` or `Jane Doe <a@b.com>`
+pub fn parse_email(text: &str) -> Option<EmailParts> {
+    let text = text.trim();
+    if text.contains('\n') {
+        return None;
+    }
+
+    let (name, address) = match (text.rfind('<'), text.ends_with('>')) {
+        (Some(start), true) => {
+            let name = text[..start].trim().trim_matches('"').trim();
+            let name = (!name.is_empty()).then(|| name.to_string());
+            (name, &text[start + 1..text.len() - 1])
+        }
+        _ => (None, text),
+    };
+    let address = address.strip_prefix("mailto:").unwrap_or(address).trim();
+
+    let (local, domain) = address.split_once('@')?;
+    if local.is_empty() || local.len() > 64 || domain.contains('@') {
+        return None;
+    }
+    if !local
+        .chars()
+        .all(|c| c.is_alphanumeric() || "._%+-'".contains(c))
+    {
+        return None;
+    }
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2
+        || labels.iter().any(|l| {
+            l.is_empty() || l.starts_with('-') || !l.chars().all(|c| c.is_alphanumeric() || c == '-')
+        })
+    {
+        return None;
+    }
+    let tld = labels.last()?;
+    if tld.len() < 2 || !tld.chars().all(|c| c.is_alphabetic()) {
+        return None;
+    }
+
+    Some(EmailParts {
+        address: address.to_string(),
+        local: local.to_string(),
+        domain: domain.to_lowercase(),
+        name,
+    })
+}
+
+/// Parse a phone number written with the usual separators, e.g.
+/// `+1 (555) 123-4567 ext. 89`.
+pub fn parse_phone(text: &str) -> Option<PhoneParts> {
+    let text = text.trim();
+    let text = text.strip_prefix("tel:").unwrap_or(text);
+    if text.contains('\n') || !text.is_ascii() {
+        return None;
+    }
+
+    let lower = text.to_lowercase();
+    let (main, extension) = match ["extension", "ext.", "ext", "x"]
+        .iter()
+        .find_map(|marker| lower.rfind(marker).map(|i| (i, marker.len())))
+    {
+        Some((i, len)) => {
+            let ext = lower[i + len..].trim();
+            if ext.is_empty() || ext.len() > 6 || !ext.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            (&text[..i], Some(ext.to_string()))
+        }
+        None => (text, None),
+    };
+
+    let main = main.trim();
+    let international = main.starts_with('+');
+    let body = main.strip_prefix('+').unwrap_or(main);
+    if !body
+        .chars()
+        .all(|c| c.is_ascii_digit() || " -.()".contains(c))
+    {
+        return None;
+    }
+
+    // Bare digit runs and things like 3.14159 are IDs or amounts
+    let groups = body
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|g| !g.is_empty())
+        .count();
+    if !international && groups < 3 {
+        return None;
+    }
+
+    // Parentheses must be balanced and only wrap an area code
+    let open = body.matches('(').count();
+    if open != body.matches(')').count() || open > 1 {
+        return None;
+    }
+
+    let digits: String = body.chars().filter(|c| c.is_ascii_digit()).collect();
+    if !(7..=15).contains(&digits.len()) {
+        return None;
+    }
+
+    // 2024-01-15 and 15.01.2024 are dates, not numbers to dial
+    let groups: Vec<usize> = body.split(['-', '.']).map(|g| g.trim().len()).collect();
+    if !international && (groups == [4, 2, 2] || groups == [2, 2, 4]) {
+        return None;
+    }
+
+    Some(PhoneParts {
+        number: if international { format!("+{}", digits) } else { digits },
+        international,
+        extension,
+    })
+}
+
+/// Parse a postal address of two to five lines (or comma-separated parts)
+/// that starts with a numbered street and contains a postal code.
+pub fn parse_address(text: &str) -> Option<AddressParts> {
+    let text = text.trim();
+    if text.len() > 300 {
+        return None;
+    }
+
+    let parts: Vec<&str> = if text.contains('\n') {
+        text.lines().map(|l| l.trim().trim_end_matches(',')).filter(|l| !l.is_empty()).collect()
+    } else {
+        text.split(',').map(|p| p.trim()).filter(|p| !p.is_empty()).collect()
+    };
+    if !(2..=5).contains(&parts.len()) {
+        return None;
+    }
+
+    let street = parts[0];
+    if !is_street(street) {
+        return None;
+    }
+
+    let (postal_index, postal_code) = parts
+        .iter()
+        .enumerate()
+        .skip(1)
+        .find_map(|(i, part)| postal_code(part).map(|code| (i, code)))?;
+
+    let locality = parts[1..=postal_index]
+        .iter()
+        .map(|p| p.replace(&postal_code, "").trim().trim_matches(',').trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let country = parts[postal_index + 1..].last().map(|c| c.to_string());
+
+    Some(AddressParts {
+        street: street.to_string(),
+        locality: (!locality.is_empty()).then_some(locality),
+        postal_code,
+        country,
+    })
+}
+
+fn is_street(line: &str) -> bool {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    if words.len() < 2 {
+        return false;
+    }
+
+    // House number either leads ("221 Baker St") or trails ("Hauptstraße 5")
+    let is_number = |w: &str| w.chars().next().is_some_and(|c| c.is_ascii_digit()) && w.len() <= 6;
+    let numbered = is_number(words[0]) || words.last().is_some_and(|w| is_number(w));
+
+    let has_suffix = words.iter().any(|w| {
+        let w = w.trim_end_matches('.').to_lowercase();
+        STREET_SUFFIXES.contains(&w.as_str()) || w.ends_with("straße") || w.ends_with("strasse")
+    });
+
+    numbered && has_suffix
+}
+
+/// Find a postal code token: US ZIP (12345 / 12345-6789), UK-style
+/// (SW1A 1AA), Canadian (K1A 0B1) or a plain 4-6 digit code.
+fn postal_code(part: &str) -> Option<String> {
+    let words: Vec<&str> = part.split_whitespace().map(|w| w.trim_matches(',')).collect();
+
+    for (i, word) in words.iter().enumerate() {
+        let digits_only = word.chars().all(|c| c.is_ascii_digit());
+        if digits_only && (4..=6).contains(&word.len()) {
+            return Some(word.to_string());
+        }
+        if let Some((zip, plus4)) = word.split_once('-') {
+            if zip.len() == 5
+                && plus4.len() == 4
+                && zip.chars().chain(plus4.chars()).all(|c| c.is_ascii_digit())
+            {
+                return Some(word.to_string());
+            }
+        }
+
+        // Two-token alphanumeric codes, e.g. "SW1A 1AA" or "K1A 0B1"
+        if let Some(next) = words.get(i + 1) {
+            let outward = (2..=4).contains(&word.len())
+                && word.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+                && word.chars().any(|c| c.is_ascii_digit())
+                && word.chars().next().is_some_and(|c| c.is_ascii_uppercase());
+            let inward = next.len() == 3
+                && next.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+                && next.chars().next().is_some_and(|c| c.is_ascii_digit());
+            if outward && inward {
+                return Some(format!("{} {}", word, next));
+            }
+        }
+    }
+
+    None
+}
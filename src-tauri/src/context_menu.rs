@@ -0,0 +1,188 @@
+//! Native right-click menu for history items.
+//!
+//! `show_item_context_menu` pops up a real menu at the pointer, so unlike a
+//! menu drawn in the webview it isn't clipped at the panel's edges. Each
+//! entry's id carries its action and the item (`item-menu/<action>/<id>/...`),
+//! and `handle_event` runs the matching command. The list refreshes through
+//! `clipboard-changed`.
+
+use crate::clipboard;
+use crate::database::Database;
+use crate::entitlements;
+use crate::transforms;
+use tauri::menu::{
+    CheckMenuItemBuilder, Menu, MenuBuilder, MenuEvent, MenuItemBuilder, SubmenuBuilder,
+};
+use tauri::{AppHandle, Emitter, LogicalPosition, Manager, Runtime, WebviewWindow};
+
+const ID_PREFIX: &str = "item-menu";
+
+/// One of the menu's entries, parsed back out of its id
+enum Action {
+    Pin(String),
+    Unpin(String),
+    Copy(String),
+    PasteAs {
+        id: String,
+        variant: String,
+    },
+    Move {
+        id: String,
+        collection_id: Option<String>,
+    },
+    Delete(String),
+}
+
+impl Action {
+    fn menu_id(&self) -> String {
+        match self {
+            Action::Pin(id) => format!("{ID_PREFIX}/pin/{id}"),
+            Action::Unpin(id) => format!("{ID_PREFIX}/unpin/{id}"),
+            Action::Copy(id) => format!("{ID_PREFIX}/copy/{id}"),
+            Action::PasteAs { id, variant } => format!("{ID_PREFIX}/paste-as/{id}/{variant}"),
+            Action::Move { id, collection_id } => format!(
+                "{ID_PREFIX}/move/{id}/{}",
+                collection_id.as_deref().unwrap_or_default()
+            ),
+            Action::Delete(id) => format!("{ID_PREFIX}/delete/{id}"),
+        }
+    }
+
+    fn parse(menu_id: &str) -> Option<Self> {
+        let mut parts = menu_id.splitn(4, '/');
+        if parts.next()? != ID_PREFIX {
+            return None;
+        }
+        let action = parts.next()?;
+        let id = parts.next()?.to_string();
+        let arg = parts.next();
+        Some(match action {
+            "pin" => Action::Pin(id),
+            "unpin" => Action::Unpin(id),
+            "copy" => Action::Copy(id),
+            "paste-as" => Action::PasteAs {
+                id,
+                variant: arg?.to_string(),
+            },
+            "move" => Action::Move {
+                id,
+                collection_id: arg.filter(|c| !c.is_empty()).map(String::from),
+            },
+            "delete" => Action::Delete(id),
+            _ => return None,
+        })
+    }
+}
+
+fn build_menu<R: Runtime>(app: &AppHandle<R>, id: &str) -> Result<Menu<R>, String> {
+    let db = app.state::<Database>();
+    let item = db
+        .get_item(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+    let collections = db.get_collections().map_err(|e| e.to_string())?;
+    let id = item.id.clone();
+
+    let pin = if item.is_pinned {
+        MenuItemBuilder::with_id(Action::Unpin(id.clone()).menu_id(), "Unpin")
+    } else {
+        MenuItemBuilder::with_id(Action::Pin(id.clone()).menu_id(), "Pin")
+    }
+    .build(app)
+    .map_err(|e| e.to_string())?;
+
+    let mut paste_as = SubmenuBuilder::new(app, "Paste As…");
+    let variants = transforms::variants(&item);
+    for variant in &variants {
+        let action = Action::PasteAs {
+            id: id.clone(),
+            variant: variant.id.to_string(),
+        };
+        paste_as = paste_as.text(action.menu_id(), variant.name);
+    }
+    let paste_as = paste_as
+        .enabled(!variants.is_empty())
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    // The item's current collection is checked; "None" takes it out
+    let mut move_to = SubmenuBuilder::new(app, "Move to Collection");
+    let targets = std::iter::once((None, "None".to_string()))
+        .chain(collections.into_iter().map(|c| (Some(c.id), c.name)));
+    for (collection_id, name) in targets {
+        let checked = collection_id == item.collection_id;
+        let action = Action::Move {
+            id: id.clone(),
+            collection_id,
+        };
+        let entry = CheckMenuItemBuilder::with_id(action.menu_id(), name)
+            .checked(checked)
+            .build(app)
+            .map_err(|e| e.to_string())?;
+        move_to = move_to.item(&entry);
+    }
+    // Collections are Pro; the action is checked again when it runs
+    let move_to = move_to
+        .enabled(entitlements::require_command(app, "move_to_collection").is_ok())
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    MenuBuilder::new(app)
+        .item(&pin)
+        .text(Action::Copy(id.clone()).menu_id(), "Copy")
+        .item(&paste_as)
+        .item(&move_to)
+        .separator()
+        .text(Action::Delete(id).menu_id(), "Delete")
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Pop up the context menu for item `id` at (`x`, `y`), in logical pixels
+/// from the window's top-left
+#[tauri::command]
+pub async fn show_item_context_menu<R: Runtime>(
+    app: AppHandle<R>,
+    window: WebviewWindow<R>,
+    id: String,
+    x: f64,
+    y: f64,
+) -> Result<(), String> {
+    let menu = build_menu(&app, &id)?;
+    window
+        .popup_menu_at(&menu, LogicalPosition::new(x, y))
+        .map_err(|e| e.to_string())
+}
+
+async fn run<R: Runtime>(app: AppHandle<R>, action: Action) -> Result<(), String> {
+    let db = app.state::<Database>();
+    match action {
+        Action::Pin(id) => clipboard::pin_item(app.clone(), db, id).await?,
+        Action::Unpin(id) => clipboard::unpin_item(app.clone(), db, id).await?,
+        Action::Copy(id) => clipboard::paste_item(app.clone(), db, id).await?,
+        Action::PasteAs { id, variant } => {
+            return clipboard::paste_variant(app.clone(), id, variant).await;
+        }
+        Action::Move { id, collection_id } => {
+            entitlements::require_command(&app, "move_to_collection")?;
+            clipboard::move_to_collection(db, id, collection_id).await?
+        }
+        Action::Delete(id) => clipboard::delete_clipboard_item(db, id).await?,
+    }
+    let _ = app.emit("clipboard-changed", ());
+    Ok(())
+}
+
+/// Run the command behind a clicked context menu entry. Other menus' events
+/// are ignored.
+pub fn handle_event<R: Runtime>(app: &AppHandle<R>, event: MenuEvent) {
+    let Some(action) = Action::parse(event.id().as_ref()) else {
+        return;
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = run(app, action).await {
+            log::warn!("Context menu action failed: {}", e);
+        }
+    });
+}
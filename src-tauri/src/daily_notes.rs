@@ -0,0 +1,107 @@
+//! Append captured items to a per-day Markdown note (e.g. an Obsidian vault's
+//! daily notes folder), so clippings are collected without a separate watcher.
+
+use crate::database::ClipboardItem;
+use crate::settings::Settings;
+use chrono::{DateTime, Local};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Whether `item` passes the configured filter
+fn matches(settings: &Settings, item: &ClipboardItem) -> bool {
+    if item.content_type == "image" || item.expires_at.is_some() {
+        return false;
+    }
+    if !settings.daily_notes_types.is_empty()
+        && !settings.daily_notes_types.iter().any(|t| t == &item.content_type)
+    {
+        return false;
+    }
+    if let Some(app) = item.source_app.as_deref() {
+        if settings
+            .daily_notes_excluded_apps
+            .iter()
+            .any(|a| a.eq_ignore_ascii_case(app))
+        {
+            return false;
+        }
+    }
+    item.content.trim().chars().count() >= settings.daily_notes_min_length as usize
+}
+
+fn note_path(folder: &Path, at: DateTime<Local>) -> PathBuf {
+    folder.join(format!("{}.md", at.format("%Y-%m-%d")))
+}
+
+fn render(item: &ClipboardItem, at: DateTime<Local>) -> String {
+    let mut entry = format!("\n### {}", at.format("%H:%M"));
+    if let Some(app) = item.source_app.as_deref() {
+        entry.push_str(&format!(" · {}", app));
+    }
+    entry.push('\n');
+
+    let metadata = item.metadata.as_ref();
+    let page_title = metadata.and_then(|m| m["title"].as_str());
+    let page_url = metadata.and_then(|m| m["url"].as_str());
+    let content = item.content.trim_end();
+
+    match item.content_type.as_str() {
+        "url" => match page_title {
+            Some(title) => entry.push_str(&format!("[{}]({})\n", title, content)),
+            None => entry.push_str(&format!("<{}>\n", content)),
+        },
+        "code" => {
+            // Longer fence than anything inside the snippet
+            let mut fence = "```".to_string();
+            while content.contains(&fence) {
+                fence.push('`');
+            }
+            entry.push_str(&format!("{}\n{}\n{}\n", fence, content, fence));
+        }
+        _ => {
+            for line in content.lines() {
+                entry.push_str(&format!("> {}\n", line));
+            }
+            if let (Some(title), Some(url)) = (page_title, page_url) {
+                entry.push_str(&format!("\n— [{}]({})\n", title, url));
+            }
+        }
+    }
+
+    entry
+}
+
+/// Append the items that match the filter to today's note
+pub fn append(settings: &Settings, items: &[ClipboardItem]) -> Result<(), String> {
+    if !settings.daily_notes_enabled || settings.daily_notes_folder.is_empty() {
+        return Ok(());
+    }
+
+    let entries: Vec<&ClipboardItem> = items.iter().filter(|i| matches(settings, i)).collect();
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let folder = PathBuf::from(&settings.daily_notes_folder);
+    std::fs::create_dir_all(&folder).map_err(|e| e.to_string())?;
+
+    let now = Local::now();
+    let path = note_path(&folder, now);
+    let is_new = !path.exists();
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| e.to_string())?;
+
+    let mut text = String::new();
+    if is_new {
+        text.push_str(&format!("# Clippings {}\n", now.format("%Y-%m-%d")));
+    }
+    for item in entries {
+        text.push_str(&render(item, item.created_at.with_timezone(&Local)));
+    }
+
+    file.write_all(text.as_bytes()).map_err(|e| e.to_string())
+}
@@ -1,8 +1,318 @@
+use crate::search::{self, ItemFilter};
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection, Result};
+use rusqlite::functions::FunctionFlags;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use uuid::Uuid;
+
+/// Columns selected for list rows, in `row_to_item_meta` order. Everything
+/// but `content`, `metadata` and `provenance`, which can be large.
+const ITEM_META_COLUMNS: &str = "id, content_type, preview, hash, is_pinned, collection_id, created_at, expires_at, last_used_at, title, source_app, byte_size, char_count, word_count, line_count, times_pasted";
+
+/// Number of columns in `ITEM_META_COLUMNS`
+const ITEM_META_COLUMN_COUNT: usize = 16;
+
+/// Columns selected for every `ClipboardItem` query, in `row_to_item` order
+const ITEM_COLUMNS: &str = "id, content_type, content, preview, hash, is_pinned, collection_id, created_at, expires_at, last_used_at, metadata, title, source_app, byte_size, char_count, word_count, line_count, provenance, deleted_at, times_pasted";
+
+/// Number of columns in `ITEM_COLUMNS`; extra selected columns start here
+const ITEM_COLUMN_COUNT: usize = 20;
+
+/// Frecency halves for every this many days without a paste
+const FRECENCY_HALF_LIFE_DAYS: f64 = 7.0;
+
+/// Schema changes applied on top of the base tables. Entry N brings the
+/// database to `user_version` N + 1; only ever append to this list.
+const MIGRATIONS: &[&str] = &[
+    // 1: track when an item was last copied or pasted
+    "ALTER TABLE clipboard_items ADD COLUMN last_used_at TEXT;",
+    // 2: JSON metadata attached at capture time
+    "ALTER TABLE clipboard_items ADD COLUMN metadata TEXT;",
+    // 3: generated or user-set titles for long items
+    "ALTER TABLE clipboard_items ADD COLUMN title TEXT;",
+    // 4: semantic search vectors (only populated with the semantic-search feature)
+    "CREATE TABLE IF NOT EXISTS item_embeddings (
+        item_id TEXT PRIMARY KEY,
+        model TEXT NOT NULL,
+        vector BLOB NOT NULL
+    );",
+    // 5: where items were copied from, and a log of where they were pasted
+    "ALTER TABLE clipboard_items ADD COLUMN source_app TEXT;
+    CREATE TABLE IF NOT EXISTS item_usage (
+        item_id TEXT NOT NULL,
+        app_id TEXT,
+        used_at TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_item_usage_used_at ON item_usage(used_at);",
+    // 6: size stats, so lists can show and sort by size without the content.
+    // Word counts are backfilled in Rust (see `backfill_word_counts`).
+    "ALTER TABLE clipboard_items ADD COLUMN byte_size INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE clipboard_items ADD COLUMN char_count INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE clipboard_items ADD COLUMN word_count INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE clipboard_items ADD COLUMN line_count INTEGER NOT NULL DEFAULT 0;
+    UPDATE clipboard_items SET byte_size = length(content) / 4 * 3 WHERE content_type = 'image';
+    UPDATE clipboard_items SET
+        byte_size = length(CAST(content AS BLOB)),
+        char_count = length(content),
+        line_count = CASE WHEN content = '' THEN 0
+            ELSE length(content) - length(replace(content, char(10), '')) + 1 - (substr(content, -1) = char(10))
+        END
+    WHERE content_type != 'image';
+    CREATE INDEX IF NOT EXISTS idx_items_byte_size ON clipboard_items(byte_size);",
+    // 7: links between items, e.g. a screenshot and the OCR text taken from it
+    "CREATE TABLE IF NOT EXISTS item_links (
+        source_id TEXT NOT NULL,
+        target_id TEXT NOT NULL,
+        relation TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        PRIMARY KEY (source_id, target_id, relation)
+    );
+    CREATE INDEX IF NOT EXISTS idx_item_links_target ON item_links(target_id);",
+    // 8: covers the history order so keyset pages are index seeks
+    "CREATE INDEX IF NOT EXISTS idx_items_history_order ON clipboard_items(is_pinned DESC, created_at DESC, id DESC);",
+    // 9: which item (and operation) a derived item was made from
+    "ALTER TABLE clipboard_items ADD COLUMN provenance TEXT;
+    CREATE INDEX IF NOT EXISTS idx_items_provenance_parent
+        ON clipboard_items(json_extract(provenance, '$.parent_id'));",
+    // 10: collections whose items are never pruned, and the single definition
+    // of "protected" that every retention path checks
+    "ALTER TABLE collections ADD COLUMN protected INTEGER NOT NULL DEFAULT 0;
+    CREATE VIEW IF NOT EXISTS protected_items AS
+        SELECT id FROM clipboard_items WHERE is_pinned = 1
+        UNION
+        SELECT item_id FROM board_items
+        UNION
+        SELECT i.id FROM clipboard_items i
+            JOIN collections c ON c.id = i.collection_id
+            WHERE c.protected = 1;",
+    // 11: trash; deleted items are kept until emptied or purged after a while
+    "ALTER TABLE clipboard_items ADD COLUMN deleted_at TEXT;
+    CREATE INDEX IF NOT EXISTS idx_items_deleted_at ON clipboard_items(deleted_at);",
+    // 12: paste counts and a frecency sort key (see `frecency_after_use`).
+    // Keys are computed in Rust by `backfill_frecency`.
+    "ALTER TABLE clipboard_items ADD COLUMN times_pasted INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE clipboard_items ADD COLUMN frecency REAL NOT NULL DEFAULT 0;
+    UPDATE clipboard_items SET times_pasted =
+        (SELECT COUNT(*) FROM item_usage WHERE item_usage.item_id = clipboard_items.id);
+    CREATE INDEX IF NOT EXISTS idx_items_frecency_order ON clipboard_items(is_pinned DESC, frecency DESC, id DESC);",
+    // 13: collections whose items are `{placeholder}` templates
+    "ALTER TABLE collections ADD COLUMN is_template INTEGER NOT NULL DEFAULT 0;",
+    // 14: how often each emoji / special character has been pasted
+    "CREATE TABLE IF NOT EXISTS symbol_usage (
+        symbol TEXT PRIMARY KEY,
+        hash TEXT NOT NULL,
+        count INTEGER NOT NULL DEFAULT 0,
+        last_used_at TEXT NOT NULL
+    );",
+    // 15: items evicted by retention, kept out of history but searchable
+    "CREATE TABLE IF NOT EXISTS archived_items (
+        id TEXT PRIMARY KEY,
+        content_type TEXT NOT NULL,
+        content TEXT NOT NULL,
+        preview TEXT NOT NULL,
+        hash TEXT NOT NULL,
+        is_pinned INTEGER NOT NULL DEFAULT 0,
+        collection_id TEXT,
+        created_at TEXT NOT NULL,
+        expires_at TEXT,
+        last_used_at TEXT,
+        metadata TEXT,
+        title TEXT,
+        source_app TEXT,
+        byte_size INTEGER NOT NULL DEFAULT 0,
+        char_count INTEGER NOT NULL DEFAULT 0,
+        word_count INTEGER NOT NULL DEFAULT 0,
+        line_count INTEGER NOT NULL DEFAULT 0,
+        provenance TEXT,
+        deleted_at TEXT,
+        times_pasted INTEGER NOT NULL DEFAULT 0,
+        frecency REAL NOT NULL DEFAULT 0,
+        archived_at TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_archived_items_created_at ON archived_items(created_at);",
+    // 16: indexes for the remaining history sort orders
+    "CREATE INDEX IF NOT EXISTS idx_items_last_used_order ON clipboard_items(is_pinned DESC, COALESCE(last_used_at, '') DESC, created_at DESC, id DESC);
+    CREATE INDEX IF NOT EXISTS idx_items_times_pasted_order ON clipboard_items(is_pinned DESC, times_pasted DESC, created_at DESC, id DESC);
+    CREATE INDEX IF NOT EXISTS idx_items_size_order ON clipboard_items(is_pinned DESC, byte_size DESC, id DESC);
+    CREATE INDEX IF NOT EXISTS idx_items_type_order ON clipboard_items(is_pinned DESC, content_type ASC, created_at DESC, id DESC);
+    CREATE INDEX IF NOT EXISTS idx_items_alphabetical_order ON clipboard_items(is_pinned DESC, lower(COALESCE(title, preview)) ASC, id ASC);",
+    // 17: content an item had before it was edited
+    "CREATE TABLE IF NOT EXISTS item_versions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        item_id TEXT NOT NULL,
+        content TEXT NOT NULL,
+        preview TEXT NOT NULL,
+        hash TEXT NOT NULL,
+        byte_size INTEGER NOT NULL DEFAULT 0,
+        char_count INTEGER NOT NULL DEFAULT 0,
+        word_count INTEGER NOT NULL DEFAULT 0,
+        line_count INTEGER NOT NULL DEFAULT 0,
+        replaced_at TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_item_versions_item ON item_versions(item_id, id);",
+    // 18: small key/value records the app keeps alongside history
+    "CREATE TABLE IF NOT EXISTS app_state (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );",
+];
+
+/// Prior versions kept per item before the oldest are pruned
+const MAX_ITEM_VERSIONS: i64 = 20;
+
+/// Snapshots kept in `backups/` before the oldest are pruned
+const MAX_SNAPSHOTS: usize = 5;
+
+/// A schema upgrade that failed and was rolled back. The pre-upgrade
+/// snapshot is kept so the user (or support) can recover from it.
+#[derive(Debug, Clone, Serialize, thiserror::Error)]
+#[error("database migration {failed_version} failed: {message}")]
+pub struct MigrationFailure {
+    pub from_version: i64,
+    pub failed_version: usize,
+    pub message: String,
+    pub snapshot_path: Option<String>,
+}
+
+/// The failed upgrade from this launch, if any
+pub struct MigrationFailureState(pub Option<MigrationFailure>);
+
+#[derive(Debug, thiserror::Error)]
+pub enum InitError {
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+    #[error(transparent)]
+    Migration(#[from] MigrationFailure),
+}
+
+fn parse_datetime(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s)
+        .unwrap_or_else(|_| Utc::now().into())
+        .with_timezone(&Utc)
+}
+
+fn parse_optional_datetime(s: Option<String>) -> Option<DateTime<Utc>> {
+    s.and_then(|s| {
+        DateTime::parse_from_rfc3339(&s)
+            .ok()
+            .map(|dt| dt.with_timezone(&Utc))
+    })
+}
+
+fn epoch_days(at: DateTime<Utc>) -> f64 {
+    at.timestamp_millis() as f64 / 86_400_000.0
+}
+
+/// Frecency is a use count that decays with a fixed half-life. Stored as
+/// `ln(score) + rate * days`, which ranks rows the same as their decayed
+/// score at any moment, so it can be indexed and paginated like a timestamp.
+fn frecency_rate() -> f64 {
+    std::f64::consts::LN_2 / FRECENCY_HALF_LIFE_DAYS
+}
+
+/// Key for an item whose only use so far is being copied at `at`
+fn initial_frecency(at: DateTime<Utc>) -> f64 {
+    frecency_rate() * epoch_days(at)
+}
+
+/// Key after one more use at `at`
+fn frecency_after_use(key: f64, at: DateTime<Utc>) -> f64 {
+    let now = frecency_rate() * epoch_days(at);
+    let score = (key - now).exp();
+    (score + 1.0).ln() + now
+}
+
+fn insert_item_row(conn: &Connection, item: &ClipboardItem) -> Result<()> {
+    conn.execute(
+        r#"
+        INSERT INTO clipboard_items (id, content_type, content, preview, hash, is_pinned, collection_id, created_at, expires_at, last_used_at, metadata, title, source_app, byte_size, char_count, word_count, line_count, provenance, deleted_at, times_pasted, frecency)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)
+        "#,
+        params![
+            item.id,
+            item.content_type,
+            item.content,
+            item.preview,
+            item.hash,
+            item.is_pinned as i32,
+            item.collection_id,
+            item.created_at.to_rfc3339(),
+            item.expires_at.map(|dt| dt.to_rfc3339()),
+            item.last_used_at.map(|dt| dt.to_rfc3339()),
+            item.metadata.as_ref().map(|m| m.to_string()),
+            item.title,
+            item.source_app,
+            item.byte_size,
+            item.char_count,
+            item.word_count,
+            item.line_count,
+            item.provenance.as_ref().and_then(|p| serde_json::to_string(p).ok()),
+            item.deleted_at.map(|dt| dt.to_rfc3339()),
+            item.times_pasted,
+            initial_frecency(item.created_at),
+        ],
+    )?;
+
+    Ok(())
+}
+
+fn row_to_item(row: &rusqlite::Row) -> Result<ClipboardItem> {
+    let created_str: String = row.get(7)?;
+
+    Ok(ClipboardItem {
+        id: row.get(0)?,
+        content_type: row.get(1)?,
+        content: row.get(2)?,
+        preview: row.get(3)?,
+        hash: row.get(4)?,
+        is_pinned: row.get::<_, i32>(5)? != 0,
+        collection_id: row.get(6)?,
+        created_at: parse_datetime(&created_str),
+        expires_at: parse_optional_datetime(row.get(8)?),
+        last_used_at: parse_optional_datetime(row.get(9)?),
+        metadata: row
+            .get::<_, Option<String>>(10)?
+            .and_then(|s| serde_json::from_str(&s).ok()),
+        title: row.get(11)?,
+        source_app: row.get(12)?,
+        byte_size: row.get(13)?,
+        char_count: row.get(14)?,
+        word_count: row.get(15)?,
+        line_count: row.get(16)?,
+        provenance: row
+            .get::<_, Option<String>>(17)?
+            .and_then(|s| serde_json::from_str(&s).ok()),
+        deleted_at: parse_optional_datetime(row.get(18)?),
+        times_pasted: row.get(19)?,
+    })
+}
+
+fn row_to_item_meta(row: &rusqlite::Row) -> Result<ItemMeta> {
+    let created_str: String = row.get(6)?;
+
+    Ok(ItemMeta {
+        id: row.get(0)?,
+        content_type: row.get(1)?,
+        preview: row.get(2)?,
+        hash: row.get(3)?,
+        is_pinned: row.get::<_, i32>(4)? != 0,
+        collection_id: row.get(5)?,
+        created_at: parse_datetime(&created_str),
+        expires_at: parse_optional_datetime(row.get(7)?),
+        last_used_at: parse_optional_datetime(row.get(8)?),
+        title: row.get(9)?,
+        source_app: row.get(10)?,
+        byte_size: row.get(11)?,
+        char_count: row.get(12)?,
+        word_count: row.get(13)?,
+        line_count: row.get(14)?,
+        times_pasted: row.get(15)?,
+    })
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClipboardItem {
@@ -15,6 +325,152 @@ pub struct ClipboardItem {
     pub collection_id: Option<String>,
     pub created_at: DateTime<Utc>,
     pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// Free-form JSON describing the item (source page, detected parts, ...)
+    pub metadata: Option<serde_json::Value>,
+    /// Short label shown instead of the preview for long items
+    pub title: Option<String>,
+    /// Bundle id (or window name) of the app the item was copied from
+    pub source_app: Option<String>,
+    /// Size of the content in bytes (decoded size for images)
+    pub byte_size: i64,
+    pub char_count: i64,
+    pub word_count: i64,
+    pub line_count: i64,
+    /// Set on items made from another item (OCR text, converted colors, ...)
+    pub provenance: Option<Provenance>,
+    /// Set while the item is in the trash
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub times_pasted: i64,
+}
+
+/// A `ClipboardItem` without its payload, for rendering the history list.
+/// The content is fetched separately when an item is opened or pasted.
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemMeta {
+    pub id: String,
+    pub content_type: String,
+    pub preview: String,
+    pub hash: String,
+    pub is_pinned: bool,
+    pub collection_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub title: Option<String>,
+    pub source_app: Option<String>,
+    pub byte_size: i64,
+    pub char_count: i64,
+    pub word_count: i64,
+    pub line_count: i64,
+    pub times_pasted: i64,
+}
+
+/// Content an item had before an edit replaced it
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemVersion {
+    pub id: i64,
+    pub item_id: String,
+    pub content: String,
+    pub preview: String,
+    pub hash: String,
+    pub byte_size: i64,
+    pub char_count: i64,
+    pub word_count: i64,
+    pub line_count: i64,
+    /// When this content was replaced
+    pub replaced_at: DateTime<Utc>,
+}
+
+fn row_to_item_version(row: &rusqlite::Row) -> Result<ItemVersion> {
+    let replaced_str: String = row.get(9)?;
+    Ok(ItemVersion {
+        id: row.get(0)?,
+        item_id: row.get(1)?,
+        content: row.get(2)?,
+        preview: row.get(3)?,
+        hash: row.get(4)?,
+        byte_size: row.get(5)?,
+        char_count: row.get(6)?,
+        word_count: row.get(7)?,
+        line_count: row.get(8)?,
+        replaced_at: DateTime::parse_from_rfc3339(&replaced_str)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now()),
+    })
+}
+
+/// An emoji or special character and how often it has been pasted
+#[derive(Debug, Clone, Serialize)]
+pub struct FrequentSymbol {
+    pub symbol: String,
+    pub count: i64,
+    pub last_used_at: DateTime<Utc>,
+    /// History item holding the symbol, if it is still there
+    pub item_id: Option<String>,
+}
+
+/// Where a derived item came from, with enough detail to derive it again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Provenance {
+    pub parent_id: String,
+    /// "ocr", "convert_color", "palette_color", ...
+    pub operation: String,
+    /// Operation arguments, e.g. `{"format": "rgb"}`
+    #[serde(default)]
+    pub options: serde_json::Value,
+}
+
+impl ClipboardItem {
+    /// Build a fresh, unpinned item captured right now
+    pub fn new(content_type: String, content: String, preview: String, hash: String) -> Self {
+        let mut item = Self {
+            id: Uuid::new_v4().to_string(),
+            content_type,
+            content,
+            preview,
+            hash,
+            is_pinned: false,
+            collection_id: None,
+            created_at: Utc::now(),
+            expires_at: None,
+            last_used_at: None,
+            metadata: None,
+            title: None,
+            source_app: None,
+            byte_size: 0,
+            char_count: 0,
+            word_count: 0,
+            line_count: 0,
+            provenance: None,
+            deleted_at: None,
+            times_pasted: 0,
+        };
+        item.refresh_stats();
+        item
+    }
+
+    /// Copied from a password manager (flagged concealed on the pasteboard)
+    pub fn is_concealed(&self) -> bool {
+        self.metadata
+            .as_ref()
+            .is_some_and(|m| m["concealed"].as_bool() == Some(true))
+    }
+
+    /// Recompute the size stats after `content` changes
+    pub fn refresh_stats(&mut self) {
+        if self.content_type == "image" {
+            self.byte_size = (self.content.len() / 4 * 3) as i64;
+            self.char_count = 0;
+            self.word_count = 0;
+            self.line_count = 0;
+        } else {
+            self.byte_size = self.content.len() as i64;
+            self.char_count = self.content.chars().count() as i64;
+            self.word_count = self.content.split_whitespace().count() as i64;
+            self.line_count = self.content.lines().count() as i64;
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +479,12 @@ pub struct Collection {
     pub name: String,
     pub color: String,
     pub created_at: DateTime<Utc>,
+    /// Items in a protected collection survive clears, expiry and the history limit
+    #[serde(default)]
+    pub protected: bool,
+    /// Items are templates whose placeholders are filled in at paste time
+    #[serde(default)]
+    pub is_template: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,24 +493,446 @@ pub struct Tag {
     pub name: String,
 }
 
+/// Position in the history list, just past the last item of a page. Pass it
+/// back to `get_items` with the same sort to fetch the next page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemCursor {
+    pub is_pinned: bool,
+    /// Values of the sort's keys for the last item
+    pub keys: Vec<serde_json::Value>,
+    pub id: String,
+}
+
+/// Order of the history list (pinned items always come first)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemSort {
+    /// Newest first
+    #[default]
+    Recent,
+    /// Most and most recently pasted first
+    Frecency,
+    /// Most recently pasted first, never-pasted items last
+    LastUsed,
+    TimesPasted,
+    /// Largest first
+    Size,
+    /// Grouped by content type, newest first within each
+    Type,
+    /// By title, or preview for untitled items
+    Alphabetical,
+}
+
+impl ItemSort {
+    /// Key expressions after `is_pinned`, and whether each sorts descending.
+    /// Each order has a matching `idx_items_*_order` index.
+    fn keys(self) -> &'static [(&'static str, bool)] {
+        match self {
+            ItemSort::Recent => &[("created_at", true)],
+            ItemSort::Frecency => &[("frecency", true)],
+            ItemSort::LastUsed => &[("COALESCE(last_used_at, '')", true), ("created_at", true)],
+            ItemSort::TimesPasted => &[("times_pasted", true), ("created_at", true)],
+            ItemSort::Size => &[("byte_size", true)],
+            ItemSort::Type => &[("content_type", false), ("created_at", true)],
+            ItemSort::Alphabetical => &[("lower(COALESCE(title, preview))", false)],
+        }
+    }
+}
+
+fn json_to_sql(value: &serde_json::Value) -> SqlValue {
+    match value {
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => SqlValue::Text(s.clone()),
+        serde_json::Value::Bool(b) => SqlValue::Integer(*b as i64),
+        _ => SqlValue::Null,
+    }
+}
+
+fn sql_to_json(value: SqlValue) -> serde_json::Value {
+    match value {
+        SqlValue::Integer(i) => i.into(),
+        SqlValue::Real(f) => f.into(),
+        SqlValue::Text(s) => s.into(),
+        SqlValue::Null | SqlValue::Blob(_) => serde_json::Value::Null,
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemPage<T> {
+    pub items: Vec<T>,
+    /// `None` once the end of history is reached
+    pub next_cursor: Option<ItemCursor>,
+    /// Items on this page that come from the archive
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub archived_ids: Vec<String>,
+}
+
+/// WHERE conditions and their bound parameters, numbered as they're added
+#[derive(Default)]
+struct WhereClause {
+    conditions: Vec<String>,
+    params: Vec<Box<dyn rusqlite::ToSql>>,
+}
+
+impl WhereClause {
+    /// Add a parameter, returning its placeholder
+    fn bind(&mut self, value: impl rusqlite::ToSql + 'static) -> String {
+        self.params.push(Box::new(value));
+        format!("?{}", self.params.len())
+    }
+
+    fn push(&mut self, condition: String) {
+        self.conditions.push(condition);
+    }
+
+    fn bind_list(&mut self, values: &[String]) -> String {
+        values
+            .iter()
+            .map(|v| self.bind(v.clone()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn push_filter(&mut self, filter: &ItemFilter) {
+        for term in &filter.text {
+            // LIKE ignores ASCII case only, so case-sensitive terms use instr()
+            let matches_text = if filter.case_sensitive {
+                let term = self.bind(term.clone());
+                format!("(instr(content, {0}) > 0 OR instr(preview, {0}) > 0)", term)
+            } else {
+                let pattern = self.bind(search::like_pattern(term));
+                format!(
+                    "(content LIKE {0} ESCAPE '\\' OR preview LIKE {0} ESCAPE '\\')",
+                    pattern
+                )
+            };
+            self.push(matches_text);
+
+            // The substring match above narrows rows before the word check
+            if filter.whole_word {
+                let term = self.bind(term.clone());
+                let case_sensitive = self.bind(filter.case_sensitive);
+                self.push(format!(
+                    "(word_match(content, {0}, {1}) OR word_match(preview, {0}, {1}))",
+                    term, case_sensitive
+                ));
+            }
+        }
+        for types in [&filter.content_types, &filter.tab_types] {
+            if !types.is_empty() {
+                let types = self.bind_list(types);
+                self.push(format!("content_type IN ({})", types));
+            }
+        }
+        for tag in &filter.tags {
+            let tag = self.bind(tag.clone());
+            self.push(format!(
+                "id IN (SELECT item_tags.item_id FROM item_tags JOIN tags ON tags.id = item_tags.tag_id WHERE tags.name = {} COLLATE NOCASE)",
+                tag
+            ));
+        }
+        if !filter.collections.is_empty() {
+            let names = self.bind_list(&filter.collections);
+            self.push(format!(
+                "collection_id IN (SELECT id FROM collections WHERE name COLLATE NOCASE IN ({}))",
+                names
+            ));
+        }
+        if let Some(collection_id) = &filter.collection_id {
+            let collection_id = self.bind(collection_id.clone());
+            self.push(format!("collection_id = {}", collection_id));
+        }
+        if !filter.tag_ids.is_empty() {
+            let mut tag_ids = filter.tag_ids.clone();
+            tag_ids.sort();
+            tag_ids.dedup();
+            let count = tag_ids.len() as i64;
+            let ids = self.bind_list(&tag_ids);
+            let count = self.bind(count);
+            self.push(format!(
+                "id IN (SELECT item_id FROM item_tags WHERE tag_id IN ({}) GROUP BY item_id HAVING COUNT(*) = {})",
+                ids, count
+            ));
+        }
+        if let Some(pinned) = filter.pinned {
+            let pinned = self.bind(pinned);
+            self.push(format!("is_pinned = {}", pinned));
+        }
+        if let Some(before) = filter.before {
+            let before = self.bind(before.to_rfc3339());
+            self.push(format!("created_at < {}", before));
+        }
+        if let Some(after) = filter.after {
+            let after = self.bind(after.to_rfc3339());
+            self.push(format!("created_at >= {}", after));
+        }
+    }
+
+    fn sql(&self) -> String {
+        if self.conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", self.conditions.join(" AND "))
+        }
+    }
+
+    fn params(&self) -> Vec<&dyn rusqlite::ToSql> {
+        self.params.iter().map(|p| p.as_ref()).collect()
+    }
+}
+
+/// An item linked to another, seen from that other item. `outgoing` is true
+/// when the link was made from the other item to this one, so a screenshot
+/// sees its OCR text as outgoing "ocr" and the text sees the screenshot as
+/// incoming.
+#[derive(Debug, Clone, Serialize)]
+pub struct RelatedItem {
+    pub item: ClipboardItem,
+    pub relation: String,
+    pub outgoing: bool,
+}
+
+/// A named pin set ("Standup links", "Release checklist"). Items can sit on
+/// any number of boards independently of their collection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Board {
+    pub id: String,
+    pub name: String,
+    pub hotkey: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
 pub struct Database {
-    conn: Mutex<Connection>,
+    /// The only connection that writes; SQLite allows one writer at a time
+    writer: Mutex<Connection>,
+    readers: ReaderPool,
+    events: EventBus,
+}
+
+/// Read-only connections for queries. In WAL mode they read alongside the
+/// writer, so listing or searching history never waits behind a capture
+/// being stored, and a slow search doesn't hold up new captures.
+struct ReaderPool {
+    conns: Vec<Mutex<Connection>>,
+    next: AtomicUsize,
+}
+
+impl ReaderPool {
+    fn open(db_path: &Path, size: usize) -> Result<Self> {
+        let conns = (0..size)
+            .map(|_| {
+                let conn = Connection::open_with_flags(
+                    db_path,
+                    OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+                )?;
+                conn.execute_batch("PRAGMA temp_store = MEMORY; PRAGMA cache_size = -4000;")?;
+                conn.busy_timeout(std::time::Duration::from_secs(5))?;
+                conn.set_prepared_statement_cache_capacity(64);
+                register_functions(&conn)?;
+                Ok(Mutex::new(conn))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            conns,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    fn empty() -> Self {
+        Self {
+            conns: Vec::new(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// An idle connection if there is one, otherwise wait on the next in turn
+    fn get(&self) -> Option<MutexGuard<'_, Connection>> {
+        if self.conns.is_empty() {
+            return None;
+        }
+        if let Some(idle) = self.conns.iter().find_map(|c| c.try_lock().ok()) {
+            return Some(idle);
+        }
+        let i = self.next.fetch_add(1, Ordering::Relaxed) % self.conns.len();
+        Some(self.conns[i].lock().unwrap())
+    }
+}
+
+/// SQL functions used by queries, registered on every connection
+fn register_functions(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function(
+        "word_match",
+        3,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let haystack = ctx.get::<Option<String>>(0)?.unwrap_or_default();
+            let needle = ctx.get::<String>(1)?;
+            let case_sensitive = ctx.get::<bool>(2)?;
+            Ok(search::contains_word(&haystack, &needle, case_sensitive))
+        },
+    )
+}
+
+/// Readers in the pool; UI queries rarely overlap more than this
+const READER_COUNT: usize = 3;
+
+/// A change to `clipboard_items`, published after the write commits
+#[derive(Debug, Clone)]
+pub enum ItemEvent {
+    Added(ClipboardItem),
+    Updated(ClipboardItem),
+    Deleted(Vec<String>),
+}
+
+type EventSink = Box<dyn Fn(ItemEvent) + Send + Sync>;
+
+/// Forwards item changes from the write paths to a single subscriber (the
+/// app, which turns them into frontend events)
+#[derive(Default)]
+struct EventBus {
+    sink: Mutex<Option<EventSink>>,
+}
+
+impl EventBus {
+    fn publish(&self, event: ItemEvent) {
+        if let Some(sink) = self.sink.lock().unwrap().as_ref() {
+            sink(event);
+        }
+    }
+}
+
+/// `VACUUM INTO` produces a consistent copy even with the WAL in use
+fn snapshot_database(
+    conn: &Connection,
+    backup_dir: &Path,
+    label: &str,
+) -> std::result::Result<PathBuf, String> {
+    std::fs::create_dir_all(backup_dir).map_err(|e| e.to_string())?;
+
+    let path = backup_dir.join(format!(
+        "yoink-{}-{}.db",
+        label,
+        Utc::now().format("%Y%m%d-%H%M%S")
+    ));
+    conn.execute("VACUUM INTO ?1", params![path.to_string_lossy()])
+        .map_err(|e| e.to_string())?;
+    log::info!("Snapshotted database to {}", path.display());
+
+    prune_snapshots(backup_dir);
+    Ok(path)
+}
+
+fn prune_snapshots(backup_dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(backup_dir) else {
+        return;
+    };
+
+    // Oldest first
+    let mut snapshots: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "db"))
+        .collect();
+    snapshots.sort_by_key(|p| std::fs::metadata(p).and_then(|m| m.modified()).ok());
+
+    while snapshots.len() > MAX_SNAPSHOTS {
+        let oldest = snapshots.remove(0);
+        let _ = std::fs::remove_file(oldest);
+    }
 }
 
 impl Database {
-    pub fn new(app_data_dir: PathBuf) -> Result<Self> {
+    pub fn new(app_data_dir: PathBuf) -> std::result::Result<Self, InitError> {
         std::fs::create_dir_all(&app_data_dir).ok();
         let db_path = app_data_dir.join("yoink.db");
-        let conn = Connection::open(db_path)?;
+        let existed = db_path.exists();
+        let conn = Connection::open(&db_path)?;
+
+        // WAL lets reads proceed while a capture is being written; NORMAL
+        // sync is still crash-safe in WAL mode and avoids an fsync per commit
+        conn.execute_batch(
+            r#"
+            PRAGMA journal_mode = WAL;
+            PRAGMA synchronous = NORMAL;
+            PRAGMA temp_store = MEMORY;
+            PRAGMA cache_size = -8000;
+            "#,
+        )?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        conn.set_prepared_statement_cache_capacity(64);
+        register_functions(&conn)?;
+
+        let mut db = Database {
+            writer: Mutex::new(conn),
+            readers: ReaderPool::empty(),
+            events: EventBus::default(),
+        };
+        db.init()?;
+
+        // A brand-new database has nothing worth snapshotting
+        let backup_dir = existed.then(|| app_data_dir.join("backups"));
+        db.migrate(backup_dir.as_deref())?;
+        // Opened once the schema is final
+        db.readers = ReaderPool::open(&db_path, READER_COUNT)?;
+        if let Err(e) = db.backfill_word_counts() {
+            log::warn!("Failed to backfill word counts: {}", e);
+        }
+        if let Err(e) = db.backfill_frecency() {
+            log::warn!("Failed to backfill frecency: {}", e);
+        }
+        Ok(db)
+    }
+
+    /// Empty, throwaway database used for the session when the real one
+    /// couldn't be upgraded, so the app can still start and report the error
+    pub fn in_memory() -> std::result::Result<Self, InitError> {
+        // A private in-memory database can't be shared, so reads go
+        // through the writer
+        let conn = Connection::open_in_memory()?;
+        register_functions(&conn)?;
         let db = Database {
-            conn: Mutex::new(conn),
+            writer: Mutex::new(conn),
+            readers: ReaderPool::empty(),
+            events: EventBus::default(),
         };
         db.init()?;
+        db.migrate(None)?;
         Ok(db)
     }
 
+    fn writer(&self) -> MutexGuard<'_, Connection> {
+        self.writer.lock().unwrap()
+    }
+
+    /// Connection for queries that don't write
+    fn reader(&self) -> MutexGuard<'_, Connection> {
+        self.readers.get().unwrap_or_else(|| self.writer())
+    }
+
+    /// Receive an `ItemEvent` for every change to history
+    pub fn subscribe(&self, sink: impl Fn(ItemEvent) + Send + Sync + 'static) {
+        *self.events.sink.lock().unwrap() = Some(Box::new(sink));
+    }
+
+    /// Publish the current state of an item after an in-place update
+    fn publish_updated(&self, id: &str) {
+        match self.get_item(id) {
+            Ok(Some(item)) => self.events.publish(ItemEvent::Updated(item)),
+            Ok(None) => {}
+            Err(e) => log::warn!("Failed to load updated item {}: {}", id, e),
+        }
+    }
+
+    fn publish_deleted(&self, ids: Vec<String>) {
+        if !ids.is_empty() {
+            self.events.publish(ItemEvent::Deleted(ids));
+        }
+    }
+
     fn init(&self) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer();
 
         conn.execute_batch(
             r#"
@@ -84,46 +968,483 @@ impl Database {
                 FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
             );
 
+            CREATE TABLE IF NOT EXISTS boards (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                hotkey TEXT,
+                created_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS board_items (
+                board_id TEXT NOT NULL,
+                item_id TEXT NOT NULL,
+                position INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (board_id, item_id),
+                FOREIGN KEY (board_id) REFERENCES boards(id) ON DELETE CASCADE,
+                FOREIGN KEY (item_id) REFERENCES clipboard_items(id) ON DELETE CASCADE
+            );
+
             CREATE INDEX IF NOT EXISTS idx_items_created_at ON clipboard_items(created_at DESC);
             CREATE INDEX IF NOT EXISTS idx_items_hash ON clipboard_items(hash);
             CREATE INDEX IF NOT EXISTS idx_items_pinned ON clipboard_items(is_pinned);
             CREATE INDEX IF NOT EXISTS idx_items_collection ON clipboard_items(collection_id);
+            CREATE INDEX IF NOT EXISTS idx_board_items_item ON board_items(item_id);
             "#,
         )?;
 
         Ok(())
     }
 
+    /// Apply pending migrations in a single transaction, so a failure leaves
+    /// the schema exactly as it was. With `backup_dir`, the database is
+    /// snapshotted there first.
+    fn migrate(&self, backup_dir: Option<&Path>) -> std::result::Result<(), InitError> {
+        let conn = self.writer();
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version as usize >= MIGRATIONS.len() {
+            return Ok(());
+        }
+
+        // Refuse to upgrade without a snapshot to fall back on
+        let snapshot = match backup_dir {
+            Some(dir) => Some(
+                snapshot_database(&conn, dir, &format!("pre-migration-v{}", version)).map_err(
+                    |message| MigrationFailure {
+                        from_version: version,
+                        failed_version: version as usize + 1,
+                        message: format!("Failed to snapshot database: {}", message),
+                        snapshot_path: None,
+                    },
+                )?,
+            ),
+            None => None,
+        };
+
+        let mut failed_version = version as usize + 1;
+        let result = (|| -> Result<()> {
+            let tx = conn.unchecked_transaction()?;
+            for (i, sql) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+                failed_version = i + 1;
+                tx.execute_batch(sql)?;
+            }
+            tx.pragma_update(None, "user_version", MIGRATIONS.len() as i64)?;
+            tx.commit()
+        })();
+
+        match result {
+            Ok(()) => {
+                log::info!(
+                    "Applied database migrations {}..={}",
+                    version + 1,
+                    MIGRATIONS.len()
+                );
+                Ok(())
+            }
+            Err(e) => Err(MigrationFailure {
+                from_version: version,
+                failed_version,
+                message: e.to_string(),
+                snapshot_path: snapshot.map(|p| p.to_string_lossy().into_owned()),
+            }
+            .into()),
+        }
+    }
+
+    /// Fill in word counts for rows stored before size stats existed
+    fn backfill_word_counts(&self) -> Result<()> {
+        let conn = self.writer();
+
+        let rows: Vec<(String, String)> = conn
+            .prepare(
+                "SELECT id, content FROM clipboard_items WHERE word_count = 0 AND char_count > 0 AND content_type != 'image'",
+            )?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>>>()?;
+
+        let tx = conn.unchecked_transaction()?;
+        for (id, content) in rows {
+            tx.execute(
+                "UPDATE clipboard_items SET word_count = ?1 WHERE id = ?2",
+                params![content.split_whitespace().count() as i64, id],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Compute frecency keys for rows stored before frecency existed
+    fn backfill_frecency(&self) -> Result<()> {
+        let conn = self.writer();
+
+        let rows: Vec<(String, i64, String, Option<String>)> = conn
+            .prepare(
+                "SELECT id, times_pasted, created_at, last_used_at FROM clipboard_items WHERE frecency = 0",
+            )?
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        let tx = conn.unchecked_transaction()?;
+        for (id, times_pasted, created_at, last_used_at) in rows {
+            // Without the individual paste times, treat them all as the last one
+            let last_used =
+                parse_optional_datetime(last_used_at).unwrap_or_else(|| parse_datetime(&created_at));
+            let frecency =
+                (1.0 + times_pasted as f64).ln() + frecency_rate() * epoch_days(last_used);
+            tx.execute(
+                "UPDATE clipboard_items SET frecency = ?1 WHERE id = ?2",
+                params![frecency, id],
+            )?;
+        }
+        tx.commit()
+    }
+
+    #[allow(dead_code)]
     pub fn insert_item(&self, item: &ClipboardItem) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer();
+        insert_item_row(&conn, item)?;
+        drop(conn);
 
-        conn.execute(
+        self.events.publish(ItemEvent::Added(item.clone()));
+        Ok(())
+    }
+
+    /// Store a burst of captures in one transaction: new content is inserted,
+    /// content already in history is promoted to the top. Returns the stored
+    /// rows in capture order.
+    pub fn store_captures(&self, items: &[ClipboardItem]) -> Result<Vec<ClipboardItem>> {
+        let conn = self.writer();
+        let tx = conn.unchecked_transaction()?;
+        let now = Utc::now();
+        let mut stored = Vec::with_capacity(items.len());
+        let mut events = Vec::with_capacity(items.len());
+
+        for item in items {
+            let existing: Option<(String, Option<String>, f64)> = tx
+                .prepare_cached(
+                    "SELECT id, metadata, frecency FROM clipboard_items WHERE hash = ?1 AND deleted_at IS NULL ORDER BY created_at DESC LIMIT 1",
+                )?
+                .query_row(params![item.hash], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .optional()?;
+
+            match existing {
+                Some((id, existing_metadata, frecency)) => {
+                    // Copying something again counts as a use, like pasting it
+                    tx.execute(
+                        "UPDATE clipboard_items SET created_at = ?1, last_used_at = ?1, frecency = ?2 WHERE id = ?3",
+                        params![now.to_rfc3339(), frecency_after_use(frecency, now), id],
+                    )?;
+                    // Keep richer metadata that arrived with the re-copy (e.g. from the browser extension)
+                    if let (None, Some(metadata)) = (existing_metadata, &item.metadata) {
+                        tx.execute(
+                            "UPDATE clipboard_items SET metadata = ?1 WHERE id = ?2",
+                            params![metadata.to_string(), id],
+                        )?;
+                    }
+                    let promoted = tx.query_row(
+                        &format!("SELECT {} FROM clipboard_items WHERE id = ?1", ITEM_COLUMNS),
+                        params![id],
+                        row_to_item,
+                    )?;
+                    events.push(ItemEvent::Updated(promoted.clone()));
+                    stored.push(promoted);
+                }
+                None => {
+                    insert_item_row(&tx, item)?;
+                    events.push(ItemEvent::Added(item.clone()));
+                    stored.push(item.clone());
+                }
+            }
+        }
+
+        tx.commit()?;
+        drop(conn);
+
+        for event in events {
+            self.events.publish(event);
+        }
+        Ok(stored)
+    }
+
+    /// Ids of the most recently copied items, newest first. Unlike history
+    /// pages, pinned items aren't moved to the front.
+    pub fn recent_item_ids(&self, limit: u32) -> Result<Vec<String>> {
+        let conn = self.reader();
+        let mut stmt = conn.prepare_cached(
+            "SELECT id FROM clipboard_items WHERE deleted_at IS NULL ORDER BY created_at DESC, id DESC LIMIT ?1",
+        )?;
+        let ids = stmt
+            .query_map(params![limit], |row| row.get(0))?
+            .collect::<Result<Vec<String>>>()?;
+        Ok(ids)
+    }
+
+    /// Replace an item's content in place (append mode grows one item). The
+    /// old content is kept as a version unless it is unchanged.
+    pub fn update_item_content(&self, item: &ClipboardItem) -> Result<()> {
+        let conn = self.writer();
+        let tx = conn.unchecked_transaction()?;
+
+        tx.execute(
+            r#"
+            INSERT INTO item_versions (item_id, content, preview, hash, byte_size, char_count, word_count, line_count, replaced_at)
+            SELECT id, content, preview, hash, byte_size, char_count, word_count, line_count, ?3
+            FROM clipboard_items WHERE id = ?1 AND hash != ?2
+            "#,
+            params![item.id, item.hash, Utc::now().to_rfc3339()],
+        )?;
+        tx.execute(
             r#"
-            INSERT INTO clipboard_items (id, content_type, content, preview, hash, is_pinned, collection_id, created_at, expires_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            DELETE FROM item_versions WHERE item_id = ?1 AND id NOT IN (
+                SELECT id FROM item_versions WHERE item_id = ?1 ORDER BY id DESC LIMIT ?2
+            )
+            "#,
+            params![item.id, MAX_ITEM_VERSIONS],
+        )?;
+        tx.execute(
+            r#"
+            UPDATE clipboard_items
+            SET content = ?1, preview = ?2, hash = ?3, created_at = ?4,
+                byte_size = ?5, char_count = ?6, word_count = ?7, line_count = ?8
+            WHERE id = ?9
             "#,
             params![
-                item.id,
-                item.content_type,
                 item.content,
                 item.preview,
                 item.hash,
-                item.is_pinned as i32,
-                item.collection_id,
-                item.created_at.to_rfc3339(),
-                item.expires_at.map(|dt| dt.to_rfc3339()),
+                Utc::now().to_rfc3339(),
+                item.byte_size,
+                item.char_count,
+                item.word_count,
+                item.line_count,
+                item.id,
             ],
         )?;
+        tx.commit()?;
+
+        drop(conn);
 
+        self.publish_updated(&item.id);
+        Ok(())
+    }
+
+    /// Earlier contents of an item, newest first
+    pub fn get_item_versions(&self, item_id: &str) -> Result<Vec<ItemVersion>> {
+        let conn = self.reader();
+
+        let mut stmt = conn.prepare_cached(
+            r#"
+            SELECT id, item_id, content, preview, hash, byte_size, char_count, word_count, line_count, replaced_at
+            FROM item_versions WHERE item_id = ?1 ORDER BY id DESC
+            "#,
+        )?;
+        let versions = stmt
+            .query_map(params![item_id], row_to_item_version)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(versions)
+    }
+
+    /// Put a version's content back on its item. The content being replaced
+    /// becomes a version itself, so a revert can be undone.
+    pub fn revert_item_version(&self, version_id: i64) -> Result<Option<ClipboardItem>> {
+        let version = self
+            .reader()
+            .prepare_cached(
+                r#"
+                SELECT id, item_id, content, preview, hash, byte_size, char_count, word_count, line_count, replaced_at
+                FROM item_versions WHERE id = ?1
+                "#,
+            )?
+            .query_row(params![version_id], row_to_item_version)
+            .optional()?;
+        let Some(version) = version else {
+            return Ok(None);
+        };
+        let Some(mut item) = self.get_item(&version.item_id)? else {
+            return Ok(None);
+        };
+
+        item.content = version.content;
+        item.preview = version.preview;
+        item.hash = version.hash;
+        item.refresh_stats();
+        self.update_item_content(&item)?;
+
+        Ok(Some(item))
+    }
+
+    /// Log a paste of `item_id` into `app_id`, and mark the item as used
+    pub fn record_usage(&self, item_id: &str, app_id: Option<&str>) -> Result<()> {
+        let conn = self.writer();
+        let now = Utc::now();
+
+        conn.execute(
+            "INSERT INTO item_usage (item_id, app_id, used_at) VALUES (?1, ?2, ?3)",
+            params![item_id, app_id, now.to_rfc3339()],
+        )?;
+        let frecency: Option<f64> = conn
+            .prepare_cached("SELECT frecency FROM clipboard_items WHERE id = ?1")?
+            .query_row(params![item_id], |row| row.get(0))
+            .optional()?;
+        if let Some(frecency) = frecency {
+            conn.execute(
+                "UPDATE clipboard_items SET last_used_at = ?1, times_pasted = times_pasted + 1, frecency = ?2 WHERE id = ?3",
+                params![now.to_rfc3339(), frecency_after_use(frecency, now), item_id],
+            )?;
+        }
+
+        drop(conn);
+
+        self.publish_updated(item_id);
+        Ok(())
+    }
+
+    /// The `app_state` value stored under `key`
+    pub fn get_app_state(&self, key: &str) -> Result<Option<String>> {
+        let conn = self.reader();
+        conn.query_row(
+            "SELECT value FROM app_state WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    pub fn set_app_state(&self, key: &str, value: &str) -> Result<()> {
+        let conn = self.writer();
+        conn.execute(
+            "INSERT INTO app_state (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_symbol_use(&self, symbol: &str, hash: &str) -> Result<()> {
+        let conn = self.writer();
+
+        conn.execute(
+            r#"
+            INSERT INTO symbol_usage (symbol, hash, count, last_used_at) VALUES (?1, ?2, 1, ?3)
+            ON CONFLICT(symbol) DO UPDATE SET
+                hash = excluded.hash,
+                count = count + 1,
+                last_used_at = excluded.last_used_at
+            "#,
+            params![symbol, hash, Utc::now().to_rfc3339()],
+        )?;
+
+        Ok(())
+    }
+
+    /// Most pasted symbols, ties broken by most recent use
+    pub fn get_frequent_symbols(&self, limit: u32) -> Result<Vec<FrequentSymbol>> {
+        let conn = self.reader();
+
+        let mut stmt = conn.prepare_cached(
+            r#"
+            SELECT s.symbol, s.count, s.last_used_at,
+                (SELECT id FROM clipboard_items
+                    WHERE hash = s.hash AND deleted_at IS NULL
+                    ORDER BY created_at DESC LIMIT 1)
+            FROM symbol_usage s
+            ORDER BY s.count DESC, s.last_used_at DESC
+            LIMIT ?1
+            "#,
+        )?;
+
+        let symbols = stmt
+            .query_map(params![limit], |row| {
+                let last_used_at: String = row.get(2)?;
+                Ok(FrequentSymbol {
+                    symbol: row.get(0)?,
+                    count: row.get(1)?,
+                    last_used_at: parse_datetime(&last_used_at),
+                    item_id: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(symbols)
+    }
+
+    /// Pastes since `since` of items still in history, as (item id, app, time)
+    pub fn get_usage_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<(String, Option<String>, DateTime<Utc>)>> {
+        let conn = self.reader();
+
+        let mut stmt = conn.prepare_cached(
+            r#"
+            SELECT item_usage.item_id, item_usage.app_id, item_usage.used_at
+            FROM item_usage
+            JOIN clipboard_items ON clipboard_items.id = item_usage.item_id
+            WHERE item_usage.used_at >= ?1 AND clipboard_items.deleted_at IS NULL
+            "#,
+        )?;
+
+        let rows = stmt
+            .query_map(params![since.to_rfc3339()], |row| {
+                let used_at: String = row.get(2)?;
+                Ok((row.get(0)?, row.get(1)?, parse_datetime(&used_at)))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(rows)
+    }
+
+    /// Most recent items copied from `app_id`
+    pub fn get_items_from_app(&self, app_id: &str, limit: u32) -> Result<Vec<ClipboardItem>> {
+        let conn = self.reader();
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM clipboard_items WHERE source_app = ?1 AND deleted_at IS NULL ORDER BY created_at DESC LIMIT ?2",
+            ITEM_COLUMNS
+        ))?;
+
+        let items = stmt
+            .query_map(params![app_id, limit], row_to_item)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(items)
+    }
+
+    pub fn set_item_title(&self, id: &str, title: Option<&str>) -> Result<()> {
+        let conn = self.writer();
+
+        conn.execute(
+            "UPDATE clipboard_items SET title = ?1 WHERE id = ?2",
+            params![title, id],
+        )?;
+
+        drop(conn);
+
+        self.publish_updated(id);
+        Ok(())
+    }
+
+    pub fn set_item_metadata(&self, id: &str, metadata: &serde_json::Value) -> Result<()> {
+        let conn = self.writer();
+
+        conn.execute(
+            "UPDATE clipboard_items SET metadata = ?1 WHERE id = ?2",
+            params![metadata.to_string(), id],
+        )?;
+
+        drop(conn);
+
+        self.publish_updated(id);
         Ok(())
     }
 
     pub fn get_last_hash(&self) -> Result<Option<String>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.reader();
 
         let result: Option<String> = conn
             .query_row(
-                "SELECT hash FROM clipboard_items ORDER BY created_at DESC LIMIT 1",
+                "SELECT hash FROM clipboard_items WHERE deleted_at IS NULL ORDER BY created_at DESC LIMIT 1",
                 [],
                 |row| row.get(0),
             )
@@ -132,230 +1453,655 @@ impl Database {
         Ok(result)
     }
 
+    /// One page of history, pinned items first and then in `sort` order.
+    /// Pages are keyset-paginated on (is_pinned, sort keys, id), so fetching
+    /// deep pages costs the same as the first.
     pub fn get_items(
         &self,
         limit: u32,
-        offset: u32,
-        search: Option<&str>,
-        collection_id: Option<&str>,
-    ) -> Result<Vec<ClipboardItem>> {
-        let conn = self.conn.lock().unwrap();
-
-        let mut query = String::from(
-            r#"
-            SELECT id, content_type, content, preview, hash, is_pinned, collection_id, created_at, expires_at
-            FROM clipboard_items
-            WHERE 1=1
-            "#,
-        );
+        cursor: Option<&ItemCursor>,
+        filter: &ItemFilter,
+        sort: ItemSort,
+        include_archive: bool,
+    ) -> Result<ItemPage<ClipboardItem>> {
+        self.history_page(
+            (ITEM_COLUMNS, ITEM_COLUMN_COUNT),
+            row_to_item,
+            limit,
+            cursor,
+            filter,
+            sort,
+            include_archive,
+        )
+    }
 
-        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    /// Like `get_items`, but without item content
+    pub fn get_items_meta(
+        &self,
+        limit: u32,
+        cursor: Option<&ItemCursor>,
+        filter: &ItemFilter,
+        sort: ItemSort,
+        include_archive: bool,
+    ) -> Result<ItemPage<ItemMeta>> {
+        self.history_page(
+            (ITEM_META_COLUMNS, ITEM_META_COLUMN_COUNT),
+            row_to_item_meta,
+            limit,
+            cursor,
+            filter,
+            sort,
+            include_archive,
+        )
+    }
 
-        if let Some(s) = search {
-            query.push_str(" AND (content LIKE ?1 OR preview LIKE ?1)");
-            params_vec.push(Box::new(format!("%{}%", s)));
+    /// `columns` is a column list and its length; whether the row is
+    /// archived and the values of every sort key are selected after it
+    #[allow(clippy::too_many_arguments)]
+    fn history_page<T>(
+        &self,
+        (columns, column_count): (&str, usize),
+        map_row: fn(&rusqlite::Row) -> Result<T>,
+        limit: u32,
+        cursor: Option<&ItemCursor>,
+        filter: &ItemFilter,
+        sort: ItemSort,
+        include_archive: bool,
+    ) -> Result<ItemPage<T>> {
+        let conn = self.reader();
+
+        let source = if include_archive {
+            format!(
+                "(SELECT {cols}, frecency, 0 AS archived FROM clipboard_items UNION ALL SELECT {cols}, frecency, 1 FROM archived_items)",
+                cols = ITEM_COLUMNS
+            )
+        } else {
+            "clipboard_items".to_string()
+        };
+        let archived = if include_archive { "archived" } else { "0" };
+
+        // Full ordering: pinned first, the sort's keys, then id to break ties
+        let keys = sort.keys();
+        let id_descending = !matches!(keys.last(), Some((_, false)));
+        let order: Vec<(&str, bool)> = std::iter::once(("is_pinned", true))
+            .chain(keys.iter().copied())
+            .chain(std::iter::once(("id", id_descending)))
+            .collect();
+
+        let mut clause = WhereClause::default();
+        clause.push("deleted_at IS NULL".to_string());
+        clause.push_filter(filter);
+
+        // Rows after the cursor in that ordering. Keys can run in different
+        // directions, so this is spelled out rather than a row-value compare.
+        if let Some(cursor) = cursor {
+            // A cursor from another sort would silently restart at the top
+            if cursor.keys.len() != keys.len() {
+                return Err(rusqlite::Error::ToSqlConversionFailure(
+                    "Cursor doesn't match the sort order; start again without one".into(),
+                ));
+            }
+            let values: Vec<SqlValue> = std::iter::once(SqlValue::Integer(cursor.is_pinned as i64))
+                .chain(cursor.keys.iter().map(json_to_sql))
+                .chain(std::iter::once(SqlValue::Text(cursor.id.clone())))
+                .collect();
+            let bound: Vec<String> = values.into_iter().map(|v| clause.bind(v)).collect();
+
+            let alternatives: Vec<String> = (0..order.len())
+                .map(|i| {
+                    let mut parts: Vec<String> = (0..i)
+                        .map(|j| format!("{} = {}", order[j].0, bound[j]))
+                        .collect();
+                    let op = if order[i].1 { "<" } else { ">" };
+                    parts.push(format!("{} {} {}", order[i].0, op, bound[i]));
+                    format!("({})", parts.join(" AND "))
+                })
+                .collect();
+            clause.push(format!("({})", alternatives.join(" OR ")));
         }
 
-        if let Some(cid) = collection_id {
-            let param_num = params_vec.len() + 1;
-            query.push_str(&format!(" AND collection_id = ?{}", param_num));
-            params_vec.push(Box::new(cid.to_string()));
+        let order_exprs: Vec<&str> = order.iter().map(|(expr, _)| *expr).collect();
+        let order_by: Vec<String> = order
+            .iter()
+            .map(|(expr, descending)| {
+                format!("{} {}", expr, if *descending { "DESC" } else { "ASC" })
+            })
+            .collect();
+        let query = format!(
+            "SELECT {}, {}, {} FROM {}{} ORDER BY {} LIMIT {}",
+            columns,
+            archived,
+            order_exprs.join(", "),
+            source,
+            clause.sql(),
+            order_by.join(", "),
+            limit
+        );
+
+        let mut stmt = conn.prepare_cached(&query)?;
+
+        let rows = stmt
+            .query_map(clause.params().as_slice(), |row| {
+                let archived = row.get::<_, bool>(column_count)?;
+                let is_pinned = row.get::<_, bool>(column_count + 1)?;
+                let keys = (0..keys.len())
+                    .map(|i| row.get::<_, SqlValue>(column_count + 2 + i).map(sql_to_json))
+                    .collect::<Result<Vec<_>>>()?;
+                let id = row.get::<_, String>(column_count + 2 + keys.len())?;
+                Ok((map_row(row)?, archived, ItemCursor { is_pinned, keys, id }))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        let archived_ids = rows
+            .iter()
+            .filter(|(_, archived, _)| *archived)
+            .map(|(_, _, position)| position.id.clone())
+            .collect();
+
+        // A short page means there is nothing after it
+        let full_page = rows.len() as u32 == limit;
+        let mut items = Vec::with_capacity(rows.len());
+        let mut next_cursor = None;
+        for (item, _, position) in rows {
+            items.push(item);
+            next_cursor = Some(position);
         }
 
-        query.push_str(" ORDER BY is_pinned DESC, created_at DESC");
-        query.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset));
+        Ok(ItemPage {
+            items,
+            next_cursor: next_cursor.filter(|_| full_page),
+            archived_ids,
+        })
+    }
 
-        let mut stmt = conn.prepare(&query)?;
+    /// Items within a byte-size range, ordered by size
+    pub fn get_items_by_size(
+        &self,
+        min_bytes: Option<i64>,
+        max_bytes: Option<i64>,
+        largest_first: bool,
+        limit: u32,
+    ) -> Result<Vec<ClipboardItem>> {
+        let conn = self.reader();
 
-        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM clipboard_items WHERE byte_size >= ?1 AND byte_size <= ?2 AND deleted_at IS NULL ORDER BY byte_size {} LIMIT ?3",
+            ITEM_COLUMNS,
+            if largest_first { "DESC" } else { "ASC" }
+        ))?;
 
         let items = stmt
-            .query_map(params_refs.as_slice(), |row| {
-                let created_str: String = row.get(7)?;
-                let expires_str: Option<String> = row.get(8)?;
-
-                Ok(ClipboardItem {
-                    id: row.get(0)?,
-                    content_type: row.get(1)?,
-                    content: row.get(2)?,
-                    preview: row.get(3)?,
-                    hash: row.get(4)?,
-                    is_pinned: row.get::<_, i32>(5)? != 0,
-                    collection_id: row.get(6)?,
-                    created_at: DateTime::parse_from_rfc3339(&created_str)
-                        .unwrap_or_else(|_| Utc::now().into())
-                        .with_timezone(&Utc),
-                    expires_at: expires_str.and_then(|s| {
-                        DateTime::parse_from_rfc3339(&s)
-                            .ok()
-                            .map(|dt| dt.with_timezone(&Utc))
-                    }),
-                })
-            })?
+            .query_map(
+                params![min_bytes.unwrap_or(0), max_bytes.unwrap_or(i64::MAX), limit],
+                row_to_item,
+            )?
             .collect::<Result<Vec<_>>>()?;
 
         Ok(items)
     }
 
     pub fn get_pinned_items(&self) -> Result<Vec<ClipboardItem>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.reader();
 
-        let mut stmt = conn.prepare(
-            r#"
-            SELECT id, content_type, content, preview, hash, is_pinned, collection_id, created_at, expires_at
-            FROM clipboard_items
-            WHERE is_pinned = 1
-            ORDER BY created_at DESC
-            "#,
-        )?;
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM clipboard_items WHERE is_pinned = 1 AND deleted_at IS NULL ORDER BY created_at DESC",
+            ITEM_COLUMNS
+        ))?;
 
         let items = stmt
-            .query_map([], |row| {
-                let created_str: String = row.get(7)?;
-                let expires_str: Option<String> = row.get(8)?;
-
-                Ok(ClipboardItem {
-                    id: row.get(0)?,
-                    content_type: row.get(1)?,
-                    content: row.get(2)?,
-                    preview: row.get(3)?,
-                    hash: row.get(4)?,
-                    is_pinned: row.get::<_, i32>(5)? != 0,
-                    collection_id: row.get(6)?,
-                    created_at: DateTime::parse_from_rfc3339(&created_str)
-                        .unwrap_or_else(|_| Utc::now().into())
-                        .with_timezone(&Utc),
-                    expires_at: expires_str.and_then(|s| {
-                        DateTime::parse_from_rfc3339(&s)
-                            .ok()
-                            .map(|dt| dt.with_timezone(&Utc))
-                    }),
-                })
-            })?
+            .query_map([], row_to_item)?
             .collect::<Result<Vec<_>>>()?;
 
         Ok(items)
     }
 
+    /// Move an item to the trash. It disappears from history but can be
+    /// restored until the trash is emptied or purged.
     pub fn delete_item(&self, id: &str) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM clipboard_items WHERE id = ?1", params![id])?;
+        let conn = self.writer();
+        let trashed = conn.execute(
+            "UPDATE clipboard_items SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+            params![Utc::now().to_rfc3339(), id],
+        )?;
+        drop(conn);
+
+        if trashed > 0 {
+            self.publish_deleted(vec![id.to_string()]);
+        }
         Ok(())
     }
 
+    /// Take an item back out of the trash
+    pub fn restore_item(&self, id: &str) -> Result<Option<ClipboardItem>> {
+        let conn = self.writer();
+        let restored = conn.execute(
+            "UPDATE clipboard_items SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+            params![id],
+        )?;
+        drop(conn);
+
+        if restored == 0 {
+            return Ok(None);
+        }
+        let item = self.get_item(id)?;
+        if let Some(item) = &item {
+            self.events.publish(ItemEvent::Added(item.clone()));
+        }
+        Ok(item)
+    }
+
+    /// Trashed items, most recently deleted first
+    pub fn get_trash(&self) -> Result<Vec<ClipboardItem>> {
+        let conn = self.reader();
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM clipboard_items WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+            ITEM_COLUMNS
+        ))?;
+
+        let items = stmt
+            .query_map([], row_to_item)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(items)
+    }
+
+    /// Permanently delete trashed items, all of them or only those trashed
+    /// before `before`. Returns how many were removed.
+    pub fn purge_trash(&self, before: Option<DateTime<Utc>>) -> Result<u32> {
+        let conn = self.writer();
+        let tx = conn.unchecked_transaction()?;
+
+        let purged = tx
+            .prepare(
+                "DELETE FROM clipboard_items WHERE deleted_at IS NOT NULL AND (?1 IS NULL OR deleted_at < ?1) RETURNING id",
+            )?
+            .query_map(params![before.map(|dt| dt.to_rfc3339())], |row| row.get(0))?
+            .collect::<Result<Vec<String>>>()?;
+
+        for id in &purged {
+            Self::delete_related_rows(&tx, id, false)?;
+        }
+
+        tx.commit()?;
+        Ok(purged.len() as u32)
+    }
+
     pub fn pin_item(&self, id: &str) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer();
         conn.execute(
             "UPDATE clipboard_items SET is_pinned = 1 WHERE id = ?1",
             params![id],
         )?;
+        drop(conn);
+
+        self.publish_updated(id);
         Ok(())
     }
 
     pub fn unpin_item(&self, id: &str) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer();
         conn.execute(
             "UPDATE clipboard_items SET is_pinned = 0 WHERE id = ?1",
             params![id],
         )?;
+        drop(conn);
+
+        self.publish_updated(id);
         Ok(())
     }
 
+    /// Merge items that share a hash into the earliest copy, which takes the
+    /// pin state, tags, boards, links and paste history of the others
+    pub fn dedupe_history(&self) -> Result<DedupeReport> {
+        let conn = self.writer();
+
+        let rows = conn
+            .prepare(
+                r#"
+                SELECT id, hash FROM clipboard_items
+                WHERE deleted_at IS NULL AND hash IN (
+                    SELECT hash FROM clipboard_items
+                    WHERE deleted_at IS NULL
+                    GROUP BY hash HAVING COUNT(*) > 1
+                )
+                ORDER BY hash, created_at ASC, id ASC
+                "#,
+            )?
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>>>()?;
+
+        // (kept, duplicate) pairs; the first row of each hash is the earliest
+        let mut merges: Vec<(String, String)> = Vec::new();
+        let mut kept: Vec<String> = Vec::new();
+        let mut current_hash: Option<String> = None;
+        for (id, hash) in rows {
+            if current_hash.as_ref() == Some(&hash) {
+                merges.push((kept.last().unwrap().clone(), id));
+            } else {
+                kept.push(id);
+                current_hash = Some(hash);
+            }
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        for (keep, dup) in &merges {
+            tx.execute(
+                r#"
+                UPDATE clipboard_items SET
+                    is_pinned = MAX(clipboard_items.is_pinned, d.is_pinned),
+                    collection_id = COALESCE(clipboard_items.collection_id, d.collection_id),
+                    title = COALESCE(clipboard_items.title, d.title),
+                    last_used_at = MAX(
+                        COALESCE(clipboard_items.last_used_at, d.last_used_at),
+                        COALESCE(d.last_used_at, clipboard_items.last_used_at)
+                    ),
+                    times_pasted = clipboard_items.times_pasted + d.times_pasted,
+                    frecency = MAX(clipboard_items.frecency, d.frecency)
+                FROM (SELECT * FROM clipboard_items WHERE id = ?2) AS d
+                WHERE clipboard_items.id = ?1
+                "#,
+                params![keep, dup],
+            )?;
+            tx.execute(
+                "INSERT OR IGNORE INTO item_tags (item_id, tag_id) SELECT ?1, tag_id FROM item_tags WHERE item_id = ?2",
+                params![keep, dup],
+            )?;
+            tx.execute(
+                "INSERT OR IGNORE INTO board_items (board_id, item_id, position) SELECT board_id, ?1, position FROM board_items WHERE item_id = ?2",
+                params![keep, dup],
+            )?;
+            tx.execute(
+                "UPDATE OR IGNORE item_links SET source_id = ?1 WHERE source_id = ?2 AND target_id != ?1",
+                params![keep, dup],
+            )?;
+            tx.execute(
+                "UPDATE OR IGNORE item_links SET target_id = ?1 WHERE target_id = ?2 AND source_id != ?1",
+                params![keep, dup],
+            )?;
+            tx.execute("UPDATE item_usage SET item_id = ?1 WHERE item_id = ?2", params![keep, dup])?;
+            tx.execute("UPDATE item_versions SET item_id = ?1 WHERE item_id = ?2", params![keep, dup])?;
+
+            tx.execute("DELETE FROM item_tags WHERE item_id = ?1", params![dup])?;
+            tx.execute("DELETE FROM board_items WHERE item_id = ?1", params![dup])?;
+            tx.execute(
+                "DELETE FROM item_links WHERE source_id = ?1 OR target_id = ?1",
+                params![dup],
+            )?;
+            tx.execute("DELETE FROM item_embeddings WHERE item_id = ?1", params![dup])?;
+            tx.execute("DELETE FROM clipboard_items WHERE id = ?1", params![dup])?;
+        }
+        tx.commit()?;
+        drop(conn);
+
+        let report = DedupeReport {
+            groups: kept.len() as u32,
+            merged: merges.len() as u32,
+        };
+        self.publish_deleted(merges.into_iter().map(|(_, dup)| dup).collect());
+        for id in &kept {
+            self.publish_updated(id);
+        }
+        Ok(report)
+    }
+
     pub fn clear_history(&self) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM clipboard_items WHERE is_pinned = 0", [])?;
+        let conn = self.writer();
+        // Pinned, board and protected-collection items survive a clear
+        let deleted = conn
+            .prepare("SELECT id FROM clipboard_items WHERE id NOT IN (SELECT id FROM protected_items)")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>>>()?;
+        Self::evict(&conn, &deleted, false)?;
+        drop(conn);
+
+        self.publish_deleted(deleted);
         Ok(())
     }
 
+    /// Just the content of an item, for lists built from `get_items_meta`
+    pub fn get_item_content(&self, id: &str) -> Result<Option<String>> {
+        let conn = self.reader();
+
+        conn.prepare_cached("SELECT content FROM clipboard_items WHERE id = ?1")?
+            .query_row(params![id], |row| row.get(0))
+            .optional()
+    }
+
     pub fn get_item(&self, id: &str) -> Result<Option<ClipboardItem>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.reader();
 
-        let result = conn.query_row(
-            r#"
-            SELECT id, content_type, content, preview, hash, is_pinned, collection_id, created_at, expires_at
-            FROM clipboard_items
-            WHERE id = ?1
-            "#,
-            params![id],
-            |row| {
-                let created_str: String = row.get(7)?;
-                let expires_str: Option<String> = row.get(8)?;
+        let result = conn
+            .prepare_cached(&format!("SELECT {} FROM clipboard_items WHERE id = ?1", ITEM_COLUMNS))?
+            .query_row(params![id], row_to_item);
 
-                Ok(ClipboardItem {
-                    id: row.get(0)?,
-                    content_type: row.get(1)?,
-                    content: row.get(2)?,
-                    preview: row.get(3)?,
-                    hash: row.get(4)?,
-                    is_pinned: row.get::<_, i32>(5)? != 0,
-                    collection_id: row.get(6)?,
-                    created_at: DateTime::parse_from_rfc3339(&created_str)
-                        .unwrap_or_else(|_| Utc::now().into())
-                        .with_timezone(&Utc),
-                    expires_at: expires_str.and_then(|s| {
-                        DateTime::parse_from_rfc3339(&s)
-                            .ok()
-                            .map(|dt| dt.with_timezone(&Utc))
-                    }),
-                })
-            },
-        );
+        match result {
+            Ok(item) => Ok(Some(item)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Evict all but the newest `limit` unprotected items, archiving them
+    /// instead of deleting when `archive` is set
+    pub fn enforce_limit(&self, limit: u32, archive: bool) -> Result<()> {
+        let conn = self.writer();
+
+        let evicted = conn
+            .prepare(
+                r#"
+                SELECT id FROM clipboard_items
+                WHERE id NOT IN (
+                    SELECT id FROM protected_items
+                    UNION ALL
+                    SELECT id FROM (
+                        SELECT id FROM clipboard_items
+                        WHERE id NOT IN (SELECT id FROM protected_items)
+                            AND deleted_at IS NULL
+                        ORDER BY created_at DESC
+                        LIMIT ?1
+                    )
+                )
+                AND deleted_at IS NULL
+                "#,
+            )?
+            .query_map(params![limit], |row| row.get(0))?
+            .collect::<Result<Vec<String>>>()?;
+        Self::evict(&conn, &evicted, archive)?;
+        drop(conn);
+
+        self.publish_deleted(evicted);
+        Ok(())
+    }
+
+    /// Evict the oldest unprotected items until history content fits in
+    /// `max_bytes`. Protected items count toward the budget but are never
+    /// evicted, so history can stay over it if they alone exceed it.
+    pub fn enforce_size_limit(&self, max_bytes: u64, archive: bool) -> Result<u32> {
+        let conn = self.writer();
+
+        let evicted = conn
+            .prepare(
+                r#"
+                SELECT id FROM clipboard_items
+                WHERE id IN (
+                    SELECT id FROM (
+                        SELECT id, SUM(byte_size) OVER (
+                            ORDER BY created_at DESC, id DESC
+                        ) AS running_size
+                        FROM clipboard_items
+                        WHERE deleted_at IS NULL
+                            AND id NOT IN (SELECT id FROM protected_items)
+                    )
+                    WHERE running_size + (
+                        SELECT COALESCE(SUM(byte_size), 0) FROM clipboard_items
+                        WHERE deleted_at IS NULL
+                            AND id IN (SELECT id FROM protected_items)
+                    ) > ?1
+                )
+                "#,
+            )?
+            .query_map(params![max_bytes as i64], |row| row.get(0))?
+            .collect::<Result<Vec<String>>>()?;
+        Self::evict(&conn, &evicted, archive)?;
+        drop(conn);
+
+        let count = evicted.len() as u32;
+        self.publish_deleted(evicted);
+        Ok(count)
+    }
+
+    /// Evict unprotected items created before `cutoff`
+    pub fn enforce_max_age(&self, cutoff: DateTime<Utc>, archive: bool) -> Result<u32> {
+        let conn = self.writer();
+
+        let evicted = conn
+            .prepare(
+                r#"
+                SELECT id FROM clipboard_items
+                WHERE created_at < ?1
+                    AND deleted_at IS NULL
+                    AND id NOT IN (SELECT id FROM protected_items)
+                "#,
+            )?
+            .query_map(params![cutoff.to_rfc3339()], |row| row.get(0))?
+            .collect::<Result<Vec<String>>>()?;
+        Self::evict(&conn, &evicted, archive)?;
+        drop(conn);
+
+        let count = evicted.len() as u32;
+        self.publish_deleted(evicted);
+        Ok(count)
+    }
+
+    /// Remove items and their related rows, moving them to the archive when
+    /// `archive` is set. Images are always deleted, since they are what
+    /// makes history large.
+    fn evict(conn: &Connection, ids: &[String], archive: bool) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        {
+            let mut archive_stmt = tx.prepare_cached(&format!(
+                r#"
+                INSERT OR REPLACE INTO archived_items ({cols}, frecency, archived_at)
+                SELECT {cols}, frecency, ?2 FROM clipboard_items
+                WHERE id = ?1 AND content_type != 'image'
+                "#,
+                cols = ITEM_COLUMNS
+            ))?;
+            let mut delete_stmt = tx.prepare_cached("DELETE FROM clipboard_items WHERE id = ?1")?;
+            let now = Utc::now().to_rfc3339();
+
+            for id in ids {
+                // Archived items keep their tags so `tag:` still finds them
+                // when searching the archive
+                let archived = archive && archive_stmt.execute(params![id, now])? > 0;
+                Self::delete_related_rows(&tx, id, archived)?;
+                delete_stmt.execute(params![id])?;
+            }
+        }
+        tx.commit()
+    }
 
-        match result {
-            Ok(item) => Ok(Some(item)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e),
+    /// Delete the rows that refer to an item leaving history. Foreign keys
+    /// aren't enforced, so nothing cascades on its own.
+    fn delete_related_rows(conn: &Connection, id: &str, keep_tags: bool) -> Result<()> {
+        if !keep_tags {
+            conn.execute("DELETE FROM item_tags WHERE item_id = ?1", params![id])?;
         }
+        conn.execute("DELETE FROM board_items WHERE item_id = ?1", params![id])?;
+        conn.execute("DELETE FROM item_embeddings WHERE item_id = ?1", params![id])?;
+        conn.execute("DELETE FROM item_usage WHERE item_id = ?1", params![id])?;
+        conn.execute("DELETE FROM item_versions WHERE item_id = ?1", params![id])?;
+        conn.execute(
+            "DELETE FROM item_links WHERE source_id = ?1 OR target_id = ?1",
+            params![id],
+        )?;
+        Ok(())
     }
 
-    pub fn enforce_limit(&self, limit: u32) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+    /// Move an archived item back into history
+    pub fn unarchive_item(&self, id: &str) -> Result<Option<ClipboardItem>> {
+        let conn = self.writer();
 
-        conn.execute(
-            r#"
-            DELETE FROM clipboard_items
-            WHERE id NOT IN (
-                SELECT id FROM clipboard_items
-                WHERE is_pinned = 1
-                UNION ALL
-                SELECT id FROM (
-                    SELECT id FROM clipboard_items
-                    WHERE is_pinned = 0
-                    ORDER BY created_at DESC
-                    LIMIT ?1
-                )
-            )
-            "#,
-            params![limit],
+        let tx = conn.unchecked_transaction()?;
+        let moved = tx.execute(
+            &format!(
+                "INSERT INTO clipboard_items ({cols}, frecency) SELECT {cols}, frecency FROM archived_items WHERE id = ?1",
+                cols = ITEM_COLUMNS
+            ),
+            params![id],
         )?;
+        tx.execute("DELETE FROM archived_items WHERE id = ?1", params![id])?;
+        tx.commit()?;
+        drop(conn);
 
-        Ok(())
+        if moved == 0 {
+            return Ok(None);
+        }
+        let item = self.get_item(id)?;
+        if let Some(item) = &item {
+            self.events.publish(ItemEvent::Added(item.clone()));
+        }
+        Ok(item)
+    }
+
+    /// Permanently delete archived items, optionally only those archived
+    /// before `cutoff`
+    pub fn purge_archive(&self, cutoff: Option<DateTime<Utc>>) -> Result<u32> {
+        let conn = self.writer();
+        let cutoff = cutoff.map(|c| c.to_rfc3339());
+        let tx = conn.unchecked_transaction()?;
+
+        let purged = tx
+            .prepare(
+                "DELETE FROM archived_items WHERE ?1 IS NULL OR archived_at < ?1 RETURNING id",
+            )?
+            .query_map(params![cutoff], |row| row.get(0))?
+            .collect::<Result<Vec<String>>>()?;
+
+        for id in &purged {
+            tx.execute("DELETE FROM item_tags WHERE item_id = ?1", params![id])?;
+        }
+
+        tx.commit()?;
+        Ok(purged.len() as u32)
     }
 
-    #[allow(dead_code)]
     pub fn cleanup_expired(&self) -> Result<u32> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer();
         let now = Utc::now().to_rfc3339();
 
-        let deleted = conn.execute(
-            "DELETE FROM clipboard_items WHERE expires_at IS NOT NULL AND expires_at < ?1",
-            params![now],
-        )?;
-
-        Ok(deleted as u32)
+        let deleted = conn
+            .prepare(
+                r#"
+                SELECT id FROM clipboard_items
+                WHERE expires_at IS NOT NULL AND expires_at < ?1
+                    AND id NOT IN (SELECT id FROM protected_items)
+                "#,
+            )?
+            .query_map(params![now], |row| row.get(0))?
+            .collect::<Result<Vec<String>>>()?;
+        Self::evict(&conn, &deleted, false)?;
+        drop(conn);
+
+        let count = deleted.len() as u32;
+        self.publish_deleted(deleted);
+        Ok(count)
     }
 
     // Collection methods
     pub fn create_collection(&self, collection: &Collection) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer();
 
         conn.execute(
-            "INSERT INTO collections (id, name, color, created_at) VALUES (?1, ?2, ?3, ?4)",
+            "INSERT INTO collections (id, name, color, created_at, protected, is_template) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
             params![
                 collection.id,
                 collection.name,
                 collection.color,
                 collection.created_at.to_rfc3339(),
+                collection.protected as i32,
+                collection.is_template as i32,
             ],
         )?;
 
@@ -363,9 +2109,11 @@ impl Database {
     }
 
     pub fn get_collections(&self) -> Result<Vec<Collection>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.reader();
 
-        let mut stmt = conn.prepare("SELECT id, name, color, created_at FROM collections ORDER BY name")?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, name, color, created_at, protected, is_template FROM collections ORDER BY name",
+        )?;
 
         let collections = stmt
             .query_map([], |row| {
@@ -378,6 +2126,8 @@ impl Database {
                     created_at: DateTime::parse_from_rfc3339(&created_str)
                         .unwrap_or_else(|_| Utc::now().into())
                         .with_timezone(&Utc),
+                    protected: row.get::<_, i32>(4)? != 0,
+                    is_template: row.get::<_, i32>(5)? != 0,
                 })
             })?
             .collect::<Result<Vec<_>>>()?;
@@ -386,21 +2136,27 @@ impl Database {
     }
 
     pub fn delete_collection(&self, id: &str) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer();
 
         // Remove collection reference from items
-        conn.execute(
-            "UPDATE clipboard_items SET collection_id = NULL WHERE collection_id = ?1",
-            params![id],
-        )?;
+        let updated = conn
+            .prepare(
+                "UPDATE clipboard_items SET collection_id = NULL WHERE collection_id = ?1 RETURNING id",
+            )?
+            .query_map(params![id], |row| row.get(0))?
+            .collect::<Result<Vec<String>>>()?;
 
         conn.execute("DELETE FROM collections WHERE id = ?1", params![id])?;
+        drop(conn);
 
+        for item_id in updated {
+            self.publish_updated(&item_id);
+        }
         Ok(())
     }
 
     pub fn update_collection(&self, id: &str, name: &str, color: &str) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer();
 
         conn.execute(
             "UPDATE collections SET name = ?1, color = ?2 WHERE id = ?3",
@@ -410,31 +2166,153 @@ impl Database {
         Ok(())
     }
 
+    pub fn set_collection_protected(&self, id: &str, protected: bool) -> Result<()> {
+        let conn = self.writer();
+
+        conn.execute(
+            "UPDATE collections SET protected = ?1 WHERE id = ?2",
+            params![protected as i32, id],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn set_collection_template(&self, id: &str, is_template: bool) -> Result<()> {
+        let conn = self.writer();
+
+        conn.execute(
+            "UPDATE collections SET is_template = ?1 WHERE id = ?2",
+            params![is_template as i32, id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Whether the item sits in a template collection
+    pub fn is_template_item(&self, item_id: &str) -> Result<bool> {
+        let conn = self.reader();
+
+        conn.prepare_cached(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM clipboard_items i
+                JOIN collections c ON c.id = i.collection_id
+                WHERE i.id = ?1 AND c.is_template = 1
+            )
+            "#,
+        )?
+        .query_row(params![item_id], |row| row.get(0))
+    }
+
     pub fn move_item_to_collection(&self, item_id: &str, collection_id: Option<&str>) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer();
 
         conn.execute(
             "UPDATE clipboard_items SET collection_id = ?1 WHERE id = ?2",
             params![collection_id, item_id],
         )?;
 
+        drop(conn);
+
+        self.publish_updated(item_id);
         Ok(())
     }
 
+    // Bulk operations for multi-select: one transaction per call, so the
+    // whole selection changes or none of it does
+
+    /// Run `sql` for each id (`?1`) with `value` (`?2`) in one transaction,
+    /// returning the ids it changed
+    fn update_each(
+        &self,
+        sql: &str,
+        ids: &[String],
+        value: &dyn rusqlite::ToSql,
+    ) -> Result<Vec<String>> {
+        let conn = self.writer();
+        let tx = conn.unchecked_transaction()?;
+        let mut changed = Vec::new();
+        {
+            let mut stmt = tx.prepare(sql)?;
+            for id in ids {
+                if stmt.execute(params![id, value])? > 0 {
+                    changed.push(id.clone());
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(changed)
+    }
+
+    /// Move items to the trash
+    pub fn delete_items(&self, ids: &[String]) -> Result<u32> {
+        let trashed = self.update_each(
+            "UPDATE clipboard_items SET deleted_at = ?2 WHERE id = ?1 AND deleted_at IS NULL",
+            ids,
+            &Utc::now().to_rfc3339(),
+        )?;
+
+        let count = trashed.len() as u32;
+        self.publish_deleted(trashed);
+        Ok(count)
+    }
+
+    pub fn pin_items(&self, ids: &[String], pinned: bool) -> Result<u32> {
+        let changed = self.update_each(
+            "UPDATE clipboard_items SET is_pinned = ?2 WHERE id = ?1 AND is_pinned != ?2",
+            ids,
+            &pinned,
+        )?;
+
+        for id in &changed {
+            self.publish_updated(id);
+        }
+        Ok(changed.len() as u32)
+    }
+
+    pub fn move_items_to_collection(
+        &self,
+        ids: &[String],
+        collection_id: Option<&str>,
+    ) -> Result<u32> {
+        let changed = self.update_each(
+            "UPDATE clipboard_items SET collection_id = ?2 WHERE id = ?1 AND collection_id IS NOT ?2",
+            ids,
+            &collection_id,
+        )?;
+
+        for id in &changed {
+            self.publish_updated(id);
+        }
+        Ok(changed.len() as u32)
+    }
+
+    pub fn tag_items(&self, ids: &[String], tag_id: &str) -> Result<u32> {
+        let tagged = self.update_each(
+            "INSERT OR IGNORE INTO item_tags (item_id, tag_id) VALUES (?1, ?2)",
+            ids,
+            &tag_id,
+        )?;
+        Ok(tagged.len() as u32)
+    }
+
     pub fn set_item_expiration(&self, item_id: &str, expires_at: Option<DateTime<Utc>>) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer();
 
         conn.execute(
             "UPDATE clipboard_items SET expires_at = ?1 WHERE id = ?2",
             params![expires_at.map(|dt| dt.to_rfc3339()), item_id],
         )?;
 
+        drop(conn);
+
+        self.publish_updated(item_id);
         Ok(())
     }
 
     // Tag methods
     pub fn create_tag(&self, tag: &Tag) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer();
 
         conn.execute(
             "INSERT OR IGNORE INTO tags (id, name) VALUES (?1, ?2)",
@@ -445,9 +2323,9 @@ impl Database {
     }
 
     pub fn get_tags(&self) -> Result<Vec<Tag>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.reader();
 
-        let mut stmt = conn.prepare("SELECT id, name FROM tags ORDER BY name")?;
+        let mut stmt = conn.prepare_cached("SELECT id, name FROM tags ORDER BY name")?;
 
         let tags = stmt
             .query_map([], |row| {
@@ -462,7 +2340,7 @@ impl Database {
     }
 
     pub fn add_tag_to_item(&self, item_id: &str, tag_id: &str) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer();
 
         conn.execute(
             "INSERT OR IGNORE INTO item_tags (item_id, tag_id) VALUES (?1, ?2)",
@@ -473,7 +2351,7 @@ impl Database {
     }
 
     pub fn remove_tag_from_item(&self, item_id: &str, tag_id: &str) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.writer();
 
         conn.execute(
             "DELETE FROM item_tags WHERE item_id = ?1 AND tag_id = ?2",
@@ -484,9 +2362,9 @@ impl Database {
     }
 
     pub fn get_item_tags(&self, item_id: &str) -> Result<Vec<Tag>> {
-        let conn = self.conn.lock().unwrap();
+        let conn = self.reader();
 
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             r#"
             SELECT t.id, t.name
             FROM tags t
@@ -507,4 +2385,512 @@ impl Database {
 
         Ok(tags)
     }
+
+    /// Items derived from `parent_id`, newest first
+    pub fn get_derived_items(&self, parent_id: &str) -> Result<Vec<ClipboardItem>> {
+        let conn = self.reader();
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM clipboard_items WHERE json_extract(provenance, '$.parent_id') = ?1 AND deleted_at IS NULL ORDER BY created_at DESC",
+            ITEM_COLUMNS
+        ))?;
+
+        let items = stmt
+            .query_map(params![parent_id], row_to_item)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(items)
+    }
+
+    // Link methods
+    pub fn link_items(&self, source_id: &str, target_id: &str, relation: &str) -> Result<()> {
+        let conn = self.writer();
+
+        conn.execute(
+            "INSERT OR IGNORE INTO item_links (source_id, target_id, relation, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![source_id, target_id, relation, Utc::now().to_rfc3339()],
+        )?;
+
+        Ok(())
+    }
+
+    /// Remove links from `source_id` to `target_id`; all relations when `relation` is None
+    pub fn unlink_items(&self, source_id: &str, target_id: &str, relation: Option<&str>) -> Result<()> {
+        let conn = self.writer();
+
+        conn.execute(
+            "DELETE FROM item_links WHERE source_id = ?1 AND target_id = ?2 AND (?3 IS NULL OR relation = ?3)",
+            params![source_id, target_id, relation],
+        )?;
+
+        Ok(())
+    }
+
+    /// Items linked to `id` in either direction, newest link first. Links to
+    /// items that have since been pruned simply don't match.
+    pub fn get_related_items(&self, id: &str) -> Result<Vec<RelatedItem>> {
+        let conn = self.reader();
+
+        let mut stmt = conn.prepare_cached(&format!(
+            r#"
+            SELECT {}, l.relation, l.outgoing
+            FROM clipboard_items
+            JOIN (
+                SELECT target_id AS related_id, relation, 1 AS outgoing, created_at AS linked_at
+                FROM item_links WHERE source_id = ?1
+                UNION ALL
+                SELECT source_id, relation, 0, created_at
+                FROM item_links WHERE target_id = ?1
+            ) l ON l.related_id = clipboard_items.id
+            WHERE deleted_at IS NULL
+            ORDER BY l.linked_at DESC
+            "#,
+            ITEM_COLUMNS
+        ))?;
+
+        let related = stmt
+            .query_map(params![id], |row| {
+                Ok(RelatedItem {
+                    item: row_to_item(row)?,
+                    relation: row.get(ITEM_COLUMN_COUNT)?,
+                    outgoing: row.get::<_, i32>(ITEM_COLUMN_COUNT + 1)? != 0,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(related)
+    }
+
+    // Board methods
+    pub fn create_board(&self, board: &Board) -> Result<()> {
+        let conn = self.writer();
+
+        conn.execute(
+            "INSERT INTO boards (id, name, hotkey, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                board.id,
+                board.name,
+                board.hotkey,
+                board.created_at.to_rfc3339(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn get_boards(&self) -> Result<Vec<Board>> {
+        let conn = self.reader();
+
+        let mut stmt = conn.prepare_cached("SELECT id, name, hotkey, created_at FROM boards ORDER BY name")?;
+
+        let boards = stmt
+            .query_map([], |row| {
+                let created_str: String = row.get(3)?;
+
+                Ok(Board {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    hotkey: row.get(2)?,
+                    created_at: DateTime::parse_from_rfc3339(&created_str)
+                        .unwrap_or_else(|_| Utc::now().into())
+                        .with_timezone(&Utc),
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(boards)
+    }
+
+    pub fn update_board(&self, id: &str, name: &str, hotkey: Option<&str>) -> Result<()> {
+        let conn = self.writer();
+
+        conn.execute(
+            "UPDATE boards SET name = ?1, hotkey = ?2 WHERE id = ?3",
+            params![name, hotkey, id],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn delete_board(&self, id: &str) -> Result<()> {
+        let conn = self.writer();
+
+        conn.execute("DELETE FROM board_items WHERE board_id = ?1", params![id])?;
+        conn.execute("DELETE FROM boards WHERE id = ?1", params![id])?;
+
+        Ok(())
+    }
+
+    pub fn add_item_to_board(&self, board_id: &str, item_id: &str) -> Result<()> {
+        let conn = self.writer();
+
+        // New items go to the end of the board
+        conn.execute(
+            r#"
+            INSERT OR IGNORE INTO board_items (board_id, item_id, position)
+            VALUES (?1, ?2, (SELECT COALESCE(MAX(position), -1) + 1 FROM board_items WHERE board_id = ?1))
+            "#,
+            params![board_id, item_id],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn remove_item_from_board(&self, board_id: &str, item_id: &str) -> Result<()> {
+        let conn = self.writer();
+
+        conn.execute(
+            "DELETE FROM board_items WHERE board_id = ?1 AND item_id = ?2",
+            params![board_id, item_id],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn get_board_items(&self, board_id: &str) -> Result<Vec<ClipboardItem>> {
+        let conn = self.reader();
+
+        let mut stmt = conn.prepare_cached(&format!(
+            r#"
+            SELECT {}
+            FROM clipboard_items
+            JOIN board_items ON id = board_items.item_id
+            WHERE board_items.board_id = ?1 AND deleted_at IS NULL
+            ORDER BY board_items.position
+            "#,
+            ITEM_COLUMNS
+        ))?;
+
+        let items = stmt
+            .query_map(params![board_id], row_to_item)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(items)
+    }
+
+    /// Text items with no vector for `model` yet, newest first
+    #[cfg(feature = "semantic-search")]
+    pub fn items_missing_embeddings(&self, model: &str, limit: u32) -> Result<Vec<(String, String)>> {
+        let conn = self.reader();
+
+        let mut stmt = conn.prepare_cached(
+            r#"
+            SELECT id, COALESCE(title || char(10), '') || content
+            FROM clipboard_items
+            WHERE content_type != 'image' AND deleted_at IS NULL
+              AND id NOT IN (SELECT item_id FROM item_embeddings WHERE model = ?1)
+            ORDER BY created_at DESC
+            LIMIT ?2
+            "#,
+        )?;
+
+        let rows = stmt
+            .query_map(params![model, limit], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(rows)
+    }
+
+    #[cfg(feature = "semantic-search")]
+    pub fn store_embeddings(&self, model: &str, rows: &[(String, Vec<f32>)]) -> Result<()> {
+        let conn = self.writer();
+        let tx = conn.unchecked_transaction()?;
+
+        for (item_id, vector) in rows {
+            let bytes: Vec<u8> = vector.iter().flat_map(|x| x.to_le_bytes()).collect();
+            tx.execute(
+                "INSERT OR REPLACE INTO item_embeddings (item_id, model, vector) VALUES (?1, ?2, ?3)",
+                params![item_id, model, bytes],
+            )?;
+        }
+
+        tx.commit()
+    }
+
+    #[cfg(feature = "semantic-search")]
+    pub fn get_embeddings(&self, model: &str) -> Result<Vec<(String, Vec<f32>)>> {
+        let conn = self.reader();
+
+        let mut stmt = conn.prepare_cached(
+            r#"
+            SELECT item_embeddings.item_id, item_embeddings.vector
+            FROM item_embeddings
+            JOIN clipboard_items ON clipboard_items.id = item_embeddings.item_id
+            WHERE item_embeddings.model = ?1 AND clipboard_items.deleted_at IS NULL
+            "#,
+        )?;
+
+        let rows = stmt
+            .query_map(params![model], |row| {
+                let bytes: Vec<u8> = row.get(1)?;
+                let vector = bytes
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect();
+                Ok((row.get(0)?, vector))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(rows)
+    }
+
+    /// Drop vectors for items removed by limits, expiry or clearing history
+    #[cfg(feature = "semantic-search")]
+    pub fn prune_embeddings(&self) -> Result<()> {
+        let conn = self.writer();
+
+        conn.execute(
+            "DELETE FROM item_embeddings WHERE item_id NOT IN (SELECT id FROM clipboard_items)",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// Totals for the storage section of settings. Trashed items are
+    /// counted separately, since they still take up space until purged.
+    pub fn get_stats(&self, largest: u32) -> Result<DbStats> {
+        let conn = self.reader();
+
+        let by_type = conn
+            .prepare_cached(
+                r#"
+                SELECT content_type, COUNT(*), COALESCE(SUM(byte_size), 0)
+                FROM clipboard_items
+                WHERE deleted_at IS NULL
+                GROUP BY content_type
+                ORDER BY 3 DESC
+                "#,
+            )?
+            .query_map([], |row| {
+                Ok(TypeStats {
+                    content_type: row.get(0)?,
+                    count: row.get(1)?,
+                    bytes: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        let largest_items = conn
+            .prepare_cached(&format!(
+                "SELECT {} FROM clipboard_items WHERE deleted_at IS NULL ORDER BY byte_size DESC LIMIT ?1",
+                ITEM_META_COLUMNS
+            ))?
+            .query_map(params![largest], row_to_item_meta)?
+            .collect::<Result<Vec<_>>>()?;
+
+        let oldest: Option<String> = conn.query_row(
+            "SELECT MIN(created_at) FROM clipboard_items WHERE deleted_at IS NULL",
+            [],
+            |row| row.get(0),
+        )?;
+
+        let (trash_items, trash_bytes): (i64, i64) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(byte_size), 0) FROM clipboard_items WHERE deleted_at IS NOT NULL",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        let page_count: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+
+        Ok(DbStats {
+            total_items: by_type.iter().map(|t| t.count).sum(),
+            total_bytes: by_type.iter().map(|t| t.bytes).sum(),
+            by_type,
+            largest_items,
+            oldest_item_at: oldest.map(|s| parse_datetime(&s)),
+            trash_items,
+            trash_bytes,
+            file_bytes: page_count * page_size,
+        })
+    }
+
+    // Maintenance methods
+
+    /// Size of the database file, after folding the WAL back into it
+    fn file_size(conn: &Connection) -> Result<i64> {
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        let page_count: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+        Ok(page_count * page_size)
+    }
+
+    /// Rebuild the file to reclaim space left by deleted items
+    pub fn vacuum(&self) -> Result<MaintenanceReport> {
+        let conn = self.writer();
+        let started = std::time::Instant::now();
+
+        let bytes_before = Self::file_size(&conn)?;
+        conn.execute_batch("VACUUM")?;
+        let bytes_after = Self::file_size(&conn)?;
+
+        Ok(MaintenanceReport {
+            duration_ms: started.elapsed().as_millis() as u64,
+            bytes_before,
+            bytes_after,
+            reclaimed_bytes: (bytes_before - bytes_after).max(0),
+            problems: Vec::new(),
+        })
+    }
+
+    /// Run SQLite's full integrity check; `problems` is empty when healthy
+    pub fn integrity_check(&self) -> Result<MaintenanceReport> {
+        let conn = self.reader();
+        let started = std::time::Instant::now();
+
+        let problems = conn
+            .prepare("PRAGMA integrity_check")?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .filter(|line| line != "ok")
+            .collect();
+        // Checkpointing needs write access, so the size comes from the writer.
+        // Without a reader pool the check already ran on it; release it first.
+        drop(conn);
+        let size = Self::file_size(&self.writer())?;
+
+        Ok(MaintenanceReport {
+            duration_ms: started.elapsed().as_millis() as u64,
+            bytes_before: size,
+            bytes_after: size,
+            reclaimed_bytes: 0,
+            problems,
+        })
+    }
+
+    /// Refresh the query planner's statistics and checkpoint the WAL
+    pub fn optimize(&self) -> Result<MaintenanceReport> {
+        let conn = self.writer();
+        let started = std::time::Instant::now();
+
+        let bytes_before = Self::file_size(&conn)?;
+        conn.execute_batch("ANALYZE; PRAGMA optimize;")?;
+        let bytes_after = Self::file_size(&conn)?;
+
+        Ok(MaintenanceReport {
+            duration_ms: started.elapsed().as_millis() as u64,
+            bytes_before,
+            bytes_after,
+            reclaimed_bytes: (bytes_before - bytes_after).max(0),
+            problems: Vec::new(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DedupeReport {
+    /// Distinct contents that had duplicates
+    pub groups: u32,
+    /// Duplicate rows merged away
+    pub merged: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeStats {
+    pub content_type: String,
+    pub count: i64,
+    pub bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DbStats {
+    pub total_items: i64,
+    /// Content size of all items; `file_bytes` also includes indexes and free pages
+    pub total_bytes: i64,
+    pub by_type: Vec<TypeStats>,
+    pub largest_items: Vec<ItemMeta>,
+    pub oldest_item_at: Option<DateTime<Utc>>,
+    pub trash_items: i64,
+    pub trash_bytes: i64,
+    pub file_bytes: i64,
+}
+
+#[tauri::command]
+pub async fn get_db_stats(
+    db: tauri::State<'_, Database>,
+    largest: Option<u32>,
+) -> Result<DbStats, String> {
+    db.get_stats(largest.unwrap_or(10)).map_err(|e| e.to_string())
+}
+
+/// Outcome of a maintenance command, for showing in settings
+#[derive(Debug, Clone, Serialize)]
+pub struct MaintenanceReport {
+    pub duration_ms: u64,
+    pub bytes_before: i64,
+    pub bytes_after: i64,
+    pub reclaimed_bytes: i64,
+    /// Integrity check findings (empty for the other commands)
+    pub problems: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn db_vacuum(db: tauri::State<'_, Database>) -> Result<MaintenanceReport, String> {
+    tokio::task::block_in_place(|| db.vacuum()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn db_integrity_check(
+    db: tauri::State<'_, Database>,
+) -> Result<MaintenanceReport, String> {
+    tokio::task::block_in_place(|| db.integrity_check()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn db_optimize(db: tauri::State<'_, Database>) -> Result<MaintenanceReport, String> {
+    tokio::task::block_in_place(|| db.optimize()).map_err(|e| e.to_string())
+}
+
+/// For a frontend that loaded after `database-migration-failed` was emitted
+#[tauri::command]
+pub async fn get_migration_failure(
+    state: tauri::State<'_, MigrationFailureState>,
+) -> Result<Option<MigrationFailure>, String> {
+    Ok(state.0.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_item(content: &str) -> ClipboardItem {
+        ClipboardItem::new(
+            "text".to_string(),
+            content.to_string(),
+            content.to_string(),
+            format!("hash-{}", content),
+        )
+    }
+
+    #[test]
+    fn dedupe_merges_rows_with_the_same_hash() {
+        let db = Database::in_memory().unwrap();
+
+        let mut earliest = text_item("hello");
+        earliest.created_at = Utc::now() - chrono::Duration::minutes(5);
+        let mut duplicate = text_item("hello");
+        duplicate.is_pinned = true;
+        db.insert_item(&earliest).unwrap();
+        db.insert_item(&duplicate).unwrap();
+
+        let tag = Tag {
+            id: "tag-1".to_string(),
+            name: "work".to_string(),
+        };
+        db.create_tag(&tag).unwrap();
+        db.add_tag_to_item(&duplicate.id, &tag.id).unwrap();
+
+        let report = db.dedupe_history().unwrap();
+        assert_eq!(report.groups, 1);
+        assert_eq!(report.merged, 1);
+
+        assert!(db.get_item(&duplicate.id).unwrap().is_none());
+        let kept = db.get_item(&earliest.id).unwrap().unwrap();
+        assert!(kept.is_pinned);
+        let tags = db.get_item_tags(&earliest.id).unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].id, tag.id);
+    }
 }
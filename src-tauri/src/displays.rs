@@ -0,0 +1,139 @@
+//! Display-configuration change handling.
+//!
+//! Connected monitors are polled; when the set or their geometry changes,
+//! any window left on a display that no longer exists (or hanging off the
+//! edge of a smaller one) is moved and shrunk onto a real display.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Monitor, PhysicalPosition, PhysicalSize, Runtime};
+
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+/// Windows moved onto a display use at most this fraction of it
+const MAX_SCREEN_FRACTION: f64 = 0.9;
+
+/// Position, size and scale of every display, for change detection
+fn fingerprint(monitors: &[Monitor]) -> Vec<(i32, i32, u32, u32, u64)> {
+    monitors
+        .iter()
+        .map(|m| {
+            (
+                m.position().x,
+                m.position().y,
+                m.size().width,
+                m.size().height,
+                m.scale_factor().to_bits(),
+            )
+        })
+        .collect()
+}
+
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut last = app
+            .available_monitors()
+            .map(|m| fingerprint(&m))
+            .unwrap_or_default();
+
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+
+            let Ok(monitors) = app.available_monitors() else {
+                continue;
+            };
+            let current = fingerprint(&monitors);
+            if current == last || monitors.is_empty() {
+                continue;
+            }
+            last = current;
+
+            log::info!("Display configuration changed ({} displays)", monitors.len());
+            for window in app.webview_windows().values() {
+                if let Err(e) = keep_on_screen(&app, window, &monitors) {
+                    log::warn!("Failed to reposition window {}: {}", window.label(), e);
+                }
+            }
+            let _ = app.emit("displays-changed", monitors.len());
+        }
+    });
+}
+
+fn contains(monitor: &Monitor, x: i32, y: i32) -> bool {
+    let pos = monitor.position();
+    let size = monitor.size();
+    x >= pos.x && y >= pos.y && x < pos.x + size.width as i32 && y < pos.y + size.height as i32
+}
+
+fn keep_on_screen<R: Runtime>(
+    app: &AppHandle<R>,
+    window: &tauri::WebviewWindow<R>,
+    monitors: &[Monitor],
+) -> Result<(), String> {
+    let pos = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+
+    // A window whose center is on a display and which fits it is fine
+    let center_x = pos.x + size.width as i32 / 2;
+    let center_y = pos.y + size.height as i32 / 2;
+    let current = monitors.iter().find(|m| contains(m, center_x, center_y));
+    if let Some(monitor) = current {
+        if size.width <= monitor.size().width && size.height <= monitor.size().height {
+            return Ok(());
+        }
+    }
+
+    let target = match current {
+        Some(monitor) => monitor.clone(),
+        None => app
+            .primary_monitor()
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| monitors[0].clone()),
+    };
+
+    let max_width = (target.size().width as f64 * MAX_SCREEN_FRACTION) as u32;
+    let max_height = (target.size().height as f64 * MAX_SCREEN_FRACTION) as u32;
+    let new_size = PhysicalSize::new(size.width.min(max_width), size.height.min(max_height));
+    if new_size != size {
+        window.set_size(new_size).map_err(|e| e.to_string())?;
+    }
+
+    let x = target.position().x + (target.size().width - new_size.width) as i32 / 2;
+    let y = target.position().y + (target.size().height - new_size.height) as i32 / 2;
+    window
+        .set_position(PhysicalPosition::new(x, y))
+        .map_err(|e| e.to_string())
+}
+
+/// A connected display, for choosing where the panel opens
+#[derive(Debug, Clone, Serialize)]
+pub struct DisplayInfo {
+    /// Matches `panel_display_name`
+    pub name: String,
+    /// Size in logical pixels
+    pub width: f64,
+    pub height: f64,
+    pub primary: bool,
+}
+
+#[tauri::command]
+pub async fn list_displays<R: Runtime>(app: AppHandle<R>) -> Result<Vec<DisplayInfo>, String> {
+    let primary = app
+        .primary_monitor()
+        .map_err(|e| e.to_string())?
+        .and_then(|m| m.name().cloned());
+    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+    Ok(monitors
+        .iter()
+        .filter_map(|monitor| {
+            let name = monitor.name()?.clone();
+            let size = monitor.size().to_logical::<f64>(monitor.scale_factor());
+            Some(DisplayInfo {
+                primary: primary.as_ref() == Some(&name),
+                name,
+                width: size.width,
+                height: size.height,
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,121 @@
+//! Dragging history items out of the panel into other apps.
+//!
+//! Drop targets like Finder, Slack or Mail want files, so `prepare_drag_item`
+//! writes the item to a temp file (or, for file items, hands back the files
+//! themselves) and the frontend starts a native drag with the paths.
+
+use crate::clipboard;
+use crate::database::{ClipboardItem, Database};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Drag files older than this are removed the next time one is written
+const DRAG_FILE_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Longest file name made from an item's preview
+const MAX_NAME_CHARS: usize = 40;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DragPayload {
+    pub paths: Vec<String>,
+    /// Whether `paths` are temp copies rather than the item's own files
+    pub temporary: bool,
+}
+
+fn drag_dir() -> PathBuf {
+    std::env::temp_dir().join("yoink-drag")
+}
+
+/// Best-effort removal of drag files left over from earlier drags
+fn remove_stale(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > DRAG_FILE_MAX_AGE);
+        if stale {
+            let path = entry.path();
+            let _ = if path.is_dir() {
+                std::fs::remove_dir_all(path)
+            } else {
+                std::fs::remove_file(path)
+            };
+        }
+    }
+}
+
+/// A file name from the start of `preview`, without characters that file
+/// systems reject
+fn file_stem(preview: &str) -> String {
+    let stem: String = preview
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => ' ',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .take(MAX_NAME_CHARS)
+        .collect();
+    let stem = stem.split_whitespace().collect::<Vec<_>>().join(" ");
+    if stem.is_empty() {
+        "Clipping".to_string()
+    } else {
+        stem
+    }
+}
+
+fn write_temp_file(item: &ClipboardItem) -> Result<PathBuf, String> {
+    let (stem, extension, bytes) = match item.content_type.as_str() {
+        "image" => ("Image".to_string(), "png", clipboard::image_png(item)?),
+        _ => (file_stem(&item.preview), "txt", item.content.clone().into_bytes()),
+    };
+
+    // One directory per item keeps the readable name free of collisions
+    let dir = drag_dir();
+    remove_stale(&dir);
+    let item_dir = dir.join(&item.id);
+    std::fs::create_dir_all(&item_dir).map_err(|e| e.to_string())?;
+
+    let path = item_dir.join(format!("{}.{}", stem, extension));
+    std::fs::write(&path, bytes).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Files to drag for item `id`: file items give their own files that still
+/// exist, anything else a temp file of its content
+#[tauri::command]
+pub async fn prepare_drag_item(
+    db: tauri::State<'_, Database>,
+    id: String,
+) -> Result<DragPayload, String> {
+    let item = db
+        .get_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+
+    if matches!(item.content_type.as_str(), "file" | "files") {
+        let paths: Vec<String> = clipboard::file_paths(&item.content)
+            .into_iter()
+            .filter(|p| Path::new(p).exists())
+            .collect();
+        if paths.is_empty() {
+            return Err("None of the files exist anymore".to_string());
+        }
+        return Ok(DragPayload {
+            paths,
+            temporary: false,
+        });
+    }
+
+    let path = write_temp_file(&item)?;
+    Ok(DragPayload {
+        paths: vec![path.to_string_lossy().into_owned()],
+        temporary: true,
+    })
+}
@@ -0,0 +1,322 @@
+//! Background title/description/favicon lookup for `url` items, so history
+//! shows page names instead of bare links.
+//!
+//! Only public hosts are fetched: loopback, private, link-local and
+//! single-label intranet names are refused, both as typed and after DNS
+//! resolution, and redirects into them aren't followed.
+
+use crate::database::{ClipboardItem, Database};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::{redirect, Url};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+const FETCH_TIMEOUT_SECS: u64 = 8;
+const MAX_REDIRECTS: usize = 5;
+/// Metadata lives in <head>; there's no need to download whole pages
+const MAX_HTML_BYTES: usize = 512 * 1024;
+const MAX_DESCRIPTION_CHARS: usize = 300;
+
+#[derive(Debug, Default)]
+struct PageMetadata {
+    title: Option<String>,
+    description: Option<String>,
+    favicon: Option<String>,
+}
+
+/// Fetch metadata for `item` in the background, then store it and emit
+/// `item-enriched`. Fields already present (e.g. from the browser extension)
+/// are left alone.
+pub fn spawn<R: Runtime>(app: &AppHandle<R>, item: ClipboardItem) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let page = match fetch(item.content.trim()).await {
+            Ok(page) => page,
+            Err(e) => {
+                log::debug!("Failed to enrich {}: {}", item.id, e);
+                return;
+            }
+        };
+
+        let db = app.state::<Database>();
+        let Ok(Some(mut item)) = db.get_item(&item.id) else {
+            return;
+        };
+
+        let metadata = item.metadata.get_or_insert_with(|| serde_json::json!({}));
+        let Some(fields) = metadata.as_object_mut() else {
+            return;
+        };
+        for (key, value) in [
+            ("title", page.title),
+            ("description", page.description),
+            ("favicon", page.favicon),
+        ] {
+            if let Some(value) = value {
+                fields.entry(key).or_insert(value.into());
+            }
+        }
+
+        if let Err(e) = db.set_item_metadata(&item.id, metadata) {
+            log::warn!("Failed to store metadata for {}: {}", item.id, e);
+            return;
+        }
+        let _ = app.emit("item-enriched", &item);
+    });
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        // "This network", 0.0.0.0/8
+        || a == 0
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        // Carrier-grade NAT, 100.64.0.0/10
+        || (a == 100 && (64..128).contains(&b)))
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_public_ipv4(v4);
+            }
+            let first = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                // Unique local, fc00::/7
+                || (first & 0xfe00) == 0xfc00
+                // Link-local, fe80::/10
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// Whether `url`'s host, as written, may be public. Names are checked again
+/// once resolved (see `PublicResolver`).
+fn is_public_host(url: &Url) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return is_public_ip(ip);
+    }
+
+    let name = host.trim_end_matches('.').to_ascii_lowercase();
+    let local_suffix = [".localhost", ".local", ".internal", ".lan", ".home.arpa"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix));
+    // Single-label names only resolve on a local network
+    name.contains('.') && name != "localhost" && !local_suffix
+}
+
+/// DNS for the fetch that only hands out public addresses. Every connection,
+/// the first request and each redirect, resolves through it, so a name can't
+/// lead (or rebind) to a private host.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            // The connector fills in the real port
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err("Not a public host".into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+async fn fetch(url: &str) -> Result<PageMetadata, String> {
+    let url = Url::parse(url).map_err(|e| e.to_string())?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("Unsupported scheme".to_string());
+    }
+    if !is_public_host(&url) {
+        return Err("Not a public host".to_string());
+    }
+
+    // Redirect targets are checked as written here; names are checked
+    // again by `PublicResolver` when connecting
+    let policy = redirect::Policy::custom(|attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            attempt.error("Too many redirects")
+        } else if !matches!(attempt.url().scheme(), "http" | "https")
+            || !is_public_host(attempt.url())
+        {
+            attempt.stop()
+        } else {
+            attempt.follow()
+        }
+    });
+
+    let mut response = reqwest::Client::builder()
+        .redirect(policy)
+        .dns_resolver(Arc::new(PublicResolver))
+        .build()
+        .map_err(|e| e.to_string())?
+        .get(url.clone())
+        .header(reqwest::header::ACCEPT, "text/html")
+        .timeout(std::time::Duration::from_secs(FETCH_TIMEOUT_SECS))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?;
+
+    // Redirects may have moved us; relative favicons resolve against the final URL
+    let base = response.url().clone();
+
+    let is_html = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map_or(true, |v| v.contains("html"));
+    if !is_html {
+        return Err("Not an HTML page".to_string());
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        body.extend_from_slice(&chunk);
+        if body.len() >= MAX_HTML_BYTES {
+            break;
+        }
+    }
+
+    Ok(parse(&String::from_utf8_lossy(&body), &base))
+}
+
+fn parse(html: &str, base: &Url) -> PageMetadata {
+    let mut page = PageMetadata::default();
+    let mut og_title = None;
+
+    for tag in tags(html, "meta") {
+        let attrs = attributes(tag);
+        let key = attr(&attrs, "property").or_else(|| attr(&attrs, "name"));
+        let Some(content) = attr(&attrs, "content").filter(|c| !c.is_empty()) else {
+            continue;
+        };
+        match key.map(|k| k.to_ascii_lowercase()).as_deref() {
+            Some("og:title") => og_title = Some(content),
+            Some("description") | Some("og:description") if page.description.is_none() => {
+                page.description = Some(content.chars().take(MAX_DESCRIPTION_CHARS).collect());
+            }
+            _ => {}
+        }
+    }
+
+    page.title = title(html).or(og_title);
+
+    page.favicon = tags(html, "link")
+        .map(attributes)
+        .find(|attrs| {
+            attr(attrs, "rel").is_some_and(|rel| {
+                rel.split_whitespace().any(|r| r.eq_ignore_ascii_case("icon"))
+            })
+        })
+        .and_then(|attrs| attr(&attrs, "href"))
+        .and_then(|href| base.join(&href).ok())
+        .or_else(|| base.join("/favicon.ico").ok())
+        .map(|url| url.to_string());
+
+    page
+}
+
+fn title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let start = lower.find("<title")?;
+    let start = start + lower[start..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let title = decode_entities(html[start..end].trim());
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+/// The insides of every `<name ...>` tag, e.g. `meta name="x" content="y"`
+fn tags<'a>(html: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    let open = format!("<{}", name);
+    let lower = html.to_ascii_lowercase();
+    let mut positions = Vec::new();
+    let mut from = 0;
+    while let Some(i) = lower[from..].find(&open) {
+        let start = from + i + open.len();
+        let Some(len) = lower[start..].find('>') else {
+            break;
+        };
+        // Skip longer tag names sharing the prefix (<linkx>)
+        if lower[start..].starts_with(|c: char| c.is_whitespace() || c == '/') {
+            positions.push((start, start + len));
+        }
+        from = start + len;
+    }
+    positions.into_iter().map(move |(s, e)| &html[s..e])
+}
+
+fn attributes(tag: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut rest = tag.trim_start();
+
+    while !rest.is_empty() {
+        let name_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].trim_matches('/').to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let value = if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            let (value, remaining) = match after_eq.chars().next() {
+                Some(q @ ('"' | '\'')) => {
+                    let inner = &after_eq[1..];
+                    let end = inner.find(q).unwrap_or(inner.len());
+                    (&inner[..end], inner.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = after_eq.find(char::is_whitespace).unwrap_or(after_eq.len());
+                    (&after_eq[..end], &after_eq[end..])
+                }
+            };
+            rest = remaining.trim_start();
+            decode_entities(value)
+        } else {
+            String::new()
+        };
+
+        if !name.is_empty() {
+            attrs.push((name, value));
+        }
+    }
+
+    attrs
+}
+
+fn attr(attrs: &[(String, String)], name: &str) -> Option<String> {
+    attrs
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.trim().to_string())
+}
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,316 @@
+//! Free/Pro gating enforced in the backend.
+//!
+//! Every invoke passes through `gate`, which rejects Pro commands unless the
+//! user has a recently validated Pro subscription, is within the grace period
+//! after one, or is on an active trial. Hiding UI alone isn't enough since any
+//! command can be called directly with `invoke`.
+//!
+//! Server validations are only kept in memory: entitlement.json is unsigned
+//! and user-writable, so anything it says about Pro could be forged. Each
+//! launch validates again, and the grace period covers a session that loses
+//! its connection.
+
+use crate::database::Database;
+use crate::hotkey::HotkeyManager;
+use crate::settings::SettingsManager;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::ipc::Invoke;
+use tauri::{AppHandle, Manager, Runtime};
+
+const TRIAL_DAYS: i64 = 14;
+/// Pro status is re-validated against the server at least this often
+const VALIDATION_INTERVAL_HOURS: i64 = 24;
+/// How long Pro keeps working when validation can't reach the server
+const GRACE_DAYS: i64 = 7;
+
+/// `app_state` key mirroring the trial start, so deleting entitlement.json
+/// doesn't hand out a fresh trial
+const TRIAL_STATE_KEY: &str = "trial_started_at";
+
+const SUPABASE_URL: Option<&str> = option_env!("VITE_SUPABASE_URL");
+const SUPABASE_ANON_KEY: Option<&str> = option_env!("VITE_SUPABASE_ANON_KEY");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tier {
+    Free,
+    Pro,
+}
+
+/// Commands that require Pro. Anything not listed is Free.
+const PRO_COMMANDS: &[&str] = &[
+    "create_collection",
+    "update_collection",
+    "delete_collection",
+    "move_to_collection",
+    "move_items_to_collection",
+    "toggle_queue_mode",
+    "paste_next_in_queue",
+    "register_queue_hotkey",
+];
+
+pub fn command_tier(command: &str) -> Tier {
+    if PRO_COMMANDS.contains(&command) {
+        Tier::Pro
+    } else {
+        Tier::Free
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct StoredEntitlement {
+    trial_started_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EntitlementStatus {
+    pub tier: Tier,
+    pub trial_ends_at: Option<DateTime<Utc>>,
+    pub trial_active: bool,
+    /// Pro is only being honored because validation is overdue
+    pub in_grace_period: bool,
+    pub pro_commands: Vec<String>,
+}
+
+pub struct EntitlementManager {
+    state: Mutex<StoredEntitlement>,
+    /// Last time the server confirmed an active Pro subscription this session
+    pro_validated_at: Mutex<Option<DateTime<Utc>>>,
+    path: PathBuf,
+}
+
+impl EntitlementManager {
+    pub fn new(app_data_dir: PathBuf) -> Self {
+        let path = app_data_dir.join("entitlement.json");
+
+        let state = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            state: Mutex::new(state),
+            pro_validated_at: Mutex::new(None),
+            path,
+        }
+    }
+
+    fn save(&self, state: &StoredEntitlement) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+        std::fs::write(&self.path, json).map_err(|e| e.to_string())
+    }
+
+    pub fn status(&self) -> EntitlementStatus {
+        let state = self.state.lock().unwrap();
+        let now = Utc::now();
+
+        // A start in the future can only come from an edited file (or a clock
+        // turned back), so it counts as a used trial
+        let trial_started_at = state.trial_started_at;
+        let trial_ends_at = trial_started_at.map(|t| t + Duration::days(TRIAL_DAYS));
+        let trial_active = trial_started_at.is_some_and(|t| t <= now)
+            && trial_ends_at.is_some_and(|end| now < end);
+
+        let validated_at = *self.pro_validated_at.lock().unwrap();
+        let (validated, in_grace) = match validated_at {
+            Some(at) if now < at + Duration::hours(VALIDATION_INTERVAL_HOURS) => (true, false),
+            Some(at)
+                if now < at + Duration::hours(VALIDATION_INTERVAL_HOURS) + Duration::days(GRACE_DAYS) =>
+            {
+                (true, true)
+            }
+            _ => (false, false),
+        };
+
+        EntitlementStatus {
+            tier: if validated || trial_active { Tier::Pro } else { Tier::Free },
+            trial_ends_at,
+            trial_active,
+            in_grace_period: in_grace && !trial_active,
+            pro_commands: PRO_COMMANDS.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    pub fn require(&self, tier: Tier) -> Result<(), String> {
+        if tier == Tier::Pro && self.status().tier != Tier::Pro {
+            return Err("This feature requires Yoink Pro".to_string());
+        }
+        Ok(())
+    }
+
+    fn record_validation(&self, is_pro: bool) {
+        *self.pro_validated_at.lock().unwrap() = is_pro.then(Utc::now);
+    }
+
+    /// Reconcile the trial start with the copy kept in the database, keeping
+    /// the earlier of the two in both places
+    pub fn sync_trial(&self, db: &Database) {
+        let stored = match db.get_app_state(TRIAL_STATE_KEY) {
+            Ok(value) => value.and_then(|v| DateTime::parse_from_rfc3339(&v).ok()),
+            Err(e) => {
+                log::warn!("Failed to read trial record: {}", e);
+                return;
+            }
+        }
+        .map(|t| t.with_timezone(&Utc));
+
+        let mut state = self.state.lock().unwrap();
+        let earliest = match (state.trial_started_at, stored) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        let Some(earliest) = earliest else {
+            return;
+        };
+        if state.trial_started_at != Some(earliest) {
+            state.trial_started_at = Some(earliest);
+            if let Err(e) = self.save(&state) {
+                log::warn!("Failed to save trial record: {}", e);
+            }
+        }
+        if stored != Some(earliest) {
+            if let Err(e) = db.set_app_state(TRIAL_STATE_KEY, &earliest.to_rfc3339()) {
+                log::warn!("Failed to save trial record: {}", e);
+            }
+        }
+    }
+
+    /// Start the one-time trial; calling it again doesn't extend it
+    pub fn start_trial(&self, db: &Database) -> Result<(), String> {
+        let mut state = self.state.lock().unwrap();
+        if state.trial_started_at.is_some() {
+            return Err("Trial has already been used".to_string());
+        }
+        let now = Utc::now();
+        db.set_app_state(TRIAL_STATE_KEY, &now.to_rfc3339())
+            .map_err(|e| e.to_string())?;
+        state.trial_started_at = Some(now);
+        self.save(&state)
+    }
+}
+
+/// Wrap the generated command handler so Pro commands are checked before
+/// they run
+pub fn gate<R, H>(handler: H) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static
+where
+    R: Runtime,
+    H: Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+{
+    move |invoke: Invoke<R>| {
+        let allowed =
+            require_command(invoke.message.webview().app_handle(), invoke.message.command());
+        if let Err(e) = allowed {
+            invoke.resolver.reject(e);
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+/// Check `command`'s tier for callers that run a command without an invoke
+/// (like the native context menu), so they can't get around `gate`
+pub fn require_command<R: Runtime>(app: &AppHandle<R>, command: &str) -> Result<(), String> {
+    let tier = command_tier(command);
+    if tier == Tier::Free {
+        return Ok(());
+    }
+    app.try_state::<EntitlementManager>()
+        .map_or(Err("Entitlements are not initialized".to_string()), |m| {
+            m.require(tier)
+        })
+}
+
+#[derive(Deserialize)]
+struct UserRow {
+    is_pro: bool,
+}
+
+/// Ask the server whether the signed-in user has Pro. The access token is the
+/// user's session JWT, so row-level security only exposes their own row.
+async fn fetch_pro_status(access_token: &str, user_id: &str) -> Result<bool, String> {
+    let (Some(url), Some(anon_key)) = (SUPABASE_URL, SUPABASE_ANON_KEY) else {
+        return Err("Licensing server is not configured in this build".to_string());
+    };
+
+    let rows: Vec<UserRow> = reqwest::Client::new()
+        .get(format!("{}/rest/v1/users", url.trim_end_matches('/')))
+        .query(&[("id", format!("eq.{}", user_id)), ("select", "is_pro".to_string())])
+        .header("apikey", anon_key)
+        .bearer_auth(access_token)
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.first().is_some_and(|row| row.is_pro))
+}
+
+/// Register or drop the saved queue hotkey to match the current tier
+fn sync_queue_hotkey<R: Runtime>(app: &AppHandle<R>) {
+    if crate::safe_mode::is_active(app) {
+        return;
+    }
+    let (Some(hotkeys), Some(settings)) = (
+        app.try_state::<HotkeyManager>(),
+        app.try_state::<SettingsManager>(),
+    ) else {
+        return;
+    };
+    if let Err(e) = hotkeys.register_queue_hotkey(app, &settings.get().queue_paste_hotkey) {
+        log::warn!("Failed to register queue paste hotkey: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn get_entitlements(
+    manager: tauri::State<'_, EntitlementManager>,
+) -> Result<EntitlementStatus, String> {
+    Ok(manager.status())
+}
+
+/// Re-validate Pro status with the server. Network failures leave the last
+/// validation in place so the grace period applies.
+#[tauri::command]
+pub async fn refresh_entitlements<R: Runtime>(
+    app: AppHandle<R>,
+    manager: tauri::State<'_, EntitlementManager>,
+    access_token: String,
+    user_id: String,
+) -> Result<EntitlementStatus, String> {
+    match fetch_pro_status(&access_token, &user_id).await {
+        Ok(is_pro) => manager.record_validation(is_pro),
+        Err(e) => log::warn!("Failed to validate Pro status: {}", e),
+    }
+    sync_queue_hotkey(&app);
+    Ok(manager.status())
+}
+
+#[tauri::command]
+pub async fn start_trial<R: Runtime>(
+    app: AppHandle<R>,
+    manager: tauri::State<'_, EntitlementManager>,
+    db: tauri::State<'_, Database>,
+) -> Result<EntitlementStatus, String> {
+    manager.start_trial(&db)?;
+    sync_queue_hotkey(&app);
+    Ok(manager.status())
+}
+
+/// Forget the validated subscription (e.g. on sign-out); the trial record stays
+#[tauri::command]
+pub async fn clear_entitlements<R: Runtime>(
+    app: AppHandle<R>,
+    manager: tauri::State<'_, EntitlementManager>,
+) -> Result<EntitlementStatus, String> {
+    manager.record_validation(false);
+    sync_queue_hotkey(&app);
+    Ok(manager.status())
+}
@@ -0,0 +1,83 @@
+//! Flashback paste: a hotkey that pastes the previous history item without
+//! showing the panel.
+//!
+//! The first press pastes the second most recent item; pressing again within
+//! `CYCLE_WINDOW` pastes the one before that, and so on. The order is taken
+//! at the first press, so the pastes themselves never reshuffle it.
+
+use crate::clipboard::{self, ClipboardMonitor};
+use crate::database::Database;
+use crate::paste_helper::{self, PasteOptions};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Presses further apart than this start over from the previous item
+const CYCLE_WINDOW: Duration = Duration::from_millis(1500);
+
+/// How far back repeated presses can reach
+const MAX_DEPTH: u32 = 20;
+
+struct Cycle {
+    ids: Vec<String>,
+    /// Index into `ids` of the last item pasted
+    index: usize,
+    pressed_at: Instant,
+}
+
+#[derive(Default)]
+pub struct FlashbackState {
+    cycle: Mutex<Option<Cycle>>,
+}
+
+impl FlashbackState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Paste the previous item, or the next older one on a repeated press
+pub async fn paste_previous<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    let id = {
+        let state = app.state::<FlashbackState>();
+        let mut cycle = state.cycle.lock().unwrap();
+
+        match cycle.as_mut() {
+            Some(c) if c.pressed_at.elapsed() < CYCLE_WINDOW => {
+                // Stay on the oldest item once there's nothing further back
+                if c.index + 1 < c.ids.len() {
+                    c.index += 1;
+                }
+                c.pressed_at = Instant::now();
+            }
+            _ => {
+                // By copy order alone; pinned items would otherwise come first
+                let ids = app
+                    .state::<Database>()
+                    .recent_item_ids(MAX_DEPTH)
+                    .map_err(|e| e.to_string())?;
+                *cycle = Some(Cycle {
+                    ids,
+                    index: 1,
+                    pressed_at: Instant::now(),
+                });
+            }
+        }
+
+        let Some(id) = cycle.as_ref().and_then(|c| c.ids.get(c.index).cloned()) else {
+            return Ok(());
+        };
+        id
+    };
+
+    let Some(item) = app.state::<Database>().get_item(&id).map_err(|e| e.to_string())? else {
+        return Ok(());
+    };
+
+    // The item is already in history; writing it back shouldn't capture it
+    if let Some(monitor) = app.try_state::<ClipboardMonitor>() {
+        monitor.mark_seen(item.hash.clone());
+    }
+    paste_helper::capture_target(&app);
+    clipboard::do_paste_and_simulate(app, item.id, PasteOptions::default()).await
+}
@@ -4,17 +4,32 @@ use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 #[cfg(target_os = "macos")]
 use tauri_nspanel::ManagerExt;
 
-use crate::database::Database;
+use crate::database::{Board, Database, ItemSort};
+use crate::entitlements;
 use crate::window::{HotkeyModeState, SelectedItemState};
 
 pub struct HotkeyManager {
     current_shortcut: std::sync::Mutex<Option<Shortcut>>,
+    board_shortcuts: std::sync::Mutex<Vec<Shortcut>>,
+    ocr_shortcut: std::sync::Mutex<Option<Shortcut>>,
+    append_shortcut: std::sync::Mutex<Option<Shortcut>>,
+    plain_paste_shortcut: std::sync::Mutex<Option<Shortcut>>,
+    queue_paste_shortcut: std::sync::Mutex<Option<Shortcut>>,
+    paste_previous_shortcut: std::sync::Mutex<Option<Shortcut>>,
+    pinned_shortcuts: std::sync::Mutex<Vec<Shortcut>>,
 }
 
 impl HotkeyManager {
     pub fn new() -> Self {
         Self {
             current_shortcut: std::sync::Mutex::new(None),
+            board_shortcuts: std::sync::Mutex::new(Vec::new()),
+            ocr_shortcut: std::sync::Mutex::new(None),
+            append_shortcut: std::sync::Mutex::new(None),
+            plain_paste_shortcut: std::sync::Mutex::new(None),
+            queue_paste_shortcut: std::sync::Mutex::new(None),
+            paste_previous_shortcut: std::sync::Mutex::new(None),
+            pinned_shortcuts: std::sync::Mutex::new(Vec::new()),
         }
     }
 
@@ -35,6 +50,14 @@ impl HotkeyManager {
 
                 let app = app_clone.clone();
                 tauri::async_runtime::spawn(async move {
+                    // In stack mode the hotkey pastes the top of the stack instead
+                    if crate::stack::is_active(&app) {
+                        if let Err(e) = crate::stack::paste_top(app).await {
+                            log::warn!("Stack paste failed: {}", e);
+                        }
+                        return;
+                    }
+
                     // Check if we're already in hotkey mode (user cycling through items)
                     let in_hotkey_mode = if let Some(hotkey_state) = app.try_state::<HotkeyModeState>() {
                         hotkey_state.is_active()
@@ -76,8 +99,8 @@ impl HotkeyManager {
                         // Set initial selected item to the most recent clipboard item
                         let first_item_id = app
                             .try_state::<Database>()
-                            .and_then(|db| db.get_items(1, 0, None, None).ok())
-                            .and_then(|items| items.into_iter().next())
+                            .and_then(|db| db.get_items(1, None, &Default::default(), ItemSort::Recent, false).ok())
+                            .and_then(|page| page.items.into_iter().next())
                             .map(|item| item.id);
                         if let Some(id) = first_item_id {
                             if let Some(selected_state) = app.try_state::<SelectedItemState>() {
@@ -112,6 +135,354 @@ impl HotkeyManager {
 
         Ok(())
     }
+
+    /// Drop every registration and bind all shortcuts again from settings.
+    /// Used after wake, when the OS may have silently lost registrations that
+    /// we still believe are active.
+    pub fn reregister_all<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        settings: &crate::settings::Settings,
+        boards: &[Board],
+    ) {
+        let _ = app.global_shortcut().unregister_all();
+        self.current_shortcut.lock().unwrap().take();
+        self.ocr_shortcut.lock().unwrap().take();
+        self.append_shortcut.lock().unwrap().take();
+        self.plain_paste_shortcut.lock().unwrap().take();
+        self.queue_paste_shortcut.lock().unwrap().take();
+        self.paste_previous_shortcut.lock().unwrap().take();
+        self.board_shortcuts.lock().unwrap().clear();
+        self.pinned_shortcuts.lock().unwrap().clear();
+
+        if let Err(e) = self.register(app, &settings.hotkey) {
+            log::warn!("Failed to re-register hotkey: {}", e);
+        }
+        if let Err(e) = self.register_ocr_hotkey(app, &settings.ocr_hotkey) {
+            log::warn!("Failed to re-register OCR hotkey: {}", e);
+        }
+        if let Err(e) = self.register_append_hotkey(app, &settings.append_mode_hotkey) {
+            log::warn!("Failed to re-register append mode hotkey: {}", e);
+        }
+        if let Err(e) = self.register_plain_paste_hotkey(app, &settings.plain_paste_hotkey) {
+            log::warn!("Failed to re-register plain paste hotkey: {}", e);
+        }
+        if let Err(e) = self.register_queue_hotkey(app, &settings.queue_paste_hotkey) {
+            log::warn!("Failed to re-register queue paste hotkey: {}", e);
+        }
+        if let Err(e) = self.register_paste_previous_hotkey(app, &settings.paste_previous_hotkey) {
+            log::warn!("Failed to re-register paste previous hotkey: {}", e);
+        }
+        if let Err(e) = self.register_pinned_hotkeys(app, &settings.pinned_paste_modifiers) {
+            log::warn!("Failed to re-register pinned item hotkeys: {}", e);
+        }
+        let _ = self.register_board_hotkeys(app, boards);
+    }
+
+    /// Bind the screenshot OCR shortcut, replacing any previous one.
+    /// An empty hotkey just unbinds it.
+    pub fn register_ocr_hotkey<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        hotkey: &str,
+    ) -> Result<(), String> {
+        let mut current = self.ocr_shortcut.lock().unwrap();
+
+        if let Some(shortcut) = current.take() {
+            let _ = app.global_shortcut().unregister(shortcut);
+        }
+
+        if hotkey.is_empty() {
+            return Ok(());
+        }
+
+        let shortcut: Shortcut = hotkey.parse().map_err(|e| format!("{:?}", e))?;
+        let app_clone = app.clone();
+
+        app.global_shortcut()
+            .on_shortcut(shortcut.clone(), move |_app, _shortcut, event| {
+                if event.state != ShortcutState::Pressed {
+                    return;
+                }
+
+                let app = app_clone.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = crate::ocr::capture_and_recognize(app).await {
+                        log::warn!("Screenshot OCR failed: {}", e);
+                    }
+                });
+            })
+            .map_err(|e| e.to_string())?;
+
+        *current = Some(shortcut);
+
+        Ok(())
+    }
+
+    /// Bind the append mode toggle, replacing any previous one.
+    /// An empty hotkey just unbinds it.
+    pub fn register_append_hotkey<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        hotkey: &str,
+    ) -> Result<(), String> {
+        let mut current = self.append_shortcut.lock().unwrap();
+
+        if let Some(shortcut) = current.take() {
+            let _ = app.global_shortcut().unregister(shortcut);
+        }
+
+        if hotkey.is_empty() {
+            return Ok(());
+        }
+
+        let shortcut: Shortcut = hotkey.parse().map_err(|e| format!("{:?}", e))?;
+        let app_clone = app.clone();
+
+        app.global_shortcut()
+            .on_shortcut(shortcut.clone(), move |_app, _shortcut, event| {
+                if event.state != ShortcutState::Pressed {
+                    return;
+                }
+                crate::clipboard::toggle_append_mode(&app_clone);
+            })
+            .map_err(|e| e.to_string())?;
+
+        *current = Some(shortcut);
+
+        Ok(())
+    }
+
+    /// Bind the paste-as-plain-text shortcut, replacing any previous one.
+    /// An empty hotkey just unbinds it.
+    pub fn register_plain_paste_hotkey<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        hotkey: &str,
+    ) -> Result<(), String> {
+        let mut current = self.plain_paste_shortcut.lock().unwrap();
+
+        if let Some(shortcut) = current.take() {
+            let _ = app.global_shortcut().unregister(shortcut);
+        }
+
+        if hotkey.is_empty() {
+            return Ok(());
+        }
+
+        let shortcut: Shortcut = hotkey.parse().map_err(|e| format!("{:?}", e))?;
+        let app_clone = app.clone();
+
+        app.global_shortcut()
+            .on_shortcut(shortcut.clone(), move |_app, _shortcut, event| {
+                if event.state != ShortcutState::Pressed {
+                    return;
+                }
+
+                let app = app_clone.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = crate::clipboard::paste_latest_plain(app).await {
+                        log::warn!("Plain text paste failed: {}", e);
+                    }
+                });
+            })
+            .map_err(|e| e.to_string())?;
+
+        *current = Some(shortcut);
+
+        Ok(())
+    }
+
+    /// Bind the paste-next-in-queue shortcut, replacing any previous one.
+    /// An empty hotkey just unbinds it.
+    pub fn register_queue_hotkey<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        hotkey: &str,
+    ) -> Result<(), String> {
+        let mut current = self.queue_paste_shortcut.lock().unwrap();
+
+        if let Some(shortcut) = current.take() {
+            let _ = app.global_shortcut().unregister(shortcut);
+        }
+
+        // Queue mode is Pro; the saved hotkey is registered again once
+        // entitlements are refreshed
+        if hotkey.is_empty() || entitlements::require_command(app, "register_queue_hotkey").is_err()
+        {
+            return Ok(());
+        }
+
+        let shortcut: Shortcut = hotkey.parse().map_err(|e| format!("{:?}", e))?;
+        let app_clone = app.clone();
+
+        app.global_shortcut()
+            .on_shortcut(shortcut.clone(), move |_app, _shortcut, event| {
+                if event.state != ShortcutState::Pressed {
+                    return;
+                }
+
+                let app = app_clone.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = crate::queue::paste_next(app).await {
+                        log::warn!("Queue paste failed: {}", e);
+                    }
+                });
+            })
+            .map_err(|e| e.to_string())?;
+
+        *current = Some(shortcut);
+
+        Ok(())
+    }
+
+    /// Bind the paste previous item shortcut, replacing any previous one.
+    /// An empty hotkey just unbinds it.
+    pub fn register_paste_previous_hotkey<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        hotkey: &str,
+    ) -> Result<(), String> {
+        let mut current = self.paste_previous_shortcut.lock().unwrap();
+
+        if let Some(shortcut) = current.take() {
+            let _ = app.global_shortcut().unregister(shortcut);
+        }
+
+        if hotkey.is_empty() {
+            return Ok(());
+        }
+
+        let shortcut: Shortcut = hotkey.parse().map_err(|e| format!("{:?}", e))?;
+        let app_clone = app.clone();
+
+        app.global_shortcut()
+            .on_shortcut(shortcut.clone(), move |_app, _shortcut, event| {
+                if event.state != ShortcutState::Pressed {
+                    return;
+                }
+
+                let app = app_clone.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = crate::flashback::paste_previous(app).await {
+                        log::warn!("Paste previous failed: {}", e);
+                    }
+                });
+            })
+            .map_err(|e| e.to_string())?;
+
+        *current = Some(shortcut);
+
+        Ok(())
+    }
+
+    /// Bind `modifiers`+1 through `modifiers`+9 to paste the first nine
+    /// pinned items, replacing any previous bindings. Empty modifiers just
+    /// unbind them; a digit that's taken elsewhere is logged and skipped.
+    pub fn register_pinned_hotkeys<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        modifiers: &str,
+    ) -> Result<(), String> {
+        let mut registered = self.pinned_shortcuts.lock().unwrap();
+
+        for shortcut in registered.drain(..) {
+            let _ = app.global_shortcut().unregister(shortcut);
+        }
+
+        let modifiers = modifiers.trim().trim_end_matches('+');
+        if modifiers.is_empty() {
+            return Ok(());
+        }
+
+        // Parse all nine up front so a bad modifier string binds nothing
+        let shortcuts = (1..=crate::paste_helper::PINNED_HOTKEYS)
+            .map(|digit| format!("{}+{}", modifiers, digit).parse::<Shortcut>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("{:?}", e))?;
+
+        for (index, shortcut) in shortcuts.into_iter().enumerate() {
+            let app_clone = app.clone();
+
+            let result = app.global_shortcut().on_shortcut(
+                shortcut.clone(),
+                move |_app, _shortcut, event| {
+                    if event.state != ShortcutState::Pressed {
+                        return;
+                    }
+
+                    let app = app_clone.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = crate::paste_helper::paste_pinned(app, index).await {
+                            log::warn!("Pinned item paste failed: {}", e);
+                        }
+                    });
+                },
+            );
+
+            match result {
+                Ok(()) => registered.push(shortcut),
+                Err(e) => log::warn!("Failed to register pinned item hotkey {}: {}", index + 1, e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replace all board shortcuts with the hotkeys configured on `boards`.
+    /// Pressing a board hotkey shows the panel and tells the frontend which
+    /// board to open. Invalid or conflicting hotkeys are logged and skipped.
+    pub fn register_board_hotkeys<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        boards: &[Board],
+    ) -> Result<(), String> {
+        let mut registered = self.board_shortcuts.lock().unwrap();
+
+        for shortcut in registered.drain(..) {
+            let _ = app.global_shortcut().unregister(shortcut);
+        }
+
+        for board in boards {
+            let Some(hotkey) = board.hotkey.as_deref().filter(|h| !h.is_empty()) else {
+                continue;
+            };
+
+            let shortcut: Shortcut = match hotkey.parse() {
+                Ok(shortcut) => shortcut,
+                Err(e) => {
+                    log::warn!("Invalid hotkey for board {}: {:?}", board.name, e);
+                    continue;
+                }
+            };
+
+            let app_clone = app.clone();
+            let board_id = board.id.clone();
+
+            let result = app.global_shortcut().on_shortcut(
+                shortcut.clone(),
+                move |_app, _shortcut, event| {
+                    if event.state != ShortcutState::Pressed {
+                        return;
+                    }
+
+                    let app = app_clone.clone();
+                    let board_id = board_id.clone();
+                    tauri::async_runtime::spawn(async move {
+                        let _ = app.emit("board-hotkey", &board_id);
+                        let _ = crate::window::show_window(app).await;
+                    });
+                },
+            );
+
+            match result {
+                Ok(()) => registered.push(shortcut),
+                Err(e) => log::warn!("Failed to register hotkey for board {}: {}", board.name, e),
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[tauri::command]
@@ -123,6 +494,78 @@ pub async fn register_hotkey<R: Runtime>(
     hotkey_manager.register(&app, &hotkey)
 }
 
+#[tauri::command]
+pub async fn register_ocr_hotkey<R: Runtime>(
+    app: AppHandle<R>,
+    hotkey_manager: tauri::State<'_, HotkeyManager>,
+    settings_manager: tauri::State<'_, crate::settings::SettingsManager>,
+    hotkey: String,
+) -> Result<(), String> {
+    hotkey_manager.register_ocr_hotkey(&app, &hotkey)?;
+    settings_manager.update_field(|s| s.ocr_hotkey = hotkey)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn register_append_hotkey<R: Runtime>(
+    app: AppHandle<R>,
+    hotkey_manager: tauri::State<'_, HotkeyManager>,
+    settings_manager: tauri::State<'_, crate::settings::SettingsManager>,
+    hotkey: String,
+) -> Result<(), String> {
+    hotkey_manager.register_append_hotkey(&app, &hotkey)?;
+    settings_manager.update_field(|s| s.append_mode_hotkey = hotkey)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn register_plain_paste_hotkey<R: Runtime>(
+    app: AppHandle<R>,
+    hotkey_manager: tauri::State<'_, HotkeyManager>,
+    settings_manager: tauri::State<'_, crate::settings::SettingsManager>,
+    hotkey: String,
+) -> Result<(), String> {
+    hotkey_manager.register_plain_paste_hotkey(&app, &hotkey)?;
+    settings_manager.update_field(|s| s.plain_paste_hotkey = hotkey)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn register_queue_hotkey<R: Runtime>(
+    app: AppHandle<R>,
+    hotkey_manager: tauri::State<'_, HotkeyManager>,
+    settings_manager: tauri::State<'_, crate::settings::SettingsManager>,
+    hotkey: String,
+) -> Result<(), String> {
+    hotkey_manager.register_queue_hotkey(&app, &hotkey)?;
+    settings_manager.update_field(|s| s.queue_paste_hotkey = hotkey)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn register_paste_previous_hotkey<R: Runtime>(
+    app: AppHandle<R>,
+    hotkey_manager: tauri::State<'_, HotkeyManager>,
+    settings_manager: tauri::State<'_, crate::settings::SettingsManager>,
+    hotkey: String,
+) -> Result<(), String> {
+    hotkey_manager.register_paste_previous_hotkey(&app, &hotkey)?;
+    settings_manager.update_field(|s| s.paste_previous_hotkey = hotkey)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn register_pinned_hotkeys<R: Runtime>(
+    app: AppHandle<R>,
+    hotkey_manager: tauri::State<'_, HotkeyManager>,
+    settings_manager: tauri::State<'_, crate::settings::SettingsManager>,
+    modifiers: String,
+) -> Result<(), String> {
+    hotkey_manager.register_pinned_hotkeys(&app, &modifiers)?;
+    settings_manager.update_field(|s| s.pinned_paste_modifiers = modifiers)?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn validate_hotkey(hotkey: String) -> Result<bool, String> {
     // Validate the hotkey format
@@ -1,34 +1,118 @@
-/// Keyboard simulation module for macOS
-/// Uses CGEvent to simulate Cmd+V keystroke
+/// Keyboard simulation module
+/// Uses CGEvent on macOS, and xdotool (X11) or wtype (Wayland) on Linux
 
 #[cfg(target_os = "macos")]
 use core_graphics::event::{CGEvent, CGEventFlags, CGEventTapLocation, CGKeyCode};
 #[cfg(target_os = "macos")]
 use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
 
-/// Virtual key code for 'V' on macOS
+/// Virtual key codes on macOS
 #[cfg(target_os = "macos")]
-const KEY_V: CGKeyCode = 9;
+pub const KEY_V: CGKeyCode = 9;
+#[cfg(target_os = "macos")]
+const KEY_HELP: CGKeyCode = 114;
+#[cfg(target_os = "macos")]
+const KEY_RETURN: CGKeyCode = 36;
+
+/// Key of a paste shortcut
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PasteKey {
+    #[default]
+    V,
+    /// Shift+Insert pastes in many X11 and Windows terminals
+    Insert,
+    /// Sent on its own after a paste-and-submit
+    Return,
+}
+
+/// A paste shortcut, e.g. `Cmd+Shift+V` for apps that don't paste on Cmd+V
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    not(any(target_os = "macos", target_os = "linux")),
+    allow(dead_code)
+)]
+pub struct Keystroke {
+    pub command: bool,
+    pub control: bool,
+    pub option: bool,
+    pub shift: bool,
+    pub key: PasteKey,
+}
+
+impl Keystroke {
+    /// The platform's usual paste shortcut
+    pub fn paste() -> Self {
+        Self {
+            command: cfg!(target_os = "macos"),
+            control: !cfg!(target_os = "macos"),
+            ..Default::default()
+        }
+    }
+
+    /// The bare Return that submits after a paste-and-submit
+    pub fn submit() -> Self {
+        Self {
+            key: PasteKey::Return,
+            ..Default::default()
+        }
+    }
 
-/// Simulate Cmd+V keystroke using CGEvent API
+    /// Parse a shortcut like `Cmd+Shift+V` or `Shift+Insert`
+    pub fn parse(shortcut: &str) -> Option<Self> {
+        let mut keystroke = Self::default();
+        let mut key = None;
+
+        for part in shortcut.split('+').map(str::trim) {
+            match part.to_lowercase().as_str() {
+                "cmd" | "command" | "super" | "meta" => keystroke.command = true,
+                "ctrl" | "control" => keystroke.control = true,
+                "alt" | "option" | "opt" => keystroke.option = true,
+                "shift" => keystroke.shift = true,
+                "v" => key = Some(PasteKey::V),
+                "insert" | "ins" => key = Some(PasteKey::Insert),
+                _ => return None,
+            }
+        }
+
+        keystroke.key = key?;
+        Some(keystroke)
+    }
+}
+
+/// Simulate a paste keystroke using CGEvent API
 #[cfg(target_os = "macos")]
-pub fn simulate_cmd_v() -> Result<(), String> {
+pub fn simulate_keystroke(keystroke: Keystroke) -> Result<(), String> {
     // Use CombinedSessionState for better compatibility across applications
     // This combines both HID system state and session state
     let source = CGEventSource::new(CGEventSourceStateID::CombinedSessionState)
         .map_err(|_| "Failed to create CGEventSource")?;
 
-    // Create key down event for 'V'
-    let key_down = CGEvent::new_keyboard_event(source.clone(), KEY_V, true)
-        .map_err(|_| "Failed to create key down event")?;
+    let key_code = match keystroke.key {
+        PasteKey::V => KEY_V,
+        PasteKey::Insert => KEY_HELP,
+        PasteKey::Return => KEY_RETURN,
+    };
 
-    // Create key up event for 'V'
-    let key_up = CGEvent::new_keyboard_event(source, KEY_V, false)
+    let key_down = CGEvent::new_keyboard_event(source.clone(), key_code, true)
+        .map_err(|_| "Failed to create key down event")?;
+    let key_up = CGEvent::new_keyboard_event(source, key_code, false)
         .map_err(|_| "Failed to create key up event")?;
 
-    // Set Command modifier flag
-    key_down.set_flags(CGEventFlags::CGEventFlagCommand);
-    key_up.set_flags(CGEventFlags::CGEventFlagCommand);
+    let mut flags = CGEventFlags::empty();
+    if keystroke.command {
+        flags |= CGEventFlags::CGEventFlagCommand;
+    }
+    if keystroke.control {
+        flags |= CGEventFlags::CGEventFlagControl;
+    }
+    if keystroke.option {
+        flags |= CGEventFlags::CGEventFlagAlternate;
+    }
+    if keystroke.shift {
+        flags |= CGEventFlags::CGEventFlagShift;
+    }
+    key_down.set_flags(flags);
+    key_up.set_flags(flags);
 
     // Post events to Session level (application level, more compatible)
     // Session posts to the current login session which works better across apps
@@ -38,8 +122,254 @@ pub fn simulate_cmd_v() -> Result<(), String> {
     Ok(())
 }
 
-#[cfg(not(target_os = "macos"))]
-pub fn simulate_cmd_v() -> Result<(), String> {
+/// Virtual key code of a key named as in a shortcut: a letter, digit or
+/// punctuation character (`C`, `KeyC`, `Digit1`), or `Space`, `Tab`, `Return`
+/// and the like. Key codes are for the ANSI layout.
+#[cfg(target_os = "macos")]
+pub fn virtual_key_code(name: &str) -> Option<CGKeyCode> {
+    let name = name.trim();
+    let name = name
+        .strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .filter(|rest| rest.chars().count() == 1)
+        .unwrap_or(name);
+
+    let code = match name.to_lowercase().as_str() {
+        "a" => 0,
+        "s" => 1,
+        "d" => 2,
+        "f" => 3,
+        "h" => 4,
+        "g" => 5,
+        "z" => 6,
+        "x" => 7,
+        "c" => 8,
+        "v" => KEY_V,
+        "b" => 11,
+        "q" => 12,
+        "w" => 13,
+        "e" => 14,
+        "r" => 15,
+        "y" => 16,
+        "t" => 17,
+        "1" => 18,
+        "2" => 19,
+        "3" => 20,
+        "4" => 21,
+        "6" => 22,
+        "5" => 23,
+        "=" | "equal" => 24,
+        "9" => 25,
+        "7" => 26,
+        "-" | "minus" => 27,
+        "8" => 28,
+        "0" => 29,
+        "]" | "bracketright" => 30,
+        "o" => 31,
+        "u" => 32,
+        "[" | "bracketleft" => 33,
+        "i" => 34,
+        "p" => 35,
+        "return" | "enter" => KEY_RETURN,
+        "l" => 37,
+        "j" => 38,
+        "'" | "quote" => 39,
+        "k" => 40,
+        ";" | "semicolon" => 41,
+        "\\" | "backslash" => 42,
+        "," | "comma" => 43,
+        "/" | "slash" => 44,
+        "n" => 45,
+        "m" => 46,
+        "." | "period" => 47,
+        "tab" => 48,
+        "space" => 49,
+        "`" | "backquote" => 50,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Virtual key code of the non-modifier key in a shortcut like
+/// `Command+Shift+C`
+#[cfg(target_os = "macos")]
+pub fn shortcut_key_code(shortcut: &str) -> Option<CGKeyCode> {
+    virtual_key_code(shortcut.rsplit('+').next()?)
+}
+
+/// Maximum characters per synthetic key event; CGEventKeyboardSetUnicodeString
+/// silently truncates longer strings
+#[cfg(target_os = "macos")]
+const TYPE_CHUNK_CHARS: usize = 16;
+
+/// Type `text` into the frontmost app as synthetic key events, bypassing the clipboard
+#[cfg(target_os = "macos")]
+pub fn type_text(text: &str) -> Result<(), String> {
+    let source = CGEventSource::new(CGEventSourceStateID::CombinedSessionState)
+        .map_err(|_| "Failed to create CGEventSource")?;
+
+    let chars: Vec<char> = text.chars().collect();
+    for chunk in chars.chunks(TYPE_CHUNK_CHARS) {
+        let chunk: String = chunk.iter().collect();
+
+        let key_down = CGEvent::new_keyboard_event(source.clone(), 0, true)
+            .map_err(|_| "Failed to create key down event")?;
+        key_down.set_string(&chunk);
+        key_down.post(CGEventTapLocation::Session);
+
+        let key_up = CGEvent::new_keyboard_event(source.clone(), 0, false)
+            .map_err(|_| "Failed to create key up event")?;
+        key_up.set_string(&chunk);
+        key_up.post(CGEventTapLocation::Session);
+
+        // Give the target app time to drain its event queue
+        std::thread::sleep(std::time::Duration::from_millis(2));
+    }
+
+    Ok(())
+}
+
+/// Tool used to send synthetic input on Linux
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LinuxBackend {
+    Xdotool,
+    Wtype,
+}
+
+#[cfg(target_os = "linux")]
+fn is_installed(tool: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|dir| dir.join(tool).is_file()))
+        .unwrap_or(false)
+}
+
+/// Pick a backend for the current session. Wayland compositors ignore X11
+/// synthetic input for native windows, so wtype comes first there, with
+/// xdotool left for XWayland apps.
+#[cfg(target_os = "linux")]
+fn linux_backend() -> Result<LinuxBackend, String> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let x11 = std::env::var_os("DISPLAY").is_some();
+
+    if wayland && is_installed("wtype") {
+        return Ok(LinuxBackend::Wtype);
+    }
+    if x11 && is_installed("xdotool") {
+        return Ok(LinuxBackend::Xdotool);
+    }
+
+    Err(match (wayland, x11) {
+        (true, _) => "Paste simulation on Wayland needs wtype installed".to_string(),
+        (false, true) => "Paste simulation on X11 needs xdotool installed".to_string(),
+        (false, false) => "No X11 or Wayland display to send the paste to".to_string(),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn run_tool(tool: &str, args: &[String]) -> Result<(), String> {
+    let output = std::process::Command::new(tool)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", tool, e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} failed: {}",
+            tool,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Type `text` into the focused window, bypassing the clipboard
+#[cfg(target_os = "linux")]
+pub fn type_text(text: &str) -> Result<(), String> {
+    match linux_backend()? {
+        LinuxBackend::Xdotool => run_tool(
+            "xdotool",
+            &["type".into(), "--clearmodifiers".into(), "--".into(), text.into()],
+        ),
+        LinuxBackend::Wtype => run_tool("wtype", &["--".into(), text.into()]),
+    }
+}
+
+/// Send a paste keystroke to the focused window. `command` maps to the
+/// Super key.
+#[cfg(target_os = "linux")]
+pub fn simulate_keystroke(keystroke: Keystroke) -> Result<(), String> {
+    let modifiers: Vec<(&str, &str)> = [
+        (keystroke.control, ("ctrl", "ctrl")),
+        (keystroke.shift, ("shift", "shift")),
+        (keystroke.option, ("alt", "alt")),
+        (keystroke.command, ("super", "logo")),
+    ]
+    .into_iter()
+    .filter_map(|(held, names)| held.then_some(names))
+    .collect();
+    let key = match keystroke.key {
+        PasteKey::V => "v",
+        PasteKey::Insert => "Insert",
+        PasteKey::Return => "Return",
+    };
+
+    match linux_backend()? {
+        LinuxBackend::Xdotool => {
+            let combo: Vec<&str> = modifiers
+                .iter()
+                .map(|(xdotool, _)| *xdotool)
+                .chain(std::iter::once(key))
+                .collect();
+            run_tool(
+                "xdotool",
+                &["key".into(), "--clearmodifiers".into(), combo.join("+")],
+            )
+        }
+        LinuxBackend::Wtype => {
+            // Press the modifiers, tap the key, release them in reverse
+            let mut args: Vec<String> = Vec::new();
+            for (_, wtype) in &modifiers {
+                args.extend(["-M".into(), wtype.to_string()]);
+            }
+            args.extend(["-k".into(), key.to_string()]);
+            for (_, wtype) in modifiers.iter().rev() {
+                args.extend(["-m".into(), wtype.to_string()]);
+            }
+            run_tool("wtype", &args)
+        }
+    }
+}
+
+/// Middle-click at the mouse pointer, which pastes the PRIMARY selection.
+/// wtype can't click, so Wayland sessions need ydotool for this.
+#[cfg(target_os = "linux")]
+pub fn simulate_middle_click() -> Result<(), String> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() && is_installed("ydotool") {
+        // 0xC2: press and release of button 2
+        return run_tool("ydotool", &["click".into(), "0xC2".into()]);
+    }
+    match linux_backend()? {
+        LinuxBackend::Xdotool => run_tool("xdotool", &["click".into(), "2".into()]),
+        LinuxBackend::Wtype => {
+            Err("Pasting from PRIMARY on Wayland needs ydotool installed".to_string())
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn simulate_middle_click() -> Result<(), String> {
+    Err("Middle-click paste is only available on Linux".to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub fn type_text(_text: &str) -> Result<(), String> {
+    Err("Keyboard simulation not implemented for this platform".to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub fn simulate_keystroke(_keystroke: Keystroke) -> Result<(), String> {
     // Not implemented for other platforms
     Err("Keyboard simulation not implemented for this platform".to_string())
 }
@@ -0,0 +1,531 @@
+//! Where the main panel opens and what shape it takes.
+//!
+//! The default layout is a fixed-size panel, opened where
+//! `window_position` says: centered (slightly high) on the display under the
+//! mouse pointer, at the pointer itself, at a saved point, or (experimentally)
+//! under the frontmost app's text caret. The bar layouts
+//! instead dock a full-width strip to the bottom or top edge of the display's
+//! work area, sliding it in from the edge on show and back out on hide.
+//!
+//! `panel_display` picks the display: the cursor's (the default), the one
+//! holding the frontmost app's window, or a specific one by name.
+
+use crate::settings::{
+    LayoutMode, PanelDisplay, PanelLayout, PanelSize, SavedPosition, Settings, WindowPosition,
+};
+use std::time::Duration;
+use tauri::{LogicalPosition, LogicalSize, Monitor, PhysicalPosition, Runtime, WebviewWindow};
+
+/// Size of the panel layout in comfortable mode, matching the window in
+/// tauri.conf.json
+const COMFORTABLE_SIZE: (f64, f64) = (700.0, 500.0);
+
+/// Size of the panel layout in compact mode: a short Spotlight-like strip
+const COMPACT_SIZE: (f64, f64) = (600.0, 280.0);
+
+/// How far above center the panel opens
+const PANEL_RAISE: f64 = 50.0;
+
+/// Gap between the mouse pointer and the panel in `at_mouse_pointer` mode
+const POINTER_OFFSET: f64 = 8.0;
+
+/// Gap between the text caret and the panel in `at_text_caret` mode
+const CARET_GAP: f64 = 4.0;
+
+/// Gap between the tray icon and a panel dropped down from it
+const TRAY_GAP: f64 = 4.0;
+
+/// Size of the preview window opened beside the panel
+pub const PREVIEW_SIZE: (f64, f64) = (420.0, 500.0);
+
+/// Gap between the panel and the preview window
+const PREVIEW_GAP: f64 = 8.0;
+
+const SLIDE_DURATION: Duration = Duration::from_millis(160);
+const SLIDE_STEPS: u32 = 12;
+
+/// A window rectangle in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frame {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Where a bar slides between when it's shown or hidden
+#[derive(Debug, Clone, Copy)]
+pub struct Slide {
+    hidden: Frame,
+    docked: Frame,
+}
+
+/// The display under the mouse pointer, or the primary one
+fn cursor_monitor<R: Runtime>(window: &WebviewWindow<R>) -> Option<Monitor> {
+    window
+        .cursor_position()
+        .ok()
+        .and_then(|cursor| window.monitor_from_point(cursor.x, cursor.y).ok().flatten())
+        .or_else(|| window.primary_monitor().ok().flatten())
+}
+
+/// The display holding the center of the frontmost app's window
+#[cfg(target_os = "macos")]
+fn focused_window_monitor<R: Runtime>(window: &WebviewWindow<R>) -> Option<Monitor> {
+    let (x, y) = crate::window::frontmost_window_center()?;
+    window.available_monitors().ok()?.into_iter().find(|monitor| {
+        let area = logical_bounds(monitor);
+        x >= area.x && x < area.x + area.width && y >= area.y && y < area.y + area.height
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+fn focused_window_monitor<R: Runtime>(window: &WebviewWindow<R>) -> Option<Monitor> {
+    cursor_monitor(window)
+}
+
+/// The connected display called `name`
+fn named_monitor<R: Runtime>(window: &WebviewWindow<R>, name: &str) -> Option<Monitor> {
+    window
+        .available_monitors()
+        .ok()?
+        .into_iter()
+        .find(|monitor| monitor.name().is_some_and(|n| n == name))
+}
+
+/// The display to open on for `settings.panel_display`, falling back to the
+/// cursor's display
+fn target_monitor<R: Runtime>(window: &WebviewWindow<R>, settings: &Settings) -> Option<Monitor> {
+    let preferred = match settings.panel_display {
+        PanelDisplay::Cursor => None,
+        PanelDisplay::FocusedWindow => focused_window_monitor(window),
+        PanelDisplay::Specific => named_monitor(window, &settings.panel_display_name),
+    };
+    preferred.or_else(|| cursor_monitor(window))
+}
+
+/// The whole of `monitor`, in logical pixels
+fn logical_bounds(monitor: &Monitor) -> Frame {
+    let scale = monitor.scale_factor();
+    let position = monitor.position().to_logical::<f64>(scale);
+    let size = monitor.size().to_logical::<f64>(scale);
+    Frame {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    }
+}
+
+/// The mouse pointer in logical pixels on `monitor`
+fn cursor_on<R: Runtime>(window: &WebviewWindow<R>, monitor: &Monitor) -> Option<LogicalPosition<f64>> {
+    let cursor: PhysicalPosition<f64> = window.cursor_position().ok()?;
+    Some(cursor.to_logical(monitor.scale_factor()))
+}
+
+/// The part of `monitor` not covered by the menu bar, dock or taskbar
+fn work_area(monitor: &Monitor) -> Frame {
+    let scale = monitor.scale_factor();
+    let area = monitor.work_area();
+    let position = area.position.to_logical::<f64>(scale);
+    let size = area.size.to_logical::<f64>(scale);
+    Frame {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    }
+}
+
+/// `frame` moved as little as possible to lie within `area`
+fn clamp_to(frame: Frame, area: Frame) -> Frame {
+    let x = frame.x.min(area.x + area.width - frame.width).max(area.x);
+    let y = frame.y.min(area.y + area.height - frame.height).max(area.y);
+    Frame { x, y, ..frame }
+}
+
+/// Name `display_panel_sizes` keys a display's preset by
+fn display_key(monitor: &Monitor) -> Option<String> {
+    monitor.name().cloned()
+}
+
+/// Size of the panel layout on `monitor`: its saved preset, or the size for
+/// `settings.layout_mode`
+fn panel_size(settings: &Settings, monitor: &Monitor) -> (f64, f64) {
+    let preset = display_key(monitor).and_then(|key| settings.display_panel_sizes.get(&key));
+    if let Some(preset) = preset {
+        return (preset.width, preset.height);
+    }
+    match settings.layout_mode {
+        LayoutMode::Comfortable => COMFORTABLE_SIZE,
+        LayoutMode::Compact => COMPACT_SIZE,
+    }
+}
+
+fn centered_panel((width, height): (f64, f64), area: Frame) -> Frame {
+    Frame {
+        x: area.x + (area.width - width) / 2.0,
+        y: area.y + (area.height - height) / 2.0 - PANEL_RAISE,
+        width,
+        height,
+    }
+}
+
+/// The work area of whichever display holds `position`
+fn area_containing<R: Runtime>(window: &WebviewWindow<R>, position: SavedPosition) -> Option<Frame> {
+    window
+        .available_monitors()
+        .ok()?
+        .iter()
+        .map(work_area)
+        .find(|area| {
+            position.x >= area.x
+                && position.x < area.x + area.width
+                && position.y >= area.y
+                && position.y < area.y + area.height
+        })
+}
+
+/// The panel with its top-left corner just off the mouse pointer
+fn at_pointer<R: Runtime>(
+    window: &WebviewWindow<R>,
+    monitor: &Monitor,
+    (width, height): (f64, f64),
+    area: Frame,
+) -> Frame {
+    let Some(cursor) = cursor_on(window, monitor) else {
+        return centered_panel((width, height), area);
+    };
+    let frame = Frame {
+        x: cursor.x + POINTER_OFFSET,
+        y: cursor.y + POINTER_OFFSET,
+        width,
+        height,
+    };
+    clamp_to(frame, area)
+}
+
+/// Bounds of the frontmost app's text caret, in logical pixels
+#[cfg(target_os = "macos")]
+fn text_caret() -> Option<Frame> {
+    let (x, y, width, height) = crate::window::text_caret_bounds()?;
+    Some(Frame {
+        x,
+        y,
+        width,
+        height,
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+fn text_caret() -> Option<Frame> {
+    None
+}
+
+/// The panel just below the caret, or above it when there's no room below
+fn at_caret(caret: Frame, (width, height): (f64, f64), area: Frame) -> Frame {
+    let below = caret.y + caret.height + CARET_GAP;
+    let y = if below + height <= area.y + area.height {
+        below
+    } else {
+        caret.y - height - CARET_GAP
+    };
+    let frame = Frame {
+        x: caret.x,
+        y,
+        width,
+        height,
+    };
+    clamp_to(frame, area)
+}
+
+/// Where the panel layout opens for `settings.window_position`
+fn panel_frame<R: Runtime>(
+    window: &WebviewWindow<R>,
+    settings: &Settings,
+    monitor: &Monitor,
+    area: Frame,
+) -> Frame {
+    let size = panel_size(settings, monitor);
+    match settings.window_position {
+        WindowPosition::CursorMonitorCenter => centered_panel(size, area),
+        WindowPosition::AtMousePointer => at_pointer(window, monitor, size, area),
+        WindowPosition::Fixed => {
+            let saved = settings
+                .fixed_window_position
+                .and_then(|position| Some((position, area_containing(window, position)?)));
+            let Some((position, saved_area)) = saved else {
+                return centered_panel(size, area);
+            };
+            let (width, height) = size;
+            let frame = Frame {
+                x: position.x,
+                y: position.y,
+                width,
+                height,
+            };
+            clamp_to(frame, saved_area)
+        }
+        WindowPosition::AtTextCaret => {
+            // The caret's display, which may not be the target one
+            let caret = text_caret().and_then(|caret| {
+                let position = SavedPosition {
+                    x: caret.x,
+                    y: caret.y,
+                };
+                Some((caret, area_containing(window, position)?))
+            });
+            match caret {
+                Some((caret, caret_area)) => at_caret(caret, size, caret_area),
+                None => at_pointer(window, monitor, size, area),
+            }
+        }
+    }
+}
+
+/// Where a bar goes in `area` for `settings.panel_layout`
+fn docked_frame(settings: &Settings, area: Frame) -> Frame {
+    let height = (settings.bar_height as f64).min(area.height);
+    let y = if settings.panel_layout == PanelLayout::BarTop {
+        area.y
+    } else {
+        area.y + area.height - height
+    };
+    Frame {
+        x: area.x,
+        y,
+        width: area.width,
+        height,
+    }
+}
+
+/// `docked` moved just past the edge it's docked to
+fn hidden_frame(settings: &Settings, area: Frame, docked: Frame) -> Frame {
+    let y = match settings.panel_layout {
+        PanelLayout::BarTop => area.y - docked.height,
+        _ => area.y + area.height,
+    };
+    Frame { y, ..docked }
+}
+
+fn set_position<R: Runtime>(window: &WebviewWindow<R>, frame: Frame) -> Result<(), String> {
+    window
+        .set_position(LogicalPosition::new(frame.x, frame.y))
+        .map_err(|e| e.to_string())
+}
+
+fn apply<R: Runtime>(window: &WebviewWindow<R>, frame: Frame) -> Result<(), String> {
+    window
+        .set_size(LogicalSize::new(frame.width, frame.height))
+        .map_err(|e| e.to_string())?;
+    set_position(window, frame)
+}
+
+/// Size and position the panel before it's shown. When `animated`, bars are
+/// left just off their edge, ready for `slide_in`.
+pub fn place<R: Runtime>(
+    window: &WebviewWindow<R>,
+    settings: &Settings,
+    animated: bool,
+) -> Result<Option<Slide>, String> {
+    let monitor = target_monitor(window, settings)
+        .ok_or_else(|| "No display to show the panel on".to_string())?;
+    let area = work_area(&monitor);
+
+    if settings.panel_layout == PanelLayout::Panel {
+        apply(window, panel_frame(window, settings, &monitor, area))?;
+        return Ok(None);
+    }
+
+    let docked = docked_frame(settings, area);
+    if !animated {
+        apply(window, docked)?;
+        return Ok(None);
+    }
+    let hidden = hidden_frame(settings, area, docked);
+    apply(window, hidden)?;
+    Ok(Some(Slide { hidden, docked }))
+}
+
+/// Size and position the panel as a popover from the tray icon at `icon`,
+/// dropping down from a menu bar icon or rising from a taskbar one. Bar
+/// layouts ignore the icon and dock as usual.
+pub fn place_at_tray<R: Runtime>(
+    window: &WebviewWindow<R>,
+    settings: &Settings,
+    icon: tauri::Rect,
+    animated: bool,
+) -> Result<Option<Slide>, String> {
+    if settings.panel_layout != PanelLayout::Panel {
+        return place(window, settings, animated);
+    }
+
+    // Tray rects are physical, so find the display before converting
+    let probe = icon.position.to_physical::<f64>(1.0);
+    let monitor = window
+        .monitor_from_point(probe.x, probe.y)
+        .ok()
+        .flatten()
+        .or_else(|| cursor_monitor(window))
+        .ok_or_else(|| "No display to show the panel on".to_string())?;
+    let scale = monitor.scale_factor();
+    let position = icon.position.to_logical::<f64>(scale);
+    let icon_size = icon.size.to_logical::<f64>(scale);
+    let area = work_area(&monitor);
+
+    let (width, height) = panel_size(settings, &monitor);
+    let bounds = logical_bounds(&monitor);
+    let below_icon = position.y < bounds.y + bounds.height / 2.0;
+    let y = if below_icon {
+        position.y + icon_size.height + TRAY_GAP
+    } else {
+        position.y - height - TRAY_GAP
+    };
+    let frame = Frame {
+        x: position.x + icon_size.width / 2.0 - width / 2.0,
+        y,
+        width,
+        height,
+    };
+    apply(window, clamp_to(frame, area))?;
+    Ok(None)
+}
+
+async fn animate<R: Runtime>(window: &WebviewWindow<R>, from: Frame, to: Frame) {
+    let step_delay = SLIDE_DURATION / SLIDE_STEPS;
+    for step in 1..=SLIDE_STEPS {
+        // Ease out: fast at first, settling into place
+        let t = step as f64 / SLIDE_STEPS as f64;
+        let eased = 1.0 - (1.0 - t).powi(3);
+        let frame = Frame {
+            y: from.y + (to.y - from.y) * eased,
+            ..to
+        };
+        if set_position(window, frame).is_err() {
+            return;
+        }
+        tokio::time::sleep(step_delay).await;
+    }
+}
+
+/// Slide a just-shown bar from its edge into place
+pub async fn slide_in<R: Runtime>(window: &WebviewWindow<R>, slide: Slide) {
+    animate(window, slide.hidden, slide.docked).await;
+}
+
+/// Slide a bar back past its edge before it's hidden. Does nothing for the
+/// panel layout.
+pub async fn slide_out<R: Runtime>(window: &WebviewWindow<R>, settings: &Settings) {
+    if settings.panel_layout == PanelLayout::Panel {
+        return;
+    }
+    let Some(monitor) = window.current_monitor().ok().flatten() else {
+        return;
+    };
+    let area = work_area(&monitor);
+    let docked = docked_frame(settings, area);
+    animate(window, docked, hidden_frame(settings, area, docked)).await;
+}
+
+/// Where the panel is now, for saving as its `fixed` position
+pub fn current_position<R: Runtime>(window: &WebviewWindow<R>) -> Result<SavedPosition, String> {
+    let scale = window.scale_factor().map_err(|e| e.to_string())?;
+    let position = window
+        .outer_position()
+        .map_err(|e| e.to_string())?
+        .to_logical::<f64>(scale);
+    Ok(SavedPosition {
+        x: position.x,
+        y: position.y,
+    })
+}
+
+/// Where the preview window goes: to the right of `panel` if it fits on the
+/// panel's display, otherwise to its left, top edges aligned
+pub fn beside<R: Runtime>(panel: &WebviewWindow<R>) -> Result<LogicalPosition<f64>, String> {
+    let monitor = panel
+        .current_monitor()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Panel isn't on a display".to_string())?;
+    let scale = monitor.scale_factor();
+    let position = panel
+        .outer_position()
+        .map_err(|e| e.to_string())?
+        .to_logical::<f64>(scale);
+    let size = panel
+        .outer_size()
+        .map_err(|e| e.to_string())?
+        .to_logical::<f64>(scale);
+    let area = work_area(&monitor);
+
+    let (width, height) = PREVIEW_SIZE;
+    let right = position.x + size.width + PREVIEW_GAP;
+    let x = if right + width <= area.x + area.width {
+        right
+    } else {
+        position.x - PREVIEW_GAP - width
+    };
+    let frame = clamp_to(
+        Frame {
+            x,
+            y: position.y,
+            width,
+            height,
+        },
+        area,
+    );
+    Ok(LogicalPosition::new(frame.x, frame.y))
+}
+
+/// Resize an open panel for a new `layout_mode` or display preset, keeping
+/// it centered where it is. Bars keep their size.
+pub fn resize<R: Runtime>(window: &WebviewWindow<R>, settings: &Settings) -> Result<(), String> {
+    if settings.panel_layout != PanelLayout::Panel {
+        return Ok(());
+    }
+    let monitor = window
+        .current_monitor()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Panel isn't on a display".to_string())?;
+    let scale = monitor.scale_factor();
+    let position = window
+        .outer_position()
+        .map_err(|e| e.to_string())?
+        .to_logical::<f64>(scale);
+    let size = window
+        .outer_size()
+        .map_err(|e| e.to_string())?
+        .to_logical::<f64>(scale);
+
+    let (width, height) = panel_size(settings, &monitor);
+    let frame = Frame {
+        x: position.x + (size.width - width) / 2.0,
+        y: position.y + (size.height - height) / 2.0,
+        width,
+        height,
+    };
+    apply(window, clamp_to(frame, work_area(&monitor)))
+}
+
+/// The display the panel is on and its size there, for saving as that
+/// display's preset
+pub fn current_display_size<R: Runtime>(
+    window: &WebviewWindow<R>,
+) -> Result<(String, PanelSize), String> {
+    let monitor = window
+        .current_monitor()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Panel isn't on a display".to_string())?;
+    let key = display_key(&monitor).ok_or_else(|| "This display has no name".to_string())?;
+    let size = window
+        .outer_size()
+        .map_err(|e| e.to_string())?
+        .to_logical::<f64>(monitor.scale_factor());
+    Ok((
+        key,
+        PanelSize {
+            width: size.width,
+            height: size.height,
+        },
+    ))
+}
@@ -1,11 +1,48 @@
+mod app_icons;
+mod boards;
+mod browser;
+mod capture;
 mod clipboard;
 mod collections;
+mod color;
+mod contact;
+mod context_menu;
+mod daily_notes;
 mod database;
+mod displays;
+mod drag;
+mod enrich;
+mod entitlements;
 mod exclusions;
+mod flashback;
 mod hotkey;
 mod keyboard;
+mod layout;
+mod lint;
+mod ocr;
+mod palette;
+mod paste_helper;
+mod pasteboard;
+mod perf;
+mod permissions;
+mod pinned_board;
+mod portable;
+mod provenance;
+mod power;
+mod preview;
 mod qrcode;
+mod queue;
+mod safe_mode;
+mod search;
+mod semantic;
+mod session;
 mod settings;
+mod stack;
+mod suggestions;
+mod symbols;
+mod templates;
+mod titles;
+mod transforms;
 mod window;
 
 use clipboard::ClipboardMonitor;
@@ -24,7 +61,7 @@ use window::SelectedItemState;
 use tauri::{
     image::Image,
     menu::{MenuBuilder, MenuItemBuilder},
-    tray::TrayIconBuilder,
+    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     Manager,
 };
 
@@ -49,25 +86,130 @@ pub fn run() {
             #[cfg(target_os = "macos")]
             app.set_activation_policy(ActivationPolicy::Accessory);
 
-            // Get app data directory
-            let app_data_dir = app
-                .path()
-                .app_data_dir()
-                .expect("Failed to get app data directory");
+            // Safe mode skips everything a bad setting could break
+            let safe_mode = safe_mode::requested();
+            if safe_mode {
+                log::warn!("Starting in safe mode: capture paused, hotkeys not registered");
+            }
+            app.manage(safe_mode::SafeMode(safe_mode));
+
+            // Get app data directory (next to the executable in portable mode)
+            let app_data_dir =
+                portable::data_dir(app.handle()).expect("Failed to get app data directory");
+            if portable::portable_data_dir().is_some() {
+                log::info!("Portable mode, storing data in {}", app_data_dir.display());
+            }
+
+            // Initialize database. A failed upgrade is rolled back; the session
+            // then runs on an empty in-memory database and the frontend is told
+            // where the pre-upgrade snapshot is.
+            let mut migration_failure = None;
+            let db = match Database::new(app_data_dir.clone()) {
+                Ok(db) => db,
+                Err(database::InitError::Migration(failure)) => {
+                    log::error!("{}", failure);
+                    migration_failure = Some(failure);
+                    Database::in_memory().expect("Failed to initialize database")
+                }
+                Err(e) => panic!("Failed to initialize database: {}", e),
+            };
+            // Drop anything that expired while the app wasn't running
+            if let Err(e) = db.cleanup_expired() {
+                log::warn!("Failed to clean up expired items: {}", e);
+            }
 
-            // Initialize database
-            let db =
-                Database::new(app_data_dir.clone()).expect("Failed to initialize database");
+            // Forward granular item changes so the frontend can patch its lists
+            let app_handle = app.handle().clone();
+            db.subscribe(move |event| {
+                let _ = match event {
+                    database::ItemEvent::Added(item) => {
+                        tauri::Emitter::emit(&app_handle, "item-added", item)
+                    }
+                    database::ItemEvent::Updated(item) => {
+                        tauri::Emitter::emit(&app_handle, "item-updated", item)
+                    }
+                    database::ItemEvent::Deleted(ids) => tauri::Emitter::emit(
+                        &app_handle,
+                        "item-deleted",
+                        serde_json::json!({ "ids": ids }),
+                    ),
+                };
+            });
             app.manage(db);
 
+            if let Some(failure) = migration_failure.clone() {
+                let app_handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    // Give the webview time to load and subscribe
+                    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+                    let _ = tauri::Emitter::emit(&app_handle, "database-migration-failed", &failure);
+                });
+            }
+            app.manage(database::MigrationFailureState(migration_failure));
+
+            // Initialize Free/Pro entitlements (checked on every invoke)
+            let entitlements = entitlements::EntitlementManager::new(app_data_dir.clone());
+            if let Some(db) = app.try_state::<database::Database>() {
+                entitlements.sync_trial(&db);
+            }
+            app.manage(entitlements);
+
+            // Local performance counters (see get_perf_metrics)
+            app.manage(perf::PerfMonitor::new());
+
+            // Cache of source app icons for the item list
+            app.manage(app_icons::AppIconCache::new(app_data_dir.clone()));
+
             // Initialize settings
             let settings_manager = SettingsManager::new(app_data_dir);
             let settings = settings_manager.get();
             app.manage(settings_manager);
 
+            // Apply age-based retention and purge long-trashed items now,
+            // then hourly while running
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    if let (Some(db), Some(settings_manager)) = (
+                        app_handle.try_state::<Database>(),
+                        app_handle.try_state::<SettingsManager>(),
+                    ) {
+                        let settings = settings_manager.get();
+                        clipboard::evict_aged_items(&db, &settings);
+                        clipboard::purge_old_trash(&db, &settings);
+                    }
+                    tokio::time::sleep(tokio::time::Duration::from_secs(3600)).await;
+                }
+            });
+
             // Initialize hotkey manager
             let hotkey_manager = HotkeyManager::new();
-            let _ = hotkey_manager.register(&app.handle(), &settings.hotkey);
+            if !safe_mode {
+                let _ = hotkey_manager.register(&app.handle(), &settings.hotkey);
+                if let Err(e) = hotkey_manager.register_ocr_hotkey(&app.handle(), &settings.ocr_hotkey) {
+                    log::warn!("Failed to register OCR hotkey: {}", e);
+                }
+                if let Err(e) = hotkey_manager.register_append_hotkey(&app.handle(), &settings.append_mode_hotkey) {
+                    log::warn!("Failed to register append mode hotkey: {}", e);
+                }
+                if let Err(e) = hotkey_manager.register_plain_paste_hotkey(&app.handle(), &settings.plain_paste_hotkey) {
+                    log::warn!("Failed to register plain paste hotkey: {}", e);
+                }
+                if let Err(e) = hotkey_manager.register_queue_hotkey(&app.handle(), &settings.queue_paste_hotkey) {
+                    log::warn!("Failed to register queue paste hotkey: {}", e);
+                }
+                if let Err(e) = hotkey_manager.register_paste_previous_hotkey(&app.handle(), &settings.paste_previous_hotkey) {
+                    log::warn!("Failed to register paste previous hotkey: {}", e);
+                }
+                if let Err(e) = hotkey_manager.register_pinned_hotkeys(&app.handle(), &settings.pinned_paste_modifiers) {
+                    log::warn!("Failed to register pinned item hotkeys: {}", e);
+                }
+                if let Some(db) = app.try_state::<Database>() {
+                    if let Ok(boards) = db.get_boards() {
+                        let _ = hotkey_manager.register_board_hotkeys(&app.handle(), &boards);
+                    }
+                }
+            }
             app.manage(hotkey_manager);
 
             // Initialize clipboard monitor
@@ -75,8 +217,17 @@ pub fn run() {
             if let Some(db) = app.try_state::<Database>() {
                 clipboard_monitor.init_last_hash(&db);
             }
+            if safe_mode {
+                clipboard_monitor.pause(None);
+            }
             app.manage(clipboard_monitor);
 
+            // Start the browser extension companion endpoint (if enabled)
+            browser::start(app.handle());
+
+            // Start semantic indexing (only with the semantic-search feature)
+            semantic::start(app.handle());
+
             // Initialize previous app state tracker (for restoring focus after hiding)
             #[cfg(target_os = "macos")]
             app.manage(PreviousAppState::new());
@@ -85,12 +236,46 @@ pub fn run() {
             #[cfg(target_os = "macos")]
             app.manage(PanelHideGuard::new());
 
+            // Initialize the delayed auto-hide timer (for hiding a while after focus loss)
+            #[cfg(target_os = "macos")]
+            app.manage(window::AutoHideTimer::new());
+
             // Initialize hotkey mode state (for preventing auto-hide while modifiers held)
             app.manage(HotkeyModeState::new());
 
             // Initialize selected item state (for hotkey mode paste on modifier release)
             app.manage(SelectedItemState::new());
 
+            // Initialize the tray icon anchor (for dropping the panel down from the tray)
+            app.manage(window::TrayAnchor::new());
+
+            // Initialize paste confirmation state (for pre-paste lint warnings)
+            app.manage(lint::PasteConfirmationState::new());
+
+            // Initialize the queue mode paste queue
+            app.manage(queue::PasteQueue::new());
+
+            // Initialize the paste stack (for stack mode)
+            app.manage(stack::PasteStack::new());
+
+            // Initialize the flashback paste cycle (for the paste previous hotkey)
+            app.manage(flashback::FlashbackState::new());
+
+            // Track the item shown in the preview window
+            app.manage(preview::PreviewState::new());
+
+            // Pause capture and hide the panel while the user is switched out or locked
+            session::start(app.handle());
+
+            // Restore hotkeys and clipboard baseline after system sleep
+            power::start(app.handle());
+
+            // Keep windows on a real display when monitors are unplugged or rearranged
+            displays::start(app.handle());
+
+            // Reopen the pinned board if it was left on
+            pinned_board::start(app.handle());
+
             // Start modifier key polling for hotkey mode paste-on-release (macOS)
             #[cfg(target_os = "macos")]
             {
@@ -107,24 +292,39 @@ pub fn run() {
 
                     // macOS virtual key codes
                     const VK_ESCAPE: u16 = 53;
-                    const VK_V: u16 = 9;
+                    // Top-row digits 1-9, which pick frequent symbols
+                    const VK_DIGITS: [u16; symbols::HOTKEY_SYMBOLS] =
+                        [18, 19, 20, 21, 23, 22, 26, 28, 25];
 
                     let mut was_active = false;
-                    let mut v_was_pressed = false;
+                    // The hotkey's own key cycles, e.g. V for Cmd+Shift+V
+                    let mut cycle_key = keyboard::KEY_V;
+                    let mut cycle_was_pressed = false;
+                    let mut digits_were_pressed = [false; symbols::HOTKEY_SYMBOLS];
 
                     loop {
                         // Poll every 30ms - fast enough to feel instant
                         std::thread::sleep(std::time::Duration::from_millis(30));
 
+                        // Nothing to do while the session is in the background
+                        if !session::is_session_active(&app_handle) {
+                            std::thread::sleep(std::time::Duration::from_secs(1));
+                            continue;
+                        }
+
                         // Only check when hotkey mode is active
                         let is_active = app_handle
                             .try_state::<HotkeyModeState>()
                             .map_or(false, |s| s.is_active());
 
                         // Unregister global shortcut when hotkey mode enters
-                        // so V keydown events aren't consumed by the shortcut system
+                        // so cycle keydown events aren't consumed by the shortcut system
                         if is_active && !was_active {
-                            v_was_pressed = true; // V is held from activation
+                            cycle_key = app_handle
+                                .try_state::<SettingsManager>()
+                                .and_then(|s| keyboard::shortcut_key_code(&s.get().hotkey))
+                                .unwrap_or(keyboard::KEY_V);
+                            cycle_was_pressed = true; // The key is held from activation
                             if let Some(hotkey_mgr) =
                                 app_handle.try_state::<HotkeyManager>()
                             {
@@ -134,7 +334,7 @@ pub fn run() {
 
                         // Re-register global shortcut when hotkey mode exits
                         if !is_active && was_active {
-                            v_was_pressed = false;
+                            cycle_was_pressed = false;
                             if let Some(hotkey_mgr) =
                                 app_handle.try_state::<HotkeyManager>()
                             {
@@ -158,16 +358,29 @@ pub fn run() {
                             CGEventSourceKeyState(1, VK_ESCAPE)
                         };
 
-                        // Also detect V key for cycling (edge-detect: only on new press)
+                        // Also detect the cycle key (edge-detect: only on new press)
                         // Try both HID state (1) and combined session state (0)
-                        let v_pressed = unsafe {
-                            CGEventSourceKeyState(1, VK_V)
-                            || CGEventSourceKeyState(0, VK_V)
+                        let cycle_pressed = unsafe {
+                            CGEventSourceKeyState(1, cycle_key)
+                            || CGEventSourceKeyState(0, cycle_key)
                         };
-                        if v_pressed && !v_was_pressed {
+                        if cycle_pressed && !cycle_was_pressed {
                             let _ = app_handle.emit("hotkey-cycle", ());
                         }
-                        v_was_pressed = v_pressed;
+                        cycle_was_pressed = cycle_pressed;
+
+                        // Digits select a frequent symbol to paste on release
+                        for (index, key) in VK_DIGITS.iter().enumerate() {
+                            // A digit hotkey cycles instead
+                            if *key == cycle_key {
+                                continue;
+                            }
+                            let pressed = unsafe { CGEventSourceKeyState(1, *key) };
+                            if pressed && !digits_were_pressed[index] {
+                                symbols::select_for_hotkey(&app_handle, index);
+                            }
+                            digits_were_pressed[index] = pressed;
+                        }
                         if esc_pressed {
                             if let Some(hotkey_state) =
                                 app_handle.try_state::<HotkeyModeState>()
@@ -219,7 +432,9 @@ pub fn run() {
                                             tauri::async_runtime::spawn(async move {
                                                 if let Err(e) =
                                                     crate::clipboard::do_paste_and_simulate(
-                                                        app, item_id,
+                                                        app,
+                                                        item_id,
+                                                        Default::default(),
                                                     )
                                                     .await
                                                 {
@@ -274,24 +489,66 @@ pub fn run() {
                 }
             }
 
+            #[cfg(not(target_os = "macos"))]
+            if let Some(window) = app.get_webview_window(window::MAIN_WINDOW_LABEL) {
+                if let Err(e) = window::set_window_blur(&window, true) {
+                    log::warn!("Failed to apply window blur: {}", e);
+                }
+            }
+
             // Setup system tray
             setup_tray(app)?;
 
+            // Run history item context menu actions
+            app.on_menu_event(context_menu::handle_event);
+
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(entitlements::gate(tauri::generate_handler![
             // Clipboard commands
             clipboard::check_clipboard,
             clipboard::get_clipboard_items,
+            clipboard::get_items_meta,
+            clipboard::get_item_content,
+            clipboard::get_items_by_size,
             clipboard::get_pinned_items,
             clipboard::delete_clipboard_item,
+            clipboard::delete_items,
+            clipboard::dedupe_history,
+            clipboard::get_item_versions,
+            clipboard::revert_item_version,
+            clipboard::restore_item,
+            clipboard::get_trash,
+            clipboard::unarchive_item,
+            clipboard::clear_archive,
+            clipboard::empty_trash,
             clipboard::pin_item,
             clipboard::unpin_item,
+            clipboard::pin_items,
             clipboard::clear_history,
             clipboard::paste_item,
             clipboard::paste_and_simulate,
+            clipboard::paste_item_plain,
+            clipboard::paste_item_and_submit,
+            clipboard::paste_item_to_app,
+            clipboard::paste_items,
+            clipboard::paste_item_transformed,
+            transforms::get_transforms,
+            transforms::apply_transforms,
+            clipboard::get_paste_variants,
+            clipboard::paste_variant,
+            drag::prepare_drag_item,
+            clipboard::confirm_paste,
+            lint::cancel_paste,
             clipboard::move_to_collection,
+            clipboard::move_items_to_collection,
             clipboard::set_expiration,
+            clipboard::set_append_mode,
+            clipboard::get_append_mode,
+            clipboard::pause_monitoring,
+            clipboard::resume_monitoring,
+            clipboard::get_monitoring_state,
+            context_menu::show_item_context_menu,
             // Window commands
             window::show_window,
             window::hide_window,
@@ -301,46 +558,145 @@ pub fn run() {
             window::exit_hotkey_mode,
             window::set_selected_item,
             window::is_hotkey_mode_active,
+            window::save_window_position,
+            window::set_layout_mode,
+            window::save_display_panel_size,
+            window::clear_display_panel_size,
+            window::set_panel_level,
+            window::set_panel_spaces,
+            window::set_tray_anchored_panel,
+            displays::list_displays,
+            // Pinned board commands
+            pinned_board::set_pinned_board,
+            pinned_board::paste_from_board,
+            // Preview window commands
+            preview::show_preview,
+            preview::set_preview_item,
+            preview::get_preview,
+            preview::render_quicklook,
+            preview::close_preview,
             // Settings commands
             settings::get_settings,
             settings::update_settings,
             settings::set_hotkey,
+            settings::reset_to_defaults,
             settings::set_theme,
             settings::set_accent_color,
             settings::add_excluded_app,
             settings::remove_excluded_app,
+            settings::set_paste_override,
             settings::toggle_queue_mode,
+            queue::get_queue,
+            queue::clear_queue,
+            queue::skip_queue_item,
+            queue::paste_next_in_queue,
+            stack::set_stack_mode,
+            stack::get_stack,
+            stack::push_to_stack,
+            stack::remove_from_stack,
+            stack::clear_stack,
+            stack::paste_from_stack,
             // Hotkey commands
             hotkey::register_hotkey,
+            hotkey::register_ocr_hotkey,
+            hotkey::register_append_hotkey,
+            hotkey::register_plain_paste_hotkey,
+            hotkey::register_queue_hotkey,
+            hotkey::register_paste_previous_hotkey,
+            hotkey::register_pinned_hotkeys,
             hotkey::validate_hotkey,
+            // Permission commands
+            permissions::check_accessibility_permission,
+            permissions::request_accessibility_permission,
+            // OCR commands
+            ocr::capture_text_from_screen,
             // Exclusions commands
             exclusions::get_current_app,
             exclusions::check_app_excluded,
+            app_icons::get_app_icon,
             // Collections commands
             collections::create_collection,
             collections::get_collections,
             collections::delete_collection,
             collections::update_collection,
+            collections::set_collection_protected,
+            collections::set_collection_template,
             collections::create_tag,
             collections::get_tags,
             collections::add_tag_to_item,
+            collections::tag_items,
             collections::remove_tag_from_item,
             collections::get_item_tags,
+            collections::link_items,
+            collections::unlink_items,
+            collections::get_related_items,
+            provenance::get_derived_items,
+            provenance::rerun_derivation,
+            // Board commands
+            boards::create_board,
+            boards::get_boards,
+            boards::update_board,
+            boards::delete_board,
+            boards::add_item_to_board,
+            boards::remove_item_from_board,
+            boards::get_board_items,
+            // Browser extension commands
+            browser::get_browser_companion_info,
+            // Suggestions
+            suggestions::get_suggestions,
+            symbols::get_frequent_symbols,
+            // Semantic search
+            semantic::semantic_search,
+            // Title commands
+            titles::set_item_title,
+            titles::generate_item_title,
+            // Template commands
+            templates::get_template_variables,
+            templates::resolve_template,
+            // Color commands
+            color::convert_color,
+            color::copy_color_as,
+            // Palette commands
+            palette::get_item_palette,
+            palette::copy_palette_color,
             // QR code command
             qrcode::generate_qr_code,
-        ])
+            // Session commands
+            session::get_session_state,
+            // Safe mode command
+            safe_mode::get_safe_mode,
+            // Portable mode command
+            portable::get_data_location,
+            // Performance commands
+            perf::get_perf_metrics,
+            perf::reset_perf_metrics,
+            perf::report_ipc_latency,
+            // Database commands
+            database::get_migration_failure,
+            database::get_db_stats,
+            database::db_vacuum,
+            database::db_integrity_check,
+            database::db_optimize,
+            // Entitlement commands
+            entitlements::get_entitlements,
+            entitlements::refresh_entitlements,
+            entitlements::start_trial,
+            entitlements::clear_entitlements,
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 
 fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     let open_item = MenuItemBuilder::with_id("open", "Open Yoink").build(app)?;
+    let append_item = MenuItemBuilder::with_id("append_mode", "Toggle Append Mode").build(app)?;
     let settings_item = MenuItemBuilder::with_id("settings", "Settings").build(app)?;
     let upgrade_item = MenuItemBuilder::with_id("upgrade", "Upgrade to Pro").build(app)?;
     let quit_item = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
 
     let menu = MenuBuilder::new(app)
         .item(&open_item)
+        .item(&append_item)
         .separator()
         .item(&settings_item)
         .item(&upgrade_item)
@@ -352,18 +708,59 @@ fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     let icon = Image::from_bytes(include_bytes!("../icons/icon.png"))
         .expect("Failed to load tray icon");
 
-    let _tray = TrayIconBuilder::new()
+    // With the tray popover on, left clicks toggle the panel instead
+    let tray_anchored = window::current_settings(app.handle()).tray_anchored_panel;
+
+    let _tray = TrayIconBuilder::with_id(window::TRAY_ID)
         .icon(icon)
         .icon_as_template(true)
         .menu(&menu)
-        .show_menu_on_left_click(true)
+        .show_menu_on_left_click(!tray_anchored)
+        .on_tray_icon_event(|tray, event| {
+            let app = tray.app_handle();
+            let Some(anchor) = app.try_state::<window::TrayAnchor>() else {
+                return;
+            };
+            // Every tray event carries the icon's rect; keep the latest
+            let rect = match &event {
+                TrayIconEvent::Click { rect, .. }
+                | TrayIconEvent::DoubleClick { rect, .. }
+                | TrayIconEvent::Enter { rect, .. }
+                | TrayIconEvent::Move { rect, .. }
+                | TrayIconEvent::Leave { rect, .. } => *rect,
+                _ => return,
+            };
+            anchor.record(rect);
+
+            let left_click = matches!(
+                event,
+                TrayIconEvent::Click {
+                    button: MouseButton::Left,
+                    button_state: MouseButtonState::Up,
+                    ..
+                }
+            );
+            let anchored = window::current_settings(app).tray_anchored_panel;
+            if left_click && anchored {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    let _ = window::toggle_window_from_tray(app).await;
+                });
+            }
+        })
         .on_menu_event(|app, event| match event.id().as_ref() {
             "open" => {
+                if let Some(anchor) = app.try_state::<window::TrayAnchor>() {
+                    anchor.request();
+                }
                 let app = app.clone();
                 tauri::async_runtime::spawn(async move {
                     let _ = window::show_window(app).await;
                 });
             }
+            "append_mode" => {
+                clipboard::toggle_append_mode(app);
+            }
             "settings" => {
                 let app = app.clone();
                 tauri::async_runtime::spawn(async move {
@@ -384,6 +781,7 @@ fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
                     .open("https://yoink.app/upgrade", None);
             }
             "quit" => {
+                clipboard::flush_pending_captures(app);
                 app.exit(0);
             }
             _ => {}
@@ -0,0 +1,147 @@
+//! Pre-paste checks that ask the user to confirm risky pastes
+//!
+//! When a check fires, `do_paste_and_simulate` emits `paste-confirmation-required`
+//! instead of pasting; the UI answers with `confirm_paste` or `cancel_paste`.
+
+use crate::paste_helper;
+use crate::settings::Settings;
+use serde::Serialize;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LintWarning {
+    pub kind: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PasteConfirmationRequest {
+    pub item_id: String,
+    pub target_app: Option<String>,
+    pub warnings: Vec<LintWarning>,
+}
+
+/// Item the user has confirmed despite lint warnings; consumed by the next paste of it
+pub struct PasteConfirmationState {
+    approved: Mutex<Option<String>>,
+}
+
+impl PasteConfirmationState {
+    pub fn new() -> Self {
+        Self {
+            approved: Mutex::new(None),
+        }
+    }
+
+    pub fn approve(&self, id: String) {
+        *self.approved.lock().unwrap() = Some(id);
+    }
+
+    pub fn clear(&self) {
+        self.approved.lock().unwrap().take();
+    }
+
+    /// Returns true (once) if `id` was approved
+    pub fn take_approval(&self, id: &str) -> bool {
+        let mut approved = self.approved.lock().unwrap();
+        if approved.as_deref() == Some(id) {
+            approved.take();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Run the configured checks for pasting `content` into `target_app`
+pub fn lint_paste(settings: &Settings, content: &str, target_app: Option<&str>) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+
+    if !settings.paste_lint_enabled {
+        return warnings;
+    }
+
+    let Some(target) = target_app else {
+        return warnings;
+    };
+
+    if paste_helper::is_chat_app(target) {
+        if let Some(kind) = detect_secret(content) {
+            warnings.push(LintWarning {
+                kind: "secret".to_string(),
+                message: format!("This looks like a {} and you're pasting into a chat app", kind),
+            });
+        }
+    }
+
+    let max_lines = settings.paste_lint_max_terminal_lines;
+    if max_lines > 0 && paste_helper::is_terminal(target) {
+        let lines = content.lines().count();
+        if lines > max_lines as usize {
+            warnings.push(LintWarning {
+                kind: "terminal_lines".to_string(),
+                message: format!("Pasting {} lines into a terminal", lines),
+            });
+        }
+    }
+
+    warnings
+}
+
+/// Heuristic secret detection; returns a human-readable name for what was found
+pub fn detect_secret(content: &str) -> Option<&'static str> {
+    if content.contains("-----BEGIN") && content.contains("PRIVATE KEY-----") {
+        return Some("private key");
+    }
+
+    for token in content.split(|c: char| c.is_whitespace() || c == '"' || c == '\'' || c == '=') {
+        if token.len() == 20
+            && token.starts_with("AKIA")
+            && token.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        {
+            return Some("AWS access key");
+        }
+
+        if ["ghp_", "gho_", "ghs_", "github_pat_"]
+            .iter()
+            .any(|p| token.starts_with(p))
+            && token.len() >= 36
+        {
+            return Some("GitHub token");
+        }
+
+        if ["xoxb-", "xoxp-", "xoxa-"].iter().any(|p| token.starts_with(p)) {
+            return Some("Slack token");
+        }
+
+        if token.starts_with("sk-") && token.len() >= 32 {
+            return Some("API key");
+        }
+
+        if is_jwt(token) {
+            return Some("JSON Web Token");
+        }
+    }
+
+    None
+}
+
+fn is_jwt(token: &str) -> bool {
+    let parts: Vec<&str> = token.split('.').collect();
+    parts.len() == 3
+        && parts[0].starts_with("eyJ")
+        && parts[1].starts_with("eyJ")
+        && parts.iter().all(|p| {
+            !p.is_empty()
+                && p.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+#[tauri::command]
+pub async fn cancel_paste(
+    confirmation_state: tauri::State<'_, PasteConfirmationState>,
+) -> Result<(), String> {
+    confirmation_state.clear();
+    Ok(())
+}
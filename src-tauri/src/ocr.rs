@@ -0,0 +1,164 @@
+/// Text recognition on images, plus the capture → OCR → history flow
+use crate::capture;
+use crate::clipboard::{self, ClipboardMonitor};
+use crate::database::{ClipboardItem, Database, Provenance};
+use crate::settings::SettingsManager;
+use std::path::Path;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+#[cfg(target_os = "macos")]
+#[link(name = "Vision", kind = "framework")]
+extern "C" {}
+
+/// Recognize text in the image at `path` using the Vision framework.
+/// Lines are returned top to bottom, joined with newlines.
+#[cfg(target_os = "macos")]
+pub fn recognize_text(path: &Path) -> Result<String, String> {
+    use cocoa::base::{id, nil, BOOL, NO, YES};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    let path = path
+        .to_str()
+        .ok_or_else(|| "Invalid image path".to_string())?;
+
+    unsafe {
+        let pool: id = msg_send![class!(NSAutoreleasePool), new];
+
+        let ns_path = NSString::alloc(nil).init_str(path);
+        let url: id = msg_send![class!(NSURL), fileURLWithPath: ns_path];
+        let options: id = msg_send![class!(NSDictionary), dictionary];
+
+        let handler: id = msg_send![class!(VNImageRequestHandler), alloc];
+        let handler: id = msg_send![handler, initWithURL: url options: options];
+
+        let request: id = msg_send![class!(VNRecognizeTextRequest), alloc];
+        let request: id = msg_send![request, init];
+        // VNRequestTextRecognitionLevelAccurate = 0
+        let _: () = msg_send![request, setRecognitionLevel: 0_i64];
+        let _: () = msg_send![request, setUsesLanguageCorrection: YES];
+
+        let requests: id = msg_send![class!(NSArray), arrayWithObject: request];
+        let mut error: id = nil;
+        let ok: BOOL = msg_send![handler, performRequests: requests error: &mut error];
+
+        let result = if ok == NO {
+            Err("Text recognition failed".to_string())
+        } else {
+            let mut lines = Vec::new();
+            let results: id = msg_send![request, results];
+            if !results.is_null() {
+                let count: usize = msg_send![results, count];
+                for i in 0..count {
+                    let observation: id = msg_send![results, objectAtIndex: i];
+                    let candidates: id = msg_send![observation, topCandidates: 1_usize];
+                    let candidate: id = msg_send![candidates, firstObject];
+                    if candidate.is_null() {
+                        continue;
+                    }
+                    let string: id = msg_send![candidate, string];
+                    if let Some(line) = crate::pasteboard::nsstring_to_string(string) {
+                        lines.push(line);
+                    }
+                }
+            }
+            Ok(lines.join("\n"))
+        };
+
+        let _: () = msg_send![request, release];
+        let _: () = msg_send![handler, release];
+        let _: () = msg_send![ns_path, release];
+        let _: () = msg_send![pool, drain];
+
+        result
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn recognize_text(_path: &Path) -> Result<String, String> {
+    Err("Text recognition not implemented for this platform".to_string())
+}
+
+/// Capture a screen region, OCR it and add the text (and optionally the
+/// screenshot) to history. Returns the text item, or `None` if cancelled.
+pub async fn capture_and_recognize<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Option<ClipboardItem>, String> {
+    let path = tokio::task::spawn_blocking(capture::capture_region)
+        .await
+        .map_err(|e| e.to_string())??;
+    let Some(path) = path else {
+        return Ok(None);
+    };
+
+    let recognize_path = path.clone();
+    let text = tokio::task::spawn_blocking(move || recognize_text(&recognize_path))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let keep_image = app
+        .try_state::<SettingsManager>()
+        .map_or(false, |s| s.get().ocr_keep_image);
+    let image = if keep_image {
+        image::open(&path).map(|img| img.to_rgba8()).ok()
+    } else {
+        None
+    };
+    let _ = std::fs::remove_file(&path);
+
+    let text = text?;
+    let monitor = app.state::<ClipboardMonitor>();
+
+    let image_item = match image {
+        Some(image) => {
+            let (width, height) = image.dimensions();
+            let rgba = image.into_raw();
+            let hash = clipboard::compute_hash_bytes(&rgba);
+            let item = clipboard::image_item(&rgba, width, height, hash);
+            Some(clipboard::store_capture(&app, &monitor, item)?)
+        }
+        None => None,
+    };
+
+    if text.trim().is_empty() {
+        let _ = app.emit("ocr-no-text", ());
+        return Ok(None);
+    }
+
+    let content_type = clipboard::detect_content_type(&text);
+    let mut metadata = clipboard::text_metadata(&content_type, &text)
+        .unwrap_or_else(|| serde_json::json!({}));
+    metadata["source"] = "ocr".into();
+
+    let mut item = ClipboardItem::new(
+        content_type,
+        text.clone(),
+        clipboard::create_text_preview(&text),
+        clipboard::compute_hash(&text),
+    );
+    item.metadata = Some(metadata);
+    item.provenance = image_item.as_ref().map(|image_item| Provenance {
+        parent_id: image_item.id.clone(),
+        operation: "ocr".to_string(),
+        options: serde_json::Value::Null,
+    });
+
+    let text_item = clipboard::store_capture(&app, &monitor, item)?;
+
+    // Keep the screenshot and its text discoverable from each other
+    if let Some(image_item) = &image_item {
+        let db = app.state::<Database>();
+        if let Err(e) = db.link_items(&image_item.id, &text_item.id, "ocr") {
+            log::warn!("Failed to link OCR text to its screenshot: {}", e);
+        }
+    }
+
+    Ok(Some(text_item))
+}
+
+#[tauri::command]
+pub async fn capture_text_from_screen<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Option<ClipboardItem>, String> {
+    capture_and_recognize(app).await
+}
@@ -0,0 +1,179 @@
+use crate::clipboard::{self, ClipboardMonitor};
+use crate::database::{Database, Provenance};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Number of swatches extracted per image
+const PALETTE_SIZE: usize = 6;
+
+/// Pixels are sampled on a grid of at most this many points per side
+const SAMPLE_GRID: u32 = 64;
+
+const KMEANS_ITERATIONS: usize = 10;
+
+/// Dominant colors of an RGBA image as `#rrggbb` strings, most common first.
+/// Runs k-means over a downsampled grid so cost doesn't grow with image size.
+pub fn extract_palette(rgba: &[u8], width: u32, height: u32) -> Vec<String> {
+    if width == 0 || height == 0 || rgba.len() < width as usize * height as usize * 4 {
+        return Vec::new();
+    }
+
+    let step_x = (width / SAMPLE_GRID).max(1);
+    let step_y = (height / SAMPLE_GRID).max(1);
+
+    let mut samples: Vec<[f32; 3]> = Vec::new();
+    for y in (0..height).step_by(step_y as usize) {
+        for x in (0..width).step_by(step_x as usize) {
+            let i = (y as usize * width as usize + x as usize) * 4;
+            // Ignore (mostly) transparent pixels
+            if rgba[i + 3] < 128 {
+                continue;
+            }
+            samples.push([rgba[i] as f32, rgba[i + 1] as f32, rgba[i + 2] as f32]);
+        }
+    }
+
+    if samples.is_empty() {
+        return Vec::new();
+    }
+
+    let k = PALETTE_SIZE.min(samples.len());
+
+    // Deterministic initialization: evenly spaced samples in luminance order
+    let mut by_luma = samples.clone();
+    by_luma.sort_by(|a, b| luma(a).total_cmp(&luma(b)));
+    let last = by_luma.len() - 1;
+    let mut centers: Vec<[f32; 3]> = (0..k)
+        .map(|i| by_luma[if k > 1 { i * last / (k - 1) } else { 0 }])
+        .collect();
+
+    let mut counts = vec![0usize; k];
+    for _ in 0..KMEANS_ITERATIONS {
+        let mut sums = vec![[0f32; 3]; k];
+        counts = vec![0usize; k];
+
+        for sample in &samples {
+            let nearest = nearest_center(&centers, sample);
+            for c in 0..3 {
+                sums[nearest][c] += sample[c];
+            }
+            counts[nearest] += 1;
+        }
+
+        for (i, center) in centers.iter_mut().enumerate() {
+            if counts[i] > 0 {
+                for c in 0..3 {
+                    center[c] = sums[i][c] / counts[i] as f32;
+                }
+            }
+        }
+    }
+
+    let mut clusters: Vec<(usize, [f32; 3])> = counts.into_iter().zip(centers).collect();
+    clusters.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut palette: Vec<String> = Vec::new();
+    for (count, center) in clusters {
+        if count == 0 {
+            continue;
+        }
+        let hex = format!(
+            "#{:02x}{:02x}{:02x}",
+            center[0].round() as u8,
+            center[1].round() as u8,
+            center[2].round() as u8
+        );
+        if !palette.contains(&hex) {
+            palette.push(hex);
+        }
+    }
+
+    palette
+}
+
+fn luma(color: &[f32; 3]) -> f32 {
+    0.299 * color[0] + 0.587 * color[1] + 0.114 * color[2]
+}
+
+fn nearest_center(centers: &[[f32; 3]], sample: &[f32; 3]) -> usize {
+    let mut best = 0;
+    let mut best_distance = f32::MAX;
+    for (i, center) in centers.iter().enumerate() {
+        let distance: f32 = (0..3).map(|c| (center[c] - sample[c]).powi(2)).sum();
+        if distance < best_distance {
+            best = i;
+            best_distance = distance;
+        }
+    }
+    best
+}
+
+/// Palette for an image item, computing and storing it if the item predates
+/// palette extraction
+#[tauri::command]
+pub async fn get_item_palette(
+    db: tauri::State<'_, Database>,
+    id: String,
+) -> Result<Vec<String>, String> {
+    let item = db
+        .get_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+
+    if item.content_type != "image" {
+        return Err("Item is not an image".to_string());
+    }
+
+    if let Some(palette) = item
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("palette"))
+        .and_then(|p| serde_json::from_value::<Vec<String>>(p.clone()).ok())
+    {
+        return Ok(palette);
+    }
+
+    let (width, height) =
+        clipboard::image_dimensions(&item).ok_or_else(|| "Unknown image size".to_string())?;
+    let rgba = STANDARD.decode(&item.content).map_err(|e| e.to_string())?;
+    let palette = extract_palette(&rgba, width, height);
+
+    let mut metadata = item.metadata.unwrap_or_else(|| serde_json::json!({}));
+    metadata["width"] = width.into();
+    metadata["height"] = height.into();
+    metadata["palette"] = serde_json::json!(palette);
+    db.set_item_metadata(&id, &metadata)
+        .map_err(|e| e.to_string())?;
+
+    Ok(palette)
+}
+
+/// Copy one swatch of an image's palette to the clipboard as hex
+#[tauri::command]
+pub async fn copy_palette_color<R: Runtime>(
+    app: AppHandle<R>,
+    db: tauri::State<'_, Database>,
+    id: String,
+    index: usize,
+) -> Result<String, String> {
+    let palette = get_item_palette(db, id.clone()).await?;
+    let hex = palette
+        .get(index)
+        .cloned()
+        .ok_or_else(|| "No swatch at that index".to_string())?;
+
+    app.state::<ClipboardMonitor>().expect_derived(
+        &hex,
+        Provenance {
+            parent_id: id,
+            operation: "palette_color".to_string(),
+            options: serde_json::json!({ "index": index }),
+        },
+    );
+    app.clipboard()
+        .write_text(&hex)
+        .map_err(|e| e.to_string())?;
+
+    Ok(hex)
+}
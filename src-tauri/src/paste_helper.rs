@@ -0,0 +1,330 @@
+//! Target-app aware paste behavior shared by the simulated paste paths
+
+use crate::clipboard::{self, ClipboardMonitor};
+use crate::database::Database;
+use crate::keyboard;
+use crate::settings::{PasteOverride, Settings, SmartPasteRule};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Pinned items reachable with the pinned paste shortcuts (modifiers+1..9)
+pub const PINNED_HOTKEYS: usize = 9;
+
+/// Bracketed-paste markers (xterm): terminals treat text between them as
+/// pasted, so embedded newlines don't execute commands
+const BRACKETED_PASTE_START: &str = "\x1b[200~";
+const BRACKETED_PASTE_END: &str = "\x1b[201~";
+
+/// Terminal emulators that get terminal paste mode when it's enabled
+const TERMINAL_APPS: &[&str] = &[
+    "com.apple.Terminal",
+    "com.googlecode.iterm2",
+    "io.alacritty",
+    "net.kovidgoyal.kitty",
+    "com.github.wez.wezterm",
+    "dev.warp.Warp-Stable",
+    "com.mitchellh.ghostty",
+    "co.zeit.hyper",
+];
+
+/// Chat apps, where pasting a secret is usually a mistake
+const CHAT_APPS: &[&str] = &[
+    "com.tinyspeck.slackmacgap",
+    "com.hnc.Discord",
+    "com.microsoft.teams",
+    "com.microsoft.teams2",
+    "ru.keepcoder.Telegram",
+    "net.whatsapp.WhatsApp",
+    "com.apple.MobileSMS",
+];
+
+/// Editors that take formatted (HTML/RTF) pastes
+const RICH_EDITOR_APPS: &[&str] = &[
+    "com.apple.TextEdit",
+    "com.apple.Notes",
+    "com.apple.mail",
+    "com.apple.iWork.Pages",
+    "com.apple.iWork.Keynote",
+    "com.microsoft.Word",
+    "com.microsoft.Powerpoint",
+    "com.microsoft.Outlook",
+    "com.microsoft.onenote.mac",
+    "notion.id",
+    "com.evernote.Evernote",
+];
+
+/// Kind of app a paste lands in, which smart paste rules are keyed by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppClass {
+    Terminal,
+    Chat,
+    RichEditor,
+    Other,
+}
+
+/// Per-call options for `do_paste_and_simulate`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PasteOptions {
+    /// Write only the plain-text flavor (defaults to the `paste_plain_text`
+    /// setting)
+    pub plain_text: Option<bool>,
+    /// Type the content as keystrokes instead of going through the clipboard
+    pub type_out: bool,
+    /// Leave the panel open after pasting
+    pub keep_open: bool,
+    /// Press Return after pasting (defaults to the `paste_and_submit`
+    /// setting)
+    pub submit: Option<bool>,
+    /// For multi-file items, paste only the file at this index
+    pub file_index: Option<usize>,
+    /// Placeholder values for template items
+    pub template_values: Option<HashMap<String, String>>,
+    /// Ids of `transforms` to run over text content, in order
+    pub transforms: Vec<String>,
+    /// Bundle id of the app to paste into, instead of the one that was
+    /// frontmost before the panel opened
+    pub target_app: Option<String>,
+}
+
+pub fn is_terminal(app_id: &str) -> bool {
+    TERMINAL_APPS.iter().any(|t| t.eq_ignore_ascii_case(app_id))
+}
+
+pub fn is_chat_app(app_id: &str) -> bool {
+    CHAT_APPS.iter().any(|t| t.eq_ignore_ascii_case(app_id))
+}
+
+pub fn is_rich_editor(app_id: &str) -> bool {
+    RICH_EDITOR_APPS.iter().any(|t| t.eq_ignore_ascii_case(app_id))
+}
+
+pub fn classify(app_id: &str) -> AppClass {
+    if is_terminal(app_id) {
+        AppClass::Terminal
+    } else if is_chat_app(app_id) {
+        AppClass::Chat
+    } else if is_rich_editor(app_id) {
+        AppClass::RichEditor
+    } else {
+        AppClass::Other
+    }
+}
+
+/// The smart paste rule for `target`'s class, when smart paste is on
+fn smart_rule<'a>(settings: &'a Settings, target: Option<&str>) -> Option<&'a SmartPasteRule> {
+    if !settings.smart_paste {
+        return None;
+    }
+    settings.smart_paste_rules.get(&classify(target?))
+}
+
+/// Whether a paste into `target` writes only plain text: the paste's own
+/// choice wins, then the smart paste rule, then `paste_plain_text`
+pub fn plain_text_for(settings: &Settings, options: &PasteOptions, target: Option<&str>) -> bool {
+    options
+        .plain_text
+        .or_else(|| smart_rule(settings, target).map(|rule| rule.plain_text))
+        .unwrap_or(settings.paste_plain_text)
+}
+
+/// The app a paste will land in: whatever was frontmost before the panel opened
+pub fn target_app<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    #[cfg(target_os = "macos")]
+    {
+        app.try_state::<crate::window::PreviousAppState>()
+            .and_then(|state| state.bundle_id())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = app;
+        crate::exclusions::get_frontmost_app()
+    }
+}
+
+/// The app a paste with `options` will land in
+pub fn target_for<R: Runtime>(app: &AppHandle<R>, options: &PasteOptions) -> Option<String> {
+    options.target_app.clone().or_else(|| target_app(app))
+}
+
+/// Bring an explicitly chosen paste target to the front
+pub fn activate_app(bundle_id: &str) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        if crate::window::activate_bundle_id(bundle_id) {
+            Ok(())
+        } else {
+            Err(format!("{} is not running", bundle_id))
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = bundle_id;
+        Err("Pasting into a chosen app is only supported on macOS".to_string())
+    }
+}
+
+/// Make the current frontmost app the paste target. Pastes started by a
+/// global hotkey while the panel is hidden would otherwise go to whichever
+/// app was frontmost when the panel last opened.
+pub fn capture_target<R: Runtime>(app: &AppHandle<R>) {
+    #[cfg(target_os = "macos")]
+    if let Some(state) = app.try_state::<crate::window::PreviousAppState>() {
+        state.capture();
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = app;
+}
+
+/// Wait for `target` to be frontmost after focus was handed back, so the
+/// paste keystroke doesn't land in another window. Each check that finds
+/// another app frontmost activates `target` again, up to
+/// `paste_focus_retries` times.
+pub async fn wait_for_target(settings: &Settings, target: Option<&str>) -> Result<(), String> {
+    let delay = std::time::Duration::from_millis(settings.paste_delay_ms);
+    tokio::time::sleep(delay).await;
+
+    // Only macOS can tell which app is frontmost cheaply and reliably
+    #[cfg(target_os = "macos")]
+    if let Some(target) = target.filter(|_| settings.paste_focus_retries > 0) {
+        let is_frontmost = || {
+            crate::window::frontmost_bundle_id()
+                .map_or(true, |frontmost| frontmost.eq_ignore_ascii_case(target))
+        };
+        for _ in 0..settings.paste_focus_retries {
+            if is_frontmost() {
+                return Ok(());
+            }
+            if !crate::window::activate_bundle_id(target) {
+                return Err(format!("{} is no longer running", target));
+            }
+            tokio::time::sleep(delay).await;
+        }
+        if !is_frontmost() {
+            return Err(format!(
+                "{} didn't come to the front; the item is on the clipboard",
+                target
+            ));
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = target;
+
+    Ok(())
+}
+
+/// Paste behavior for `target`: an explicit per-app override wins, then
+/// terminal paste mode for known terminals, otherwise a plain paste (through
+/// PRIMARY if `primary_selection_paste` is on). A smart paste rule can add
+/// newline stripping to the latter two.
+pub fn override_for(settings: &Settings, target: Option<&str>) -> PasteOverride {
+    let fallback = PasteOverride {
+        primary_selection: settings.primary_selection_paste,
+        ..Default::default()
+    };
+    let Some(target) = target else {
+        return fallback;
+    };
+
+    if let Some(found) = settings
+        .paste_overrides
+        .iter()
+        .find(|(app_id, _)| app_id.eq_ignore_ascii_case(target))
+        .map(|(_, o)| o.clone())
+    {
+        return found;
+    }
+
+    let mut paste_override = if settings.terminal_paste_mode && is_terminal(target) {
+        PasteOverride {
+            bracketed_paste: true,
+            strip_trailing_newlines: true,
+            ..fallback
+        }
+    } else {
+        fallback
+    };
+    if let Some(rule) = smart_rule(settings, Some(target)) {
+        paste_override.strip_trailing_newlines |= rule.strip_trailing_newlines;
+    }
+    paste_override
+}
+
+/// Apply the text adjustments of `paste_override` before the content is written
+pub fn prepare_text(text: &str, paste_override: &PasteOverride) -> String {
+    if paste_override.strip_trailing_newlines {
+        text.trim_end_matches(['\n', '\r']).to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Wrap `text` in bracketed-paste markers. Escape bytes are dropped first:
+/// an end marker inside the clip would end the paste early and type the rest
+/// as commands.
+fn bracketed_paste(text: &str) -> String {
+    let text: String = text.chars().filter(|c| *c != '\x1b').collect();
+    format!("{}{}{}", BRACKETED_PASTE_START, text, BRACKETED_PASTE_END)
+}
+
+/// Send the paste to the frontmost app: either the paste keystroke (the
+/// override's, if it has one), the text typed out between bracketed-paste
+/// markers, or on Linux a middle click pasting it from PRIMARY. Non-text
+/// items (empty `text`) always use the keystroke.
+pub fn simulate_paste(text: &str, paste_override: &PasteOverride) -> Result<(), String> {
+    if paste_override.bracketed_paste && !text.is_empty() {
+        keyboard::type_text(&bracketed_paste(text))
+    } else if cfg!(target_os = "linux") && paste_override.primary_selection && !text.is_empty() {
+        crate::pasteboard::write_primary_selection(text)?;
+        keyboard::simulate_middle_click()
+    } else {
+        let keystroke = paste_override
+            .keystroke
+            .as_deref()
+            .and_then(keyboard::Keystroke::parse)
+            .unwrap_or_else(keyboard::Keystroke::paste);
+        keyboard::simulate_keystroke(keystroke)
+    }
+}
+
+/// Paste the `index`th pinned item (in pinned list order) straight into the
+/// frontmost app, without showing the panel
+pub async fn paste_pinned<R: Runtime>(app: AppHandle<R>, index: usize) -> Result<(), String> {
+    let item = app
+        .state::<Database>()
+        .get_pinned_items()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .nth(index);
+    let Some(item) = item else {
+        return Ok(());
+    };
+
+    // The item is already in history; writing it back shouldn't capture it
+    if let Some(monitor) = app.try_state::<ClipboardMonitor>() {
+        monitor.mark_seen(item.hash.clone());
+    }
+    capture_target(&app);
+    clipboard::do_paste_and_simulate(app, item.id, PasteOptions::default()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bracketed_paste_strips_embedded_end_markers() {
+        let wrapped = bracketed_paste("ls\x1b[201~\nrm -rf ~\n");
+        assert_eq!(
+            wrapped,
+            format!("{}ls[201~\nrm -rf ~\n{}", BRACKETED_PASTE_START, BRACKETED_PASTE_END)
+        );
+        assert_eq!(wrapped.matches(BRACKETED_PASTE_END).count(), 1);
+    }
+}
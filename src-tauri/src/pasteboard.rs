@@ -0,0 +1,391 @@
+/// Inspection of the native pasteboard beyond what the clipboard plugin exposes
+#[cfg(target_os = "macos")]
+use cocoa::base::id;
+
+/// Marker types from the nspasteboard.org conventions used by password managers
+#[cfg(target_os = "macos")]
+const CONCEALED_TYPE: &str = "org.nspasteboard.ConcealedType";
+#[cfg(target_os = "macos")]
+const TRANSIENT_TYPE: &str = "org.nspasteboard.TransientType";
+#[cfg(target_os = "macos")]
+const AUTO_GENERATED_TYPE: &str = "org.nspasteboard.AutoGeneratedType";
+/// Added by the system to content synced from another device via Universal Clipboard
+#[cfg(target_os = "macos")]
+const REMOTE_CLIPBOARD_TYPE: &str = "com.apple.is-remote-clipboard";
+
+/// Hints the source app attached to the current clipboard contents
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PasteboardMarkers {
+    /// Sensitive data such as a password (1Password, Keychain Access, ...)
+    pub concealed: bool,
+    /// Content the source app doesn't want recorded at all
+    pub transient: bool,
+    /// Content that arrived from another device via Universal Clipboard / Handoff
+    pub remote: bool,
+}
+
+/// Convert an NSString to a Rust String
+#[cfg(target_os = "macos")]
+pub(crate) unsafe fn nsstring_to_string(string: id) -> Option<String> {
+    use objc::{msg_send, sel, sel_impl};
+
+    if string.is_null() {
+        return None;
+    }
+    let ptr: *const std::os::raw::c_char = msg_send![string, UTF8String];
+    if ptr.is_null() {
+        return None;
+    }
+    std::ffi::CStr::from_ptr(ptr).to_str().ok().map(String::from)
+}
+
+/// UTIs of every flavor currently on the general pasteboard
+#[cfg(target_os = "macos")]
+pub fn current_types() -> Vec<String> {
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe {
+        let pool: id = msg_send![class!(NSAutoreleasePool), new];
+
+        let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+        let types: id = msg_send![pasteboard, types];
+
+        let mut result = Vec::new();
+        if !types.is_null() {
+            let count: usize = msg_send![types, count];
+            for i in 0..count {
+                let uti: id = msg_send![types, objectAtIndex: i];
+                if let Some(uti) = nsstring_to_string(uti) {
+                    result.push(uti);
+                }
+            }
+        }
+
+        let _: () = msg_send![pool, drain];
+        result
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn current_types() -> Vec<String> {
+    Vec::new()
+}
+
+#[cfg(target_os = "macos")]
+pub fn markers() -> PasteboardMarkers {
+    let types = current_types();
+    let has = |uti: &str| types.iter().any(|t| t == uti);
+
+    PasteboardMarkers {
+        concealed: has(CONCEALED_TYPE),
+        transient: has(TRANSIENT_TYPE) || has(AUTO_GENERATED_TYPE),
+        remote: has(REMOTE_CLIPBOARD_TYPE),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn markers() -> PasteboardMarkers {
+    PasteboardMarkers::default()
+}
+
+/// Put files on the general pasteboard as file URLs, so Finder and upload
+/// fields receive the files themselves rather than their paths as text
+#[cfg(target_os = "macos")]
+pub fn write_file_urls(paths: &[String]) -> Result<(), String> {
+    use cocoa::base::{nil, BOOL, YES};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe {
+        let pool: id = msg_send![class!(NSAutoreleasePool), new];
+
+        let urls: id = msg_send![class!(NSMutableArray), array];
+        for path in paths {
+            let ns_path = NSString::alloc(nil).init_str(path);
+            let url: id = msg_send![class!(NSURL), fileURLWithPath: ns_path];
+            let _: () = msg_send![ns_path, release];
+            let _: () = msg_send![urls, addObject: url];
+        }
+
+        let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+        let _: i64 = msg_send![pasteboard, clearContents];
+        let ok: BOOL = msg_send![pasteboard, writeObjects: urls];
+
+        let _: () = msg_send![pool, drain];
+
+        if ok == YES {
+            Ok(())
+        } else {
+            Err("Failed to write files to the pasteboard".to_string())
+        }
+    }
+}
+
+/// Put files on the clipboard as a CF_HDROP list, which is what Explorer and
+/// file upload dialogs accept
+#[cfg(target_os = "windows")]
+pub fn write_file_urls(paths: &[String]) -> Result<(), String> {
+    use clipboard_win::{formats, set_clipboard};
+
+    set_clipboard(formats::FileList, paths).map_err(|e| e.to_string())
+}
+
+/// Files on the clipboard as a native file list. Explorer puts copied files
+/// there as CF_HDROP only, with no text flavor to fall back on.
+#[cfg(target_os = "windows")]
+pub fn read_file_list() -> Option<Vec<String>> {
+    use clipboard_win::{formats, get_clipboard};
+
+    let paths: Vec<String> = get_clipboard(formats::FileList).ok()?;
+    (!paths.is_empty()).then_some(paths)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn read_file_list() -> Option<Vec<String>> {
+    None
+}
+
+/// Formatted flavors that accompany copied text
+#[derive(Debug, Clone, Default)]
+pub struct RichText {
+    pub html: Option<String>,
+    pub rtf: Option<String>,
+}
+
+impl RichText {
+    pub fn is_empty(&self) -> bool {
+        self.html.is_none() && self.rtf.is_none()
+    }
+}
+
+#[cfg(target_os = "macos")]
+const HTML_TYPE: &str = "public.html";
+#[cfg(target_os = "macos")]
+const RTF_TYPE: &str = "public.rtf";
+
+/// The general pasteboard's value for `uti`, as a string
+#[cfg(target_os = "macos")]
+fn read_string(uti: &str) -> Option<String> {
+    use cocoa::base::nil;
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe {
+        let pool: id = msg_send![class!(NSAutoreleasePool), new];
+
+        let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+        let ns_type = NSString::alloc(nil).init_str(uti);
+        let value: id = msg_send![pasteboard, stringForType: ns_type];
+        let result = nsstring_to_string(value);
+        let _: () = msg_send![ns_type, release];
+
+        let _: () = msg_send![pool, drain];
+        result
+    }
+}
+
+/// Add a `uti` flavor to what's already on the general pasteboard
+#[cfg(target_os = "macos")]
+fn add_string(uti: &str, value: &str) -> Result<(), String> {
+    use cocoa::base::{nil, BOOL, YES};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe {
+        let pool: id = msg_send![class!(NSAutoreleasePool), new];
+
+        let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+        let ns_type = NSString::alloc(nil).init_str(uti);
+        let ns_value = NSString::alloc(nil).init_str(value);
+        let types: id = msg_send![class!(NSArray), arrayWithObject: ns_type];
+        let _: i64 = msg_send![pasteboard, addTypes: types owner: nil];
+        let ok: BOOL = msg_send![pasteboard, setString: ns_value forType: ns_type];
+        let _: () = msg_send![ns_value, release];
+        let _: () = msg_send![ns_type, release];
+
+        let _: () = msg_send![pool, drain];
+
+        if ok == YES {
+            Ok(())
+        } else {
+            Err(format!("Failed to write {} to the pasteboard", uti))
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn read_rich_text() -> RichText {
+    RichText {
+        html: read_string(HTML_TYPE),
+        rtf: read_string(RTF_TYPE),
+    }
+}
+
+/// Add the formatted flavors next to the text that was just written, so
+/// rich text editors paste formatting while others still get plain text
+#[cfg(target_os = "macos")]
+pub fn add_rich_text(rich: &RichText) -> Result<(), String> {
+    if let Some(html) = &rich.html {
+        add_string(HTML_TYPE, html)?;
+    }
+    if let Some(rtf) = &rich.rtf {
+        add_string(RTF_TYPE, rtf)?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn html_format() -> Option<u32> {
+    clipboard_win::register_format("HTML Format").map(|f| f.get())
+}
+
+#[cfg(target_os = "windows")]
+fn rtf_format() -> Option<u32> {
+    clipboard_win::register_format("Rich Text Format").map(|f| f.get())
+}
+
+/// The HTML fragment of the CF_HTML flavor, without its offset header
+#[cfg(target_os = "windows")]
+fn read_html() -> Option<String> {
+    use clipboard_win::{formats, get_clipboard};
+
+    let raw: Vec<u8> = get_clipboard(formats::RawData(html_format()?)).ok()?;
+    let raw = String::from_utf8_lossy(&raw);
+    let offset = |key: &str| -> Option<usize> {
+        let start = raw.find(key)? + key.len();
+        let value = raw[start..].lines().next()?;
+        value.trim().parse().ok()
+    };
+
+    // Offsets are in bytes into the whole payload, header included
+    let start = offset("StartFragment:")?;
+    let end = offset("EndFragment:")?;
+    raw.get(start..end).map(String::from)
+}
+
+#[cfg(target_os = "windows")]
+fn read_rtf() -> Option<String> {
+    use clipboard_win::{formats, get_clipboard};
+
+    let raw: Vec<u8> = get_clipboard(formats::RawData(rtf_format()?)).ok()?;
+    let rtf = String::from_utf8_lossy(&raw);
+    Some(rtf.trim_end_matches('\0').to_string()).filter(|rtf| !rtf.is_empty())
+}
+
+#[cfg(target_os = "windows")]
+pub fn read_rich_text() -> RichText {
+    RichText {
+        html: read_html(),
+        rtf: read_rtf(),
+    }
+}
+
+/// Build the CF_HTML payload for `fragment`
+#[cfg(target_os = "windows")]
+fn cf_html(fragment: &str) -> String {
+    const HEADER_LEN: usize = 105;
+    let prefix = "<html><body><!--StartFragment-->";
+    let suffix = "<!--EndFragment--></body></html>";
+
+    let start_fragment = HEADER_LEN + prefix.len();
+    let end_fragment = start_fragment + fragment.len();
+    let end_html = end_fragment + suffix.len();
+    format!(
+        "Version:0.9\r\nStartHTML:{:010}\r\nEndHTML:{:010}\r\nStartFragment:{:010}\r\nEndFragment:{:010}\r\n{}{}{}",
+        HEADER_LEN, end_html, start_fragment, end_fragment, prefix, fragment, suffix
+    )
+}
+
+/// Add CF_HTML and RTF flavors next to whatever was just written, so rich
+/// text editors paste formatting while others still get plain text
+#[cfg(target_os = "windows")]
+pub fn add_rich_text(rich: &RichText) -> Result<(), String> {
+    let _clipboard = clipboard_win::Clipboard::new_attempts(10).map_err(|e| e.to_string())?;
+
+    if let Some(html) = &rich.html {
+        let format = html_format().ok_or_else(|| "CF_HTML is not available".to_string())?;
+        clipboard_win::raw::set_without_clear(format, cf_html(html).as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    if let Some(rtf) = &rich.rtf {
+        let format = rtf_format().ok_or_else(|| "RTF is not available".to_string())?;
+        clipboard_win::raw::set_without_clear(format, rtf.as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn read_rich_text() -> RichText {
+    RichText::default()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn add_rich_text(_rich: &RichText) -> Result<(), String> {
+    Ok(())
+}
+
+/// Current X11 PRIMARY selection (the middle-click buffer), via whichever of
+/// xclip, xsel or wl-paste is installed
+#[cfg(target_os = "linux")]
+pub fn read_primary_selection() -> Option<String> {
+    use std::process::Command;
+
+    let tools: [(&str, &[&str]); 3] = [
+        ("xclip", &["-o", "-selection", "primary"]),
+        ("xsel", &["--primary", "--output"]),
+        ("wl-paste", &["--primary", "--no-newline"]),
+    ];
+
+    for (tool, args) in tools {
+        let Ok(output) = Command::new(tool).args(args).output() else {
+            continue;
+        };
+        if output.status.success() {
+            return String::from_utf8(output.stdout).ok();
+        }
+    }
+
+    None
+}
+
+/// Replace the PRIMARY selection with `text`, via whichever of xclip, xsel
+/// or wl-copy is installed. The tool keeps running in the background to
+/// serve the selection until something else takes it.
+#[cfg(target_os = "linux")]
+pub fn write_primary_selection(text: &str) -> Result<(), String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let tools: [(&str, &[&str]); 3] = [
+        ("xclip", &["-selection", "primary"]),
+        ("xsel", &["--primary", "--input"]),
+        ("wl-copy", &["--primary"]),
+    ];
+
+    for (tool, args) in tools {
+        let Ok(mut child) = Command::new(tool)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes()).map_err(|e| e.to_string())?;
+        }
+        let status = child.wait().map_err(|e| e.to_string())?;
+        if status.success() {
+            return Ok(());
+        }
+    }
+
+    Err("Setting the PRIMARY selection needs xclip, xsel or wl-copy installed".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn write_primary_selection(_text: &str) -> Result<(), String> {
+    Err("The PRIMARY selection only exists on Linux".to_string())
+}
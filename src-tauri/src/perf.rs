@@ -0,0 +1,118 @@
+//! Local-only performance counters.
+//!
+//! Keeps the most recent timings for the hot paths (capture, database,
+//! paste) in memory so slowness can be pinned on the DB, IPC or paste
+//! simulation. Nothing is persisted or sent anywhere.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Samples kept per metric
+const WINDOW: usize = 200;
+
+/// Full `check_clipboard` call that produced a new item
+pub const CAPTURE: &str = "capture";
+/// Writing a batch of captures to SQLite
+pub const DB_WRITE: &str = "db_write";
+/// History list queries
+pub const QUERY: &str = "query";
+/// Writing a pasted item to the clipboard
+pub const PASTE_WRITE: &str = "paste_write";
+/// Simulating the paste keystroke (or typing the text out)
+pub const PASTE_SIMULATE: &str = "paste_simulate";
+/// Invoke round trips, as reported by the frontend
+pub const IPC: &str = "ipc";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricSummary {
+    pub count: usize,
+    pub last_ms: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Default)]
+pub struct PerfMonitor {
+    samples: Mutex<BTreeMap<&'static str, VecDeque<f64>>>,
+}
+
+impl PerfMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, metric: &'static str, elapsed: Duration) {
+        let mut samples = self.samples.lock().unwrap();
+        let window = samples.entry(metric).or_default();
+        if window.len() == WINDOW {
+            window.pop_front();
+        }
+        window.push_back(elapsed.as_secs_f64() * 1000.0);
+    }
+
+    pub fn summary(&self) -> BTreeMap<&'static str, MetricSummary> {
+        let samples = self.samples.lock().unwrap();
+        samples
+            .iter()
+            .filter(|(_, window)| !window.is_empty())
+            .map(|(metric, window)| (*metric, summarize(window)))
+            .collect()
+    }
+
+    pub fn reset(&self) {
+        self.samples.lock().unwrap().clear();
+    }
+}
+
+fn summarize(window: &VecDeque<f64>) -> MetricSummary {
+    let mut sorted: Vec<f64> = window.iter().copied().collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let percentile = |p: f64| sorted[((sorted.len() - 1) as f64 * p).round() as usize];
+
+    MetricSummary {
+        count: sorted.len(),
+        last_ms: *window.back().unwrap(),
+        mean_ms: sorted.iter().sum::<f64>() / sorted.len() as f64,
+        p50_ms: percentile(0.5),
+        p95_ms: percentile(0.95),
+        max_ms: *sorted.last().unwrap(),
+    }
+}
+
+/// Record the time since `start` under `metric`, if the monitor is running
+pub fn record_since<R: Runtime>(app: &AppHandle<R>, metric: &'static str, start: Instant) {
+    if let Some(perf) = app.try_state::<PerfMonitor>() {
+        perf.record(metric, start.elapsed());
+    }
+}
+
+#[tauri::command]
+pub async fn get_perf_metrics(
+    perf: tauri::State<'_, PerfMonitor>,
+) -> Result<BTreeMap<&'static str, MetricSummary>, String> {
+    Ok(perf.summary())
+}
+
+#[tauri::command]
+pub async fn reset_perf_metrics(perf: tauri::State<'_, PerfMonitor>) -> Result<(), String> {
+    perf.reset();
+    Ok(())
+}
+
+/// Let the frontend report how long an invoke took end to end, which is the
+/// only way to see IPC overhead
+#[tauri::command]
+pub async fn report_ipc_latency(
+    perf: tauri::State<'_, PerfMonitor>,
+    millis: f64,
+) -> Result<(), String> {
+    if millis.is_finite() && millis >= 0.0 {
+        perf.record(IPC, Duration::from_secs_f64(millis / 1000.0));
+    }
+    Ok(())
+}
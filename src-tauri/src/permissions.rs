@@ -0,0 +1,101 @@
+//! Accessibility permission, which macOS requires before we can post the
+//! synthetic key events a simulated paste is made of.
+//!
+//! Without it those events are dropped silently, so pastes check first and
+//! emit `accessibility-permission-required` instead. Once a request has been
+//! made, `accessibility-permission-granted` follows as soon as the user
+//! turns it on in System Settings.
+
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// How often, and for how long, to check for the grant after a request
+#[cfg(target_os = "macos")]
+const GRANT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+#[cfg(target_os = "macos")]
+const GRANT_POLL_ATTEMPTS: u32 = 300;
+
+#[cfg(target_os = "macos")]
+const ACCESSIBILITY_SETTINGS_URL: &str =
+    "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility";
+
+#[cfg(target_os = "macos")]
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXIsProcessTrustedWithOptions(options: cocoa::base::id) -> bool;
+    static kAXTrustedCheckOptionPrompt: cocoa::base::id;
+}
+
+/// Whether we're trusted for accessibility, optionally showing the system
+/// prompt that offers to open System Settings
+#[cfg(target_os = "macos")]
+fn is_trusted(prompt: bool) -> bool {
+    use cocoa::base::{id, nil};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe {
+        let options: id = if prompt {
+            let yes: id = msg_send![class!(NSNumber), numberWithBool: true];
+            msg_send![class!(NSDictionary), dictionaryWithObject: yes forKey: kAXTrustedCheckOptionPrompt]
+        } else {
+            nil
+        };
+        AXIsProcessTrustedWithOptions(options)
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn is_trusted(_prompt: bool) -> bool {
+    true
+}
+
+/// Fail a paste up front, and tell the UI why, if its keystroke would be
+/// dropped for lack of permission
+pub fn ensure_accessibility<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    if is_trusted(false) {
+        return Ok(());
+    }
+    let _ = app.emit("accessibility-permission-required", ());
+    Err("Accessibility permission is required to paste into other apps".to_string())
+}
+
+/// Emit `accessibility-permission-granted` once the user grants permission
+#[cfg(target_os = "macos")]
+fn watch_for_grant<R: Runtime>(app: AppHandle<R>) {
+    std::thread::spawn(move || {
+        for _ in 0..GRANT_POLL_ATTEMPTS {
+            std::thread::sleep(GRANT_POLL_INTERVAL);
+            if is_trusted(false) {
+                let _ = app.emit("accessibility-permission-granted", ());
+                return;
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn check_accessibility_permission() -> Result<bool, String> {
+    Ok(is_trusted(false))
+}
+
+/// Show the system prompt and the Accessibility pane of System Settings,
+/// returning whether permission is already granted
+#[tauri::command]
+pub async fn request_accessibility_permission<R: Runtime>(app: AppHandle<R>) -> Result<bool, String> {
+    if is_trusted(true) {
+        return Ok(true);
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("open")
+            .arg(ACCESSIBILITY_SETTINGS_URL)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+        watch_for_grant(app);
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = app;
+
+    Ok(false)
+}
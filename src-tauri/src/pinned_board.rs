@@ -0,0 +1,79 @@
+//! Pinned board: an always-visible mini window of pinned items.
+//!
+//! While `pinned_board_enabled` is on, a small grid of the pinned items
+//! (`window::PINNED_BOARD_WINDOW_LABEL`) stays up next to whatever you're
+//! working in, independent of the main panel. Clicking a tile pastes it into
+//! the frontmost app. The window follows `pinned-changed`, which carries the
+//! pinned items.
+
+use crate::clipboard::{self, ClipboardMonitor};
+use crate::database::{ClipboardItem, Database};
+use crate::paste_helper::{self, PasteOptions};
+use crate::settings::{Settings, SettingsManager};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Tell the board (and anything else listening) the pinned items changed
+pub fn notify<R: Runtime>(app: &AppHandle<R>) {
+    let Some(db) = app.try_state::<Database>() else {
+        return;
+    };
+    if let Ok(items) = db.get_pinned_items() {
+        let _ = app.emit("pinned-changed", items);
+    }
+}
+
+/// Open the board at startup if it was left on. Safe mode keeps it closed.
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    let enabled = app
+        .try_state::<SettingsManager>()
+        .is_some_and(|s| s.get().pinned_board_enabled);
+    if !enabled || crate::safe_mode::is_active(app) {
+        return;
+    }
+    if let Err(e) = crate::window::show_pinned_board_window(app) {
+        log::warn!("Failed to open pinned board: {}", e);
+    }
+}
+
+/// Turn the pinned board on or off, opening or closing its window
+#[tauri::command]
+pub async fn set_pinned_board<R: Runtime>(
+    app: AppHandle<R>,
+    manager: tauri::State<'_, SettingsManager>,
+    enabled: bool,
+) -> Result<Settings, String> {
+    let settings = manager.update_field(|s| s.pinned_board_enabled = enabled)?;
+    if enabled {
+        crate::window::show_pinned_board_window(&app)?;
+    } else {
+        crate::window::close_pinned_board_window(&app);
+    }
+    Ok(settings)
+}
+
+/// Paste a pinned item from the board into the frontmost app, leaving the
+/// board and the main panel as they are
+#[tauri::command]
+pub async fn paste_from_board<R: Runtime>(
+    app: AppHandle<R>,
+    db: tauri::State<'_, Database>,
+    id: String,
+) -> Result<Option<ClipboardItem>, String> {
+    let Some(item) = db.get_item(&id).map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+
+    // Writing the item back to the clipboard must not capture it again
+    if let Some(monitor) = app.try_state::<ClipboardMonitor>() {
+        monitor.mark_seen(item.hash.clone());
+    }
+    // The board never takes focus, so whatever is frontmost is the target
+    paste_helper::capture_target(&app);
+    let options = PasteOptions {
+        keep_open: true,
+        ..Default::default()
+    };
+    clipboard::do_paste_and_simulate(app, item.id.clone(), options).await?;
+
+    Ok(Some(item))
+}
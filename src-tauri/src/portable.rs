@@ -0,0 +1,72 @@
+//! Portable mode: keep all data next to the executable.
+//!
+//! Enabled by a `yoink.portable` file beside the executable (or beside
+//! `Yoink.app` on macOS) or by launching with `--portable`. Settings, the
+//! database and cached assets then live in `yoink-data/` there, or in the
+//! relative directory named on the first line of the marker file, instead of
+//! the OS app-data path.
+
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+
+const MARKER_FILE: &str = "yoink.portable";
+const FLAG: &str = "--portable";
+const DEFAULT_DIR: &str = "yoink-data";
+
+/// Directory the app is launched from, as the user sees it: the folder
+/// holding `Yoink.app` rather than its `Contents/MacOS`
+fn install_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let dir = exe.parent()?;
+    if dir.ends_with("Contents/MacOS") {
+        return dir.ancestors().nth(3).map(Path::to_path_buf);
+    }
+    Some(dir.to_path_buf())
+}
+
+/// The portable data directory, if portable mode is on
+pub fn portable_data_dir() -> Option<PathBuf> {
+    let install_dir = install_dir()?;
+    let marker = install_dir.join(MARKER_FILE);
+
+    let relative = if marker.is_file() {
+        std::fs::read_to_string(&marker)
+            .ok()
+            .and_then(|s| s.lines().next().map(|l| l.trim().to_string()))
+            .filter(|l| !l.is_empty())
+            .unwrap_or_else(|| DEFAULT_DIR.to_string())
+    } else if std::env::args().any(|arg| arg == FLAG) {
+        DEFAULT_DIR.to_string()
+    } else {
+        return None;
+    };
+
+    // Only relative paths, so the data can't silently end up off the volume
+    let relative = Path::new(&relative);
+    if relative.is_absolute() {
+        log::warn!("Ignoring absolute path in {}", MARKER_FILE);
+        return Some(install_dir.join(DEFAULT_DIR));
+    }
+    Some(install_dir.join(relative))
+}
+
+/// Where settings, the database and assets are stored
+pub fn data_dir<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<PathBuf> {
+    match portable_data_dir() {
+        Some(dir) => Ok(dir),
+        None => app.path().app_data_dir(),
+    }
+}
+
+/// The data directory in use and whether it is the portable one, for the
+/// about section of settings
+#[tauri::command]
+pub async fn get_data_location<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<serde_json::Value, String> {
+    let dir = data_dir(&app).map_err(|e| e.to_string())?;
+    Ok(serde_json::json!({
+        "path": dir,
+        "portable": portable_data_dir().is_some(),
+    }))
+}
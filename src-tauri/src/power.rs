@@ -0,0 +1,72 @@
+//! Sleep/wake recovery.
+//!
+//! Global shortcut registrations occasionally don't survive a long sleep, so
+//! after waking every shortcut is registered again, transient panel state is
+//! reset and the clipboard baseline is reloaded. Sleep is detected from the
+//! wall clock jumping past a polling thread, which works the same on every
+//! platform.
+
+use crate::clipboard::ClipboardMonitor;
+use crate::database::Database;
+use crate::hotkey::HotkeyManager;
+use crate::settings::SettingsManager;
+use crate::window::HotkeyModeState;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// Gaps longer than this beyond the interval mean the machine was asleep
+const SLEEP_THRESHOLD: Duration = Duration::from_secs(30);
+
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut last = SystemTime::now();
+        loop {
+            std::thread::sleep(CHECK_INTERVAL);
+
+            let now = SystemTime::now();
+            let gap = now.duration_since(last).unwrap_or_default();
+            last = now;
+
+            if gap > CHECK_INTERVAL + SLEEP_THRESHOLD {
+                log::info!("Woke after ~{}s asleep", gap.as_secs());
+                on_wake(&app);
+            }
+        }
+    });
+}
+
+fn on_wake<R: Runtime>(app: &AppHandle<R>) {
+    // Modifier state from before sleep is meaningless now
+    if let Some(hotkey_state) = app.try_state::<HotkeyModeState>() {
+        hotkey_state.exit();
+    }
+    #[cfg(target_os = "macos")]
+    if let Some(guard) = app.try_state::<crate::window::PanelHideGuard>() {
+        guard.clear_hiding();
+    }
+
+    if let (Some(hotkey_manager), Some(settings_manager), false) = (
+        app.try_state::<HotkeyManager>(),
+        app.try_state::<SettingsManager>(),
+        crate::safe_mode::is_active(app),
+    ) {
+        let boards = app
+            .try_state::<Database>()
+            .and_then(|db| db.get_boards().ok())
+            .unwrap_or_default();
+        hotkey_manager.reregister_all(app, &settings_manager.get(), &boards);
+    }
+
+    // Re-sync with the newest stored item so a copy made just before sleep
+    // is neither lost nor captured twice
+    if let (Some(monitor), Some(db)) = (
+        app.try_state::<ClipboardMonitor>(),
+        app.try_state::<Database>(),
+    ) {
+        monitor.init_last_hash(&db);
+    }
+
+    let _ = app.emit("system-woke", ());
+}
@@ -0,0 +1,220 @@
+//! Detachable preview of the highlighted item.
+//!
+//! `show_preview` opens a small window (`window::PREVIEW_WINDOW_LABEL`) beside
+//! the main panel with the item's full content: the whole image, all of a
+//! long text, or a listing of its files. The window follows
+//! `preview-changed`, which carries the `Preview` to show.
+//!
+//! Previews are rendered for display rather than sent raw (`render_quicklook`):
+//! images are written once to a temp PNG the webview loads over the asset
+//! protocol, JSON is pretty-printed, and huge text comes in chunks.
+
+use crate::clipboard::{self, FileEntry};
+use crate::database::{ClipboardItem, Database};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Characters of text sent per chunk
+const TEXT_CHUNK_CHARS: usize = 100_000;
+
+/// Id of the item the preview window shows, while it's open
+#[derive(Default)]
+pub struct PreviewState {
+    id: Mutex<Option<String>>,
+}
+
+impl PreviewState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn current(&self) -> Option<String> {
+        self.id.lock().unwrap().clone()
+    }
+
+    fn set(&self, id: Option<String>) {
+        *self.id.lock().unwrap() = id;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextFormat {
+    Plain,
+    /// Pretty-printed from the item's JSON
+    Json,
+}
+
+/// Full content of an item, in the form the preview window renders it
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Preview {
+    /// One chunk of the text, starting `offset` characters in
+    Text {
+        id: String,
+        content_type: String,
+        format: TextFormat,
+        text: String,
+        offset: usize,
+        /// Where the next chunk starts, if there is one
+        next_offset: Option<usize>,
+        total_chars: usize,
+    },
+    Image {
+        id: String,
+        /// Temp PNG, for the webview's asset protocol
+        path: String,
+        width: u32,
+        height: u32,
+    },
+    Files {
+        id: String,
+        entries: Vec<FileEntry>,
+    },
+}
+
+fn quicklook_dir() -> PathBuf {
+    std::env::temp_dir().join("yoink-quicklook")
+}
+
+/// The item's image as a temp PNG, written the first time it's previewed
+fn image_file(item: &ClipboardItem) -> Result<PathBuf, String> {
+    let dir = quicklook_dir();
+    let path = dir.join(format!("{}.png", item.id));
+    if !path.exists() {
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        std::fs::write(&path, clipboard::image_png(item)?).map_err(|e| e.to_string())?;
+    }
+    Ok(path)
+}
+
+/// The `offset`th-character chunk of `text`
+fn text_chunk(item: &ClipboardItem, format: TextFormat, text: &str, offset: usize) -> Preview {
+    let total_chars = text.chars().count();
+    let offset = offset.min(total_chars);
+    let chunk: String = text.chars().skip(offset).take(TEXT_CHUNK_CHARS).collect();
+    let end = offset + chunk.chars().count();
+    Preview::Text {
+        id: item.id.clone(),
+        content_type: item.content_type.clone(),
+        format,
+        text: chunk,
+        offset,
+        next_offset: (end < total_chars).then_some(end),
+        total_chars,
+    }
+}
+
+fn preview_of(item: &ClipboardItem, offset: usize) -> Result<Preview, String> {
+    match item.content_type.as_str() {
+        "image" => {
+            let (width, height) = clipboard::image_dimensions(item)
+                .ok_or_else(|| "Unknown image size".to_string())?;
+            Ok(Preview::Image {
+                id: item.id.clone(),
+                path: image_file(item)?.to_string_lossy().into_owned(),
+                width,
+                height,
+            })
+        }
+        "file" | "files" => Ok(Preview::Files {
+            id: item.id.clone(),
+            entries: clipboard::file_entries(&item.content),
+        }),
+        _ => {
+            let pretty = crate::transforms::parse_json(&item.content)
+                .and_then(|json| serde_json::to_string_pretty(&json).ok());
+            Ok(match pretty {
+                Some(pretty) => text_chunk(item, TextFormat::Json, &pretty, offset),
+                None => text_chunk(item, TextFormat::Plain, &item.content, offset),
+            })
+        }
+    }
+}
+
+fn load<R: Runtime>(app: &AppHandle<R>, id: &str, offset: usize) -> Result<Preview, String> {
+    let item = app
+        .state::<Database>()
+        .get_item(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+    preview_of(&item, offset)
+}
+
+/// Show `id` in the preview window and tell it to re-render
+fn present<R: Runtime>(app: &AppHandle<R>, state: &PreviewState, id: String) -> Result<(), String> {
+    let preview = load(app, &id, 0)?;
+    state.set(Some(id));
+    crate::window::show_preview_window(app)?;
+    let _ = app.emit("preview-changed", preview);
+    Ok(())
+}
+
+/// Forget the previewed item once its window is gone
+pub fn clear<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(state) = app.try_state::<PreviewState>() {
+        state.set(None);
+    }
+}
+
+/// Open the preview window on item `id`, or close it if it's already
+/// showing `id`
+#[tauri::command]
+pub async fn show_preview<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, PreviewState>,
+    id: String,
+) -> Result<(), String> {
+    if state.current().as_deref() == Some(id.as_str()) {
+        state.set(None);
+        crate::window::close_preview_window(&app);
+        return Ok(());
+    }
+    present(&app, &state, id)
+}
+
+/// Switch an open preview to item `id`, as the highlight moves. Does
+/// nothing while the preview is closed.
+#[tauri::command]
+pub async fn set_preview_item<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, PreviewState>,
+    id: String,
+) -> Result<(), String> {
+    match state.current() {
+        Some(current) if current != id => present(&app, &state, id),
+        _ => Ok(()),
+    }
+}
+
+/// What the preview window should show when it loads
+#[tauri::command]
+pub async fn get_preview<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, PreviewState>,
+) -> Result<Option<Preview>, String> {
+    state.current().map(|id| load(&app, &id, 0)).transpose()
+}
+
+/// Item `id` rendered for previewing, from `offset` characters in for text
+/// (see `Preview::Text::next_offset`)
+#[tauri::command]
+pub async fn render_quicklook<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+    offset: Option<usize>,
+) -> Result<Preview, String> {
+    load(&app, &id, offset.unwrap_or(0))
+}
+
+#[tauri::command]
+pub async fn close_preview<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, PreviewState>,
+) -> Result<(), String> {
+    state.set(None);
+    crate::window::close_preview_window(&app);
+    Ok(())
+}
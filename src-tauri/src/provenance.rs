@@ -0,0 +1,100 @@
+//! Tracing derived items back to their source.
+//!
+//! Items made from another item (OCR text from a screenshot, a color copied
+//! in another format, a palette swatch) record their parent and the
+//! operation in `ClipboardItem::provenance`. This lists an item's
+//! derivatives and re-runs an operation once the source has changed.
+
+use crate::clipboard;
+use crate::color;
+use crate::database::{ClipboardItem, Database};
+use crate::ocr;
+use crate::palette;
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+/// Apply `operation` to `parent` again, returning the new text
+async fn derive(
+    parent: &ClipboardItem,
+    operation: &str,
+    options: &serde_json::Value,
+) -> Result<String, String> {
+    match operation {
+        "convert_color" => {
+            let format = options
+                .get("format")
+                .and_then(|f| f.as_str())
+                .ok_or_else(|| "Missing color format".to_string())?;
+            color::convert_color(parent.content.clone(), format.to_string()).await
+        }
+        "palette_color" => {
+            let index = options
+                .get("index")
+                .and_then(|i| i.as_u64())
+                .ok_or_else(|| "Missing swatch index".to_string())? as usize;
+            let (width, height) = clipboard::image_dimensions(parent)
+                .ok_or_else(|| "Unknown image size".to_string())?;
+            let rgba = STANDARD.decode(&parent.content).map_err(|e| e.to_string())?;
+            palette::extract_palette(&rgba, width, height)
+                .get(index)
+                .cloned()
+                .ok_or_else(|| "No swatch at that index".to_string())
+        }
+        "ocr" => {
+            let (width, height) = clipboard::image_dimensions(parent)
+                .ok_or_else(|| "Unknown image size".to_string())?;
+            let rgba = STANDARD.decode(&parent.content).map_err(|e| e.to_string())?;
+            tokio::task::spawn_blocking(move || {
+                let image = image::RgbaImage::from_raw(width, height, rgba)
+                    .ok_or_else(|| "Image data doesn't match its size".to_string())?;
+                let path = std::env::temp_dir()
+                    .join(format!("yoink-ocr-{}.png", uuid::Uuid::new_v4()));
+                image.save(&path).map_err(|e| e.to_string())?;
+                let text = ocr::recognize_text(&path);
+                let _ = std::fs::remove_file(&path);
+                text
+            })
+            .await
+            .map_err(|e| e.to_string())?
+        }
+        _ => Err(format!("Can't re-run operation: {}", operation)),
+    }
+}
+
+/// Items derived from `id`, newest first
+#[tauri::command]
+pub async fn get_derived_items(
+    db: tauri::State<'_, Database>,
+    id: String,
+) -> Result<Vec<ClipboardItem>, String> {
+    db.get_derived_items(&id).map_err(|e| e.to_string())
+}
+
+/// Re-derive item `id` from its (possibly edited) parent and update it in place
+#[tauri::command]
+pub async fn rerun_derivation(
+    db: tauri::State<'_, Database>,
+    id: String,
+) -> Result<ClipboardItem, String> {
+    let mut item = db
+        .get_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+    let provenance = item
+        .provenance
+        .clone()
+        .ok_or_else(|| "Item wasn't derived from another item".to_string())?;
+    let parent = db
+        .get_item(&provenance.parent_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Source item no longer exists".to_string())?;
+
+    let text = derive(&parent, &provenance.operation, &provenance.options).await?;
+
+    item.preview = clipboard::create_text_preview(&text);
+    item.hash = clipboard::compute_hash(&text);
+    item.content = text;
+    item.refresh_stats();
+    db.update_item_content(&item).map_err(|e| e.to_string())?;
+
+    Ok(item)
+}
@@ -0,0 +1,157 @@
+//! Queue mode: paste copies back in the order they were made.
+//!
+//! While `queue_mode_enabled` is on, every new capture is added to the end of
+//! the queue. Each paste from the queue (its hotkey or `paste_next_in_queue`)
+//! takes the item at the front, so copying A, B, C and pasting three times
+//! gives A, B, C. Changes are announced with `queue-changed`, carrying the
+//! queued items.
+
+use crate::clipboard::{self, ClipboardMonitor};
+use crate::database::{ClipboardItem, Database};
+use crate::entitlements;
+use crate::paste_helper::{self, PasteOptions};
+use crate::settings::SettingsManager;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Ids of queued items, next to paste first
+#[derive(Default)]
+pub struct PasteQueue {
+    ids: Mutex<VecDeque<String>>,
+}
+
+impl PasteQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push(&self, id: String) {
+        let mut ids = self.ids.lock().unwrap();
+        // A re-copy moves to the back rather than being queued twice
+        ids.retain(|queued| *queued != id);
+        ids.push_back(id);
+    }
+
+    fn pop(&self) -> Option<String> {
+        self.ids.lock().unwrap().pop_front()
+    }
+
+    fn clear(&self) {
+        self.ids.lock().unwrap().clear();
+    }
+
+    fn ids(&self) -> Vec<String> {
+        self.ids.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+/// Queued items in paste order. Items deleted from history (or moved to the
+/// trash) since they were queued are dropped.
+fn queued_items<R: Runtime>(app: &AppHandle<R>) -> Vec<ClipboardItem> {
+    let (Some(queue), Some(db)) = (app.try_state::<PasteQueue>(), app.try_state::<Database>())
+    else {
+        return Vec::new();
+    };
+
+    let mut items = Vec::new();
+    for id in queue.ids() {
+        match db.get_item(&id) {
+            Ok(Some(item)) if item.deleted_at.is_none() => items.push(item),
+            _ => queue.ids.lock().unwrap().retain(|queued| *queued != id),
+        }
+    }
+    items
+}
+
+fn notify<R: Runtime>(app: &AppHandle<R>) {
+    let _ = app.emit("queue-changed", queued_items(app));
+}
+
+/// Add freshly stored captures to the queue, if queue mode is on
+pub fn enqueue<R: Runtime>(app: &AppHandle<R>, items: &[ClipboardItem]) {
+    let Some(queue) = app.try_state::<PasteQueue>() else {
+        return;
+    };
+    let enabled = app
+        .try_state::<SettingsManager>()
+        .is_some_and(|s| s.get().queue_mode_enabled)
+        && entitlements::require_command(app, "toggle_queue_mode").is_ok();
+    if !enabled || items.is_empty() {
+        return;
+    }
+    for item in items {
+        queue.push(item.id.clone());
+    }
+    notify(app);
+}
+
+/// Empty the queue, e.g. when queue mode is turned off
+pub fn clear<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(queue) = app.try_state::<PasteQueue>() {
+        queue.clear();
+        notify(app);
+    }
+}
+
+/// Paste the item at the front of the queue into the frontmost app
+pub async fn paste_next<R: Runtime>(app: AppHandle<R>) -> Result<Option<ClipboardItem>, String> {
+    // The hotkey calls this directly, without going through the command gate
+    entitlements::require_command(&app, "paste_next_in_queue")?;
+    // Skip anything deleted or trashed since it was queued
+    let queue = app.state::<PasteQueue>();
+    let db = app.state::<Database>();
+    let item = loop {
+        let Some(id) = queue.pop() else {
+            break None;
+        };
+        let item = db.get_item(&id).map_err(|e| e.to_string())?;
+        if let Some(item) = item.filter(|item| item.deleted_at.is_none()) {
+            break Some(item);
+        }
+    };
+    notify(&app);
+
+    let Some(item) = item else {
+        return Ok(None);
+    };
+
+    // Writing the item back to the clipboard must not queue it again
+    if let Some(monitor) = app.try_state::<ClipboardMonitor>() {
+        monitor.mark_seen(item.hash.clone());
+    }
+    paste_helper::capture_target(&app);
+    clipboard::do_paste_and_simulate(app, item.id.clone(), PasteOptions::default()).await?;
+
+    Ok(Some(item))
+}
+
+#[tauri::command]
+pub async fn get_queue<R: Runtime>(app: AppHandle<R>) -> Result<Vec<ClipboardItem>, String> {
+    Ok(queued_items(&app))
+}
+
+#[tauri::command]
+pub async fn clear_queue<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    clear(&app);
+    Ok(())
+}
+
+/// Drop the next queued item without pasting it, returning what's left
+#[tauri::command]
+pub async fn skip_queue_item<R: Runtime>(
+    app: AppHandle<R>,
+    queue: tauri::State<'_, PasteQueue>,
+) -> Result<Vec<ClipboardItem>, String> {
+    queue.pop();
+    notify(&app);
+    Ok(queued_items(&app))
+}
+
+/// Paste the next queued item, returning it (None once the queue is empty)
+#[tauri::command]
+pub async fn paste_next_in_queue<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Option<ClipboardItem>, String> {
+    paste_next(app).await
+}
@@ -0,0 +1,28 @@
+//! Safe mode startup for recovering from bad settings.
+//!
+//! Launching with `--safe-mode` (or `YOINK_SAFE_MODE=1`) starts with capture
+//! paused, no global shortcuts registered and the default window behavior,
+//! while still loading settings so they can be fixed or reset from the UI.
+//! Safe mode lasts until the app is restarted.
+
+use tauri::{AppHandle, Manager, Runtime};
+
+const FLAG: &str = "--safe-mode";
+const ENV_VAR: &str = "YOINK_SAFE_MODE";
+
+pub struct SafeMode(pub bool);
+
+/// Whether this launch asked for safe mode
+pub fn requested() -> bool {
+    std::env::args().any(|arg| arg == FLAG)
+        || std::env::var(ENV_VAR).is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+pub fn is_active<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.try_state::<SafeMode>().is_some_and(|s| s.0)
+}
+
+#[tauri::command]
+pub async fn get_safe_mode<R: Runtime>(app: AppHandle<R>) -> Result<bool, String> {
+    Ok(is_active(&app))
+}
@@ -0,0 +1,197 @@
+//! Search box query language.
+//!
+//! A query is free text mixed with `key:value` operators, e.g.
+//! `invoice type:image tag:work before:2024-01-01`. Free-text terms must all
+//! match; quote a phrase (`"exact phrase"`) or an operator value
+//! (`collection:"Side project"`) to keep spaces in it. Supported operators:
+//!
+//! - `type:` content type (`text`, `url`/`link`, `file`, `image`, `code`, ...)
+//! - `tag:` tag name
+//! - `collection:` collection name
+//! - `pinned:` `true` or `false`
+//! - `before:` / `after:` a date (`2024-01-01`, local midnight) or RFC 3339 time
+//!
+//! Anything that isn't a known operator is searched for as text.
+
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use serde::Deserialize;
+
+/// Everything a history query can be narrowed by
+#[derive(Debug, Clone, Default)]
+pub struct ItemFilter {
+    /// Substrings that must all appear in the content or preview
+    pub text: Vec<String>,
+    /// Matches any of these content types (`type:` operators)
+    pub content_types: Vec<String>,
+    /// Matches any of these content types, from the type tabs. Kept apart
+    /// from `content_types` so `type:` operators narrow a tab, not widen it.
+    pub tab_types: Vec<String>,
+    /// Tag names the item must all have
+    pub tags: Vec<String>,
+    /// Matches items in any of these collections, by name
+    pub collections: Vec<String>,
+    pub collection_id: Option<String>,
+    /// Tag ids the item must all have
+    pub tag_ids: Vec<String>,
+    pub pinned: Option<bool>,
+    pub before: Option<DateTime<Utc>>,
+    pub after: Option<DateTime<Utc>>,
+    /// Match text terms case-sensitively
+    pub case_sensitive: bool,
+    /// Only match text terms as whole words
+    pub whole_word: bool,
+}
+
+/// How free-text terms are matched, set next to the search box
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SearchOptions {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+}
+
+/// `needle` appears in `haystack` with no letter or digit directly before or
+/// after it. Backs the `word_match` SQL function.
+pub fn contains_word(haystack: &str, needle: &str, case_sensitive: bool) -> bool {
+    if needle.is_empty() {
+        return false;
+    }
+    let (haystack, needle) = if case_sensitive {
+        (haystack.to_string(), needle.to_string())
+    } else {
+        (haystack.to_lowercase(), needle.to_lowercase())
+    };
+
+    let is_word_char = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    haystack.match_indices(&needle).any(|(start, found)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + found.len()..].chars().next();
+        !is_word_char(before) && !is_word_char(after)
+    })
+}
+
+/// `term` as a LIKE pattern matching it anywhere, with LIKE's wildcards in
+/// it taken literally (use with `ESCAPE '\'`)
+pub fn like_pattern(term: &str) -> String {
+    let mut pattern = String::with_capacity(term.len() + 2);
+    pattern.push('%');
+    for c in term.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// Split on whitespace, keeping quoted runs together (quotes removed).
+/// Tokens that start with a quote are flagged as literal text.
+fn tokenize(input: &str) -> Vec<(String, bool)> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut literal = false;
+    let mut in_quotes = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => {
+                if current.is_empty() && !in_quotes {
+                    literal = true;
+                }
+                in_quotes = !in_quotes;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push((std::mem::take(&mut current), literal));
+                }
+                literal = false;
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push((current, literal));
+    }
+    tokens
+}
+
+/// A date means midnight local time; anything else must be RFC 3339
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let midnight = date.and_hms_opt(0, 0, 0)?;
+        return Local
+            .from_local_datetime(&midnight)
+            .earliest()
+            .map(|t| t.with_timezone(&Utc));
+    }
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Content types a `type:` value or type tab stands for
+pub fn content_types(value: &str) -> Vec<String> {
+    match value.to_lowercase().as_str() {
+        "link" | "url" => vec!["url".to_string()],
+        "file" | "files" => vec!["file".to_string(), "files".to_string()],
+        "image" | "img" => vec!["image".to_string()],
+        other => vec![other.to_string()],
+    }
+}
+
+/// Parse a search box query
+pub fn parse(input: &str) -> ItemFilter {
+    let mut filter = ItemFilter::default();
+
+    for (token, literal) in tokenize(input) {
+        if literal {
+            filter.text.push(token);
+            continue;
+        }
+        let Some((key, value)) = token.split_once(':') else {
+            filter.text.push(token);
+            continue;
+        };
+        if value.is_empty() {
+            filter.text.push(token);
+            continue;
+        }
+
+        let understood = match key.to_lowercase().as_str() {
+            "type" => {
+                filter.content_types.extend(content_types(value));
+                true
+            }
+            "tag" => {
+                filter.tags.push(value.to_string());
+                true
+            }
+            "collection" => {
+                filter.collections.push(value.to_string());
+                true
+            }
+            "pinned" => match value.to_lowercase().as_str() {
+                "true" | "yes" => {
+                    filter.pinned = Some(true);
+                    true
+                }
+                "false" | "no" => {
+                    filter.pinned = Some(false);
+                    true
+                }
+                _ => false,
+            },
+            "before" => parse_time(value).map(|t| filter.before = Some(t)).is_some(),
+            "after" => parse_time(value).map(|t| filter.after = Some(t)).is_some(),
+            _ => false,
+        };
+
+        // URLs, times like 10:30 and malformed operators are just text
+        if !understood {
+            filter.text.push(token);
+        }
+    }
+
+    filter
+}
@@ -0,0 +1,192 @@
+//! Optional on-device semantic search.
+//!
+//! Items are embedded in the background with a small sentence-embedding model
+//! and the vectors are stored in SQLite, so queries match by meaning rather
+//! than keywords. The model adds a lot to the bundle, so all of this is only
+//! built with the `semantic-search` feature; without it the command reports
+//! that the index is unavailable.
+
+use crate::database::ClipboardItem;
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(not(feature = "semantic-search"), allow(dead_code))]
+pub struct SemanticMatch {
+    pub item: ClipboardItem,
+    pub score: f32,
+}
+
+#[cfg(feature = "semantic-search")]
+mod index {
+    use super::SemanticMatch;
+    use crate::database::Database;
+    use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use tauri::{AppHandle, Manager, Runtime};
+
+    /// Stored alongside each vector so switching models re-embeds everything
+    pub const MODEL_ID: &str = "all-minilm-l6-v2";
+
+    const INDEX_INTERVAL: Duration = Duration::from_secs(30);
+    const INDEX_BATCH: u32 = 32;
+    /// Long items are embedded by their opening, which is what people remember
+    const MAX_EMBED_CHARS: usize = 2000;
+
+    pub struct SemanticIndex {
+        model: Mutex<Option<TextEmbedding>>,
+        cache_dir: PathBuf,
+    }
+
+    impl SemanticIndex {
+        pub fn new(app_data_dir: PathBuf) -> Self {
+            Self {
+                model: Mutex::new(None),
+                cache_dir: app_data_dir.join("models"),
+            }
+        }
+
+        /// Embed texts as unit vectors, loading the model on first use
+        pub fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, String> {
+            let mut model = self.model.lock().unwrap();
+            if model.is_none() {
+                let options = InitOptions::new(EmbeddingModel::AllMiniLML6V2)
+                    .with_cache_dir(self.cache_dir.clone())
+                    .with_show_download_progress(false);
+                *model = Some(TextEmbedding::try_new(options).map_err(|e| e.to_string())?);
+            }
+
+            let texts: Vec<String> = texts
+                .into_iter()
+                .map(|t| t.chars().take(MAX_EMBED_CHARS).collect())
+                .collect();
+
+            let vectors = model
+                .as_ref()
+                .unwrap()
+                .embed(texts, None)
+                .map_err(|e| e.to_string())?;
+
+            Ok(vectors.into_iter().map(normalize).collect())
+        }
+    }
+
+    fn normalize(mut v: Vec<f32>) -> Vec<f32> {
+        let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            v.iter_mut().for_each(|x| *x /= norm);
+        }
+        v
+    }
+
+    /// Index new items on a background thread
+    pub fn start<R: Runtime>(app: &AppHandle<R>) {
+        let Ok(app_data_dir) = crate::portable::data_dir(app) else {
+            return;
+        };
+        app.manage(SemanticIndex::new(app_data_dir));
+
+        let app = app.clone();
+        std::thread::spawn(move || loop {
+            if let Err(e) = index_pending(&app) {
+                log::warn!("Semantic indexing failed: {}", e);
+            }
+            std::thread::sleep(INDEX_INTERVAL);
+        });
+    }
+
+    fn index_pending<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+        let db = app.state::<Database>();
+        let index = app.state::<SemanticIndex>();
+
+        db.prune_embeddings().map_err(|e| e.to_string())?;
+
+        loop {
+            let pending = db
+                .items_missing_embeddings(MODEL_ID, INDEX_BATCH)
+                .map_err(|e| e.to_string())?;
+            if pending.is_empty() {
+                return Ok(());
+            }
+
+            let (ids, texts): (Vec<String>, Vec<String>) = pending.into_iter().unzip();
+            let vectors = index.embed(texts)?;
+            let rows: Vec<(String, Vec<f32>)> = ids.into_iter().zip(vectors).collect();
+            db.store_embeddings(MODEL_ID, &rows)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    pub fn search<R: Runtime>(
+        app: &AppHandle<R>,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SemanticMatch>, String> {
+        let db = app.state::<Database>();
+        let index = app
+            .try_state::<SemanticIndex>()
+            .ok_or_else(|| "Semantic index is not running".to_string())?;
+
+        let query = index
+            .embed(vec![query.to_string()])?
+            .pop()
+            .ok_or_else(|| "Failed to embed query".to_string())?;
+
+        let mut scored: Vec<(String, f32)> = db
+            .get_embeddings(MODEL_ID)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|(_, v)| v.len() == query.len())
+            .map(|(id, v)| {
+                let score = v.iter().zip(&query).map(|(a, b)| a * b).sum();
+                (id, score)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(limit);
+
+        let mut matches = Vec::with_capacity(scored.len());
+        for (id, score) in scored {
+            if let Some(item) = db.get_item(&id).map_err(|e| e.to_string())? {
+                matches.push(SemanticMatch { item, score });
+            }
+        }
+
+        Ok(matches)
+    }
+}
+
+/// Start background indexing (no-op unless built with `semantic-search`)
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    #[cfg(feature = "semantic-search")]
+    index::start(app);
+    #[cfg(not(feature = "semantic-search"))]
+    let _ = app;
+}
+
+#[tauri::command]
+pub async fn semantic_search<R: Runtime>(
+    app: AppHandle<R>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<SemanticMatch>, String> {
+    #[cfg(feature = "semantic-search")]
+    {
+        let query = query.trim().to_string();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let limit = limit.unwrap_or(20) as usize;
+        tauri::async_runtime::spawn_blocking(move || index::search(&app, &query, limit))
+            .await
+            .map_err(|e| e.to_string())?
+    }
+
+    #[cfg(not(feature = "semantic-search"))]
+    {
+        let _ = (app, query, limit);
+        Err("Semantic search is not available in this build".to_string())
+    }
+}
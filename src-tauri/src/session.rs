@@ -0,0 +1,119 @@
+//! Fast user switching and screen lock awareness.
+//!
+//! While another user is on the console or the screen is locked, capture is
+//! paused and the panel is hidden; everything resumes when the user returns.
+//! On macOS the session dictionary is polled, like the modifier-key poller,
+//! rather than registering for workspace notifications.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager, Runtime};
+
+#[cfg(target_os = "macos")]
+use tauri::Emitter;
+
+pub struct SessionState {
+    active: AtomicBool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionInfo {
+    pub active: bool,
+}
+
+impl SessionState {
+    pub fn new() -> Self {
+        Self {
+            active: AtomicBool::new(true),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+}
+
+/// False while the user's session is switched out or locked
+pub fn is_session_active<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.try_state::<SessionState>()
+        .map_or(true, |s| s.is_active())
+}
+
+/// Whether this process's login session is on the console and unlocked
+#[cfg(target_os = "macos")]
+fn query_session() -> bool {
+    use cocoa::base::{id, nil, BOOL, YES};
+    use cocoa::foundation::NSString;
+    use objc::{msg_send, sel, sel_impl};
+
+    extern "C" {
+        fn CGSessionCopyCurrentDictionary() -> id;
+        fn CFRelease(cf: id);
+    }
+
+    unsafe {
+        // The dictionary is toll-free bridged to NSDictionary
+        let dict = CGSessionCopyCurrentDictionary();
+        if dict.is_null() {
+            return false;
+        }
+
+        let flag = |key: &str| -> Option<bool> {
+            let ns_key = NSString::alloc(nil).init_str(key);
+            let value: id = msg_send![dict, objectForKey: ns_key];
+            let _: () = msg_send![ns_key, release];
+            if value.is_null() {
+                return None;
+            }
+            let b: BOOL = msg_send![value, boolValue];
+            Some(b == YES)
+        };
+
+        let on_console = flag("kCGSSessionOnConsoleKey").unwrap_or(true);
+        let locked = flag("CGSSessionScreenIsLocked").unwrap_or(false);
+
+        CFRelease(dict);
+        on_console && !locked
+    }
+}
+
+/// Watch for the session going inactive and back
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(SessionState::new());
+
+    #[cfg(target_os = "macos")]
+    {
+        let app = app.clone();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(std::time::Duration::from_secs(1));
+
+            let active = query_session();
+            let Some(state) = app.try_state::<SessionState>() else {
+                continue;
+            };
+            if state.active.swap(active, Ordering::SeqCst) == active {
+                continue;
+            }
+
+            if active {
+                log::info!("Session active again, resuming capture");
+            } else {
+                log::info!("Session inactive (user switch or lock), pausing capture");
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    let _ = crate::window::hide_window(app).await;
+                });
+            }
+            let _ = app.emit("session-state-changed", SessionInfo { active });
+        });
+    }
+}
+
+#[tauri::command]
+pub async fn get_session_state(
+    state: tauri::State<'_, SessionState>,
+) -> Result<SessionInfo, String> {
+    Ok(SessionInfo {
+        active: state.is_active(),
+    })
+}
@@ -1,6 +1,147 @@
+use crate::entitlements;
+use crate::paste_helper::AppClass;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Paste behavior tweaks for a specific target app
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PasteOverride {
+    /// Type the content out wrapped in bracketed-paste escape sequences
+    #[serde(default)]
+    pub bracketed_paste: bool,
+
+    #[serde(default)]
+    pub strip_trailing_newlines: bool,
+
+    /// Shortcut to paste with instead of the usual Cmd+V / Ctrl+V, e.g.
+    /// `Ctrl+Shift+V` for Linux terminals
+    #[serde(default)]
+    pub keystroke: Option<String>,
+
+    /// Linux: put text in the PRIMARY selection and paste it with a middle
+    /// click, where the mouse pointer is
+    #[serde(default)]
+    pub primary_selection: bool,
+}
+
+/// Shape and placement of the main panel
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanelLayout {
+    /// A floating panel centered on the active display
+    #[default]
+    Panel,
+    /// A full-width strip docked to the bottom edge of the active display
+    BarBottom,
+    /// A full-width strip docked to the top edge of the active display
+    BarTop,
+}
+
+/// How high the panel floats above other windows (macOS)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanelLevel {
+    /// Above normal windows, below the menu bar and Dock
+    #[default]
+    Floating,
+    /// Above the menu bar and Dock
+    Status,
+    /// Above everything, including full-screen video and games
+    ScreenSaver,
+}
+
+/// Which spaces the panel shows up in (macOS)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanelSpaces {
+    /// Present in every space at once
+    #[default]
+    AllSpaces,
+    /// Opens in whichever space is active and stays there
+    CurrentSpace,
+}
+
+/// What the panel does when it loses focus
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoHide {
+    #[default]
+    Immediately,
+    /// After `auto_hide_delay_secs`, unless it gets focus back first
+    AfterDelay,
+    /// Stay up until closed, like `sticky_mode`
+    Never,
+}
+
+/// How much room the panel layout takes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutMode {
+    /// The full panel with previews
+    #[default]
+    Comfortable,
+    /// A short strip for keyboard-only use
+    Compact,
+}
+
+/// Which display the panel opens on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanelDisplay {
+    /// The display under the mouse pointer
+    #[default]
+    Cursor,
+    /// The display holding the frontmost app's window (macOS; elsewhere the
+    /// cursor's display)
+    FocusedWindow,
+    /// The display named `panel_display_name`, while it's connected
+    Specific,
+}
+
+/// Where the panel layout opens
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowPosition {
+    /// Centered, slightly high, on the display under the mouse pointer
+    #[default]
+    CursorMonitorCenter,
+    /// With its top-left corner at the mouse pointer
+    AtMousePointer,
+    /// At `fixed_window_position`
+    Fixed,
+    /// Experimental: just below the text caret in the frontmost app, like
+    /// inline autocomplete. Falls back to the mouse pointer in apps that
+    /// don't report their caret (macOS only).
+    AtTextCaret,
+}
+
+/// A saved panel size, in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PanelSize {
+    pub width: f64,
+    pub height: f64,
+}
+
+/// A saved top-left window corner, in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SavedPosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// What smart paste does when the target is a given class of app
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SmartPasteRule {
+    /// Paste only plain text; false keeps the stored formatting
+    #[serde(default)]
+    pub plain_text: bool,
+
+    #[serde(default)]
+    pub strip_trailing_newlines: bool,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
@@ -36,6 +177,244 @@ pub struct Settings {
 
     #[serde(default)]
     pub sticky_mode: bool,
+
+    /// Hide the panel on focus loss immediately, after a delay, or never.
+    /// `sticky_mode` still wins and keeps it up.
+    #[serde(default)]
+    pub auto_hide: AutoHide,
+
+    #[serde(default = "default_auto_hide_delay_secs")]
+    pub auto_hide_delay_secs: u32,
+
+    /// Keep the panel up as a floating palette that never takes focus, so
+    /// clicking an item pastes straight into the frontmost app
+    #[serde(default)]
+    pub palette_mode: bool,
+
+    #[serde(default = "default_true")]
+    pub capture_images: bool,
+
+    #[serde(default = "default_true")]
+    pub capture_files: bool,
+
+    /// Shortcut for capture → OCR → history (empty disables it)
+    #[serde(default)]
+    pub ocr_hotkey: String,
+
+    /// Also keep the captured screenshot in history alongside the OCR text
+    #[serde(default)]
+    pub ocr_keep_image: bool,
+
+    /// Also record the X11 PRIMARY (middle-click) selection on Linux
+    #[serde(default)]
+    pub capture_primary_selection: bool,
+
+    /// Paste text through the PRIMARY selection on Linux, for apps without a
+    /// per-app override
+    #[serde(default)]
+    pub primary_selection_paste: bool,
+
+    /// Record items synced from other devices via Universal Clipboard
+    #[serde(default = "default_true")]
+    pub capture_handoff: bool,
+
+    /// Fetch page titles, descriptions and favicons for copied links. Off by
+    /// default: a fetch can use up one-time links and tells the site you
+    /// copied it.
+    #[serde(default)]
+    pub enrich_urls: bool,
+
+    /// Generate short titles for long text items
+    #[serde(default)]
+    pub auto_titles: bool,
+
+    #[serde(default)]
+    pub browser_extension_enabled: bool,
+
+    #[serde(default = "default_browser_extension_port")]
+    pub browser_extension_port: u16,
+
+    /// Shared secret the browser extension must send with every request
+    #[serde(default)]
+    pub browser_extension_token: String,
+
+    /// What to do with password-manager copies: "skip" or "expire"
+    #[serde(default = "default_concealed_copy_mode")]
+    pub concealed_copy_mode: String,
+
+    /// Use bracketed paste and strip trailing newlines when pasting into known terminals
+    #[serde(default)]
+    pub terminal_paste_mode: bool,
+
+    /// Per-app paste behavior, keyed by bundle id
+    #[serde(default)]
+    pub paste_overrides: HashMap<String, PasteOverride>,
+
+    /// Adapt pastes to the kind of target app, following `smart_paste_rules`
+    #[serde(default)]
+    pub smart_paste: bool,
+
+    /// Smart paste behavior per app class; classes without a rule paste as usual
+    #[serde(default = "default_smart_paste_rules")]
+    pub smart_paste_rules: HashMap<AppClass, SmartPasteRule>,
+
+    /// Ask for confirmation before risky pastes (secrets into chat, long terminal pastes)
+    #[serde(default)]
+    pub paste_lint_enabled: bool,
+
+    /// Line count above which terminal pastes need confirmation (0 disables)
+    #[serde(default = "default_paste_lint_max_terminal_lines")]
+    pub paste_lint_max_terminal_lines: u32,
+
+    /// Inserted between copies while append mode is on
+    #[serde(default = "default_append_separator")]
+    pub append_separator: String,
+
+    /// Shortcut that toggles append mode (empty disables it)
+    #[serde(default)]
+    pub append_mode_hotkey: String,
+
+    /// Append new captures to a per-day Markdown note
+    #[serde(default)]
+    pub daily_notes_enabled: bool,
+
+    /// Folder the daily notes are written to (e.g. inside an Obsidian vault)
+    #[serde(default)]
+    pub daily_notes_folder: String,
+
+    /// Content types to include (empty includes every text type)
+    #[serde(default)]
+    pub daily_notes_types: Vec<String>,
+
+    /// Apps whose copies never go into daily notes
+    #[serde(default)]
+    pub daily_notes_excluded_apps: Vec<String>,
+
+    /// Skip captures shorter than this many characters
+    #[serde(default)]
+    pub daily_notes_min_length: u32,
+
+    /// Days deleted items stay in the trash before being purged (0 keeps
+    /// them until the trash is emptied)
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+
+    /// Upper bound on the content size of history in MB, evicting the oldest
+    /// unprotected items first (0 for no limit)
+    #[serde(default = "default_max_history_size_mb")]
+    pub max_history_size_mb: u32,
+
+    /// Move items evicted by the history limits to the archive instead of
+    /// deleting them (images are deleted either way)
+    #[serde(default = "default_true")]
+    pub archive_evicted_items: bool,
+
+    /// Evict unprotected items last copied more than this many days ago
+    /// (0 keeps them regardless of age)
+    #[serde(default)]
+    pub max_age_days: u32,
+
+    /// Put the previous clipboard contents back after an auto-paste, so
+    /// pasting from history doesn't replace what was just copied
+    #[serde(default)]
+    pub transient_paste: bool,
+
+    /// Paste without formatting unless a paste asks for it
+    #[serde(default)]
+    pub paste_plain_text: bool,
+
+    /// Shortcut that pastes the latest item as plain text (empty disables it)
+    #[serde(default)]
+    pub plain_paste_hotkey: String,
+
+    /// Shortcut that pastes the next item in the queue (empty disables it)
+    #[serde(default)]
+    pub queue_paste_hotkey: String,
+
+    /// Shortcut that pastes the previous history item, going further back
+    /// on repeated presses (empty disables it)
+    #[serde(default)]
+    pub paste_previous_hotkey: String,
+
+    /// How long to wait after handing focus back before sending the paste
+    /// keystroke. Heavy apps can need more than the default.
+    #[serde(default = "default_paste_delay_ms")]
+    pub paste_delay_ms: u64,
+
+    /// How many times to re-activate the target app if it isn't frontmost
+    /// when the paste is due (0 pastes without checking)
+    #[serde(default = "default_paste_focus_retries")]
+    pub paste_focus_retries: u32,
+
+    /// Press Return after every paste, for chat apps and search fields
+    #[serde(default)]
+    pub paste_and_submit: bool,
+
+    /// Modifiers for the pinned item shortcuts, e.g. `Cmd+Ctrl`: with them
+    /// held, 1-9 paste the first nine pinned items (empty disables them)
+    #[serde(default)]
+    pub pinned_paste_modifiers: String,
+
+    /// Floating panel, or a bar docked to the bottom or top screen edge
+    #[serde(default)]
+    pub panel_layout: PanelLayout,
+
+    /// Clicking the tray icon drops the panel down from the icon, like a
+    /// menu bar popover, instead of opening the tray menu
+    #[serde(default)]
+    pub tray_anchored_panel: bool,
+
+    /// How high the panel floats above other windows
+    #[serde(default)]
+    pub panel_level: PanelLevel,
+
+    /// Let the panel appear over full-screen apps. Off, it only opens in
+    /// regular spaces.
+    #[serde(default = "default_true")]
+    pub show_over_full_screen: bool,
+
+    /// Whether the panel joins every space or only the current one
+    #[serde(default)]
+    pub panel_spaces: PanelSpaces,
+
+    /// Keep a mini window of pinned items up for one-click pasting
+    #[serde(default)]
+    pub pinned_board_enabled: bool,
+
+    /// Fade and slide the panel in and out. Off, or with the system's
+    /// reduce motion setting on, it just appears and disappears.
+    #[serde(default = "default_true")]
+    pub panel_animations: bool,
+
+    /// Size of the panel layout: comfortable, or a compact strip
+    #[serde(default)]
+    pub layout_mode: LayoutMode,
+
+    /// Panel sizes saved per display, keyed by display name, overriding
+    /// `layout_mode` there
+    #[serde(default)]
+    pub display_panel_sizes: HashMap<String, PanelSize>,
+
+    /// Height of the bar layouts, in logical pixels
+    #[serde(default = "default_bar_height")]
+    pub bar_height: u32,
+
+    /// Which display the panel and bars open on
+    #[serde(default)]
+    pub panel_display: PanelDisplay,
+
+    /// Display for `specific` placement, as listed by `list_displays`
+    #[serde(default)]
+    pub panel_display_name: String,
+
+    /// Where the panel layout opens; bars always dock to their edge
+    #[serde(default)]
+    pub window_position: WindowPosition,
+
+    /// Where the panel opens in `fixed` position mode. Unset, or off every
+    /// connected display, falls back to centering on the cursor's display.
+    #[serde(default)]
+    pub fixed_window_position: Option<SavedPosition>,
 }
 
 fn default_hotkey() -> String {
@@ -61,6 +440,67 @@ fn default_font_size() -> u32 {
     14
 }
 
+fn default_browser_extension_port() -> u16 {
+    47811
+}
+
+fn default_concealed_copy_mode() -> String {
+    "skip".to_string()
+}
+
+fn default_paste_lint_max_terminal_lines() -> u32 {
+    10
+}
+
+fn default_append_separator() -> String {
+    "\n".to_string()
+}
+
+fn default_trash_retention_days() -> u32 {
+    30
+}
+
+fn default_max_history_size_mb() -> u32 {
+    1024
+}
+
+fn default_paste_delay_ms() -> u64 {
+    100
+}
+
+fn default_paste_focus_retries() -> u32 {
+    3
+}
+
+fn default_auto_hide_delay_secs() -> u32 {
+    3
+}
+
+fn default_bar_height() -> u32 {
+    300
+}
+
+/// Terminals get plain text without a trailing newline that would run the
+/// command; rich editors keep formatting
+fn default_smart_paste_rules() -> HashMap<AppClass, SmartPasteRule> {
+    HashMap::from([
+        (
+            AppClass::Terminal,
+            SmartPasteRule {
+                plain_text: true,
+                strip_trailing_newlines: true,
+            },
+        ),
+        (
+            AppClass::RichEditor,
+            SmartPasteRule {
+                plain_text: false,
+                strip_trailing_newlines: false,
+            },
+        ),
+    ])
+}
+
 fn default_true() -> bool {
     true
 }
@@ -79,6 +519,62 @@ impl Default for Settings {
             queue_mode_enabled: false,
             auto_paste: true,
             sticky_mode: false,
+            auto_hide: AutoHide::default(),
+            auto_hide_delay_secs: default_auto_hide_delay_secs(),
+            palette_mode: false,
+            capture_images: true,
+            capture_files: true,
+            ocr_hotkey: String::new(),
+            ocr_keep_image: false,
+            capture_primary_selection: false,
+            primary_selection_paste: false,
+            capture_handoff: true,
+            enrich_urls: false,
+            auto_titles: false,
+            browser_extension_enabled: false,
+            browser_extension_port: default_browser_extension_port(),
+            browser_extension_token: String::new(),
+            concealed_copy_mode: default_concealed_copy_mode(),
+            terminal_paste_mode: false,
+            paste_overrides: HashMap::new(),
+            smart_paste: false,
+            smart_paste_rules: default_smart_paste_rules(),
+            paste_lint_enabled: false,
+            paste_lint_max_terminal_lines: default_paste_lint_max_terminal_lines(),
+            append_separator: default_append_separator(),
+            append_mode_hotkey: String::new(),
+            daily_notes_enabled: false,
+            daily_notes_folder: String::new(),
+            daily_notes_types: Vec::new(),
+            daily_notes_excluded_apps: Vec::new(),
+            daily_notes_min_length: 0,
+            trash_retention_days: default_trash_retention_days(),
+            max_history_size_mb: default_max_history_size_mb(),
+            archive_evicted_items: true,
+            max_age_days: 0,
+            transient_paste: false,
+            paste_plain_text: false,
+            plain_paste_hotkey: String::new(),
+            queue_paste_hotkey: String::new(),
+            paste_previous_hotkey: String::new(),
+            paste_delay_ms: default_paste_delay_ms(),
+            paste_focus_retries: default_paste_focus_retries(),
+            paste_and_submit: false,
+            pinned_paste_modifiers: String::new(),
+            panel_layout: PanelLayout::default(),
+            tray_anchored_panel: false,
+            panel_level: PanelLevel::default(),
+            show_over_full_screen: true,
+            panel_spaces: PanelSpaces::default(),
+            pinned_board_enabled: false,
+            panel_animations: true,
+            layout_mode: LayoutMode::default(),
+            display_panel_sizes: HashMap::new(),
+            bar_height: default_bar_height(),
+            panel_display: PanelDisplay::default(),
+            panel_display_name: String::new(),
+            window_position: WindowPosition::default(),
+            fixed_window_position: None,
         }
     }
 }
@@ -143,11 +639,21 @@ pub async fn get_settings(manager: tauri::State<'_, SettingsManager>) -> Result<
     Ok(manager.get())
 }
 
+/// Settings behind Pro commands keep their stored values unless the user
+/// is entitled, so this can't be used to get around `entitlements::gate`
 #[tauri::command]
-pub async fn update_settings(
+pub async fn update_settings<R: Runtime>(
+    app: AppHandle<R>,
     manager: tauri::State<'_, SettingsManager>,
-    settings: Settings,
+    mut settings: Settings,
 ) -> Result<(), String> {
+    let current = manager.get();
+    if entitlements::require_command(&app, "toggle_queue_mode").is_err() {
+        settings.queue_mode_enabled = current.queue_mode_enabled;
+    }
+    if entitlements::require_command(&app, "register_queue_hotkey").is_err() {
+        settings.queue_paste_hotkey = current.queue_paste_hotkey;
+    }
     manager.update(settings)
 }
 
@@ -159,6 +665,31 @@ pub async fn set_hotkey(
     manager.update_field(|s| s.hotkey = hotkey)
 }
 
+/// Replace all settings with the defaults, keeping the browser extension
+/// token so a paired extension keeps working
+#[tauri::command]
+pub async fn reset_to_defaults<R: Runtime>(
+    app: AppHandle<R>,
+    manager: tauri::State<'_, SettingsManager>,
+) -> Result<Settings, String> {
+    let mut defaults = Settings::default();
+    defaults.browser_extension_token = manager.get().browser_extension_token;
+    manager.update(defaults.clone())?;
+
+    // Hotkeys stay off until restart in safe mode
+    if !crate::safe_mode::is_active(&app) {
+        if let Some(hotkey_manager) = app.try_state::<crate::hotkey::HotkeyManager>() {
+            let boards = app
+                .try_state::<crate::database::Database>()
+                .and_then(|db| db.get_boards().ok())
+                .unwrap_or_default();
+            hotkey_manager.reregister_all(&app, &defaults, &boards);
+        }
+    }
+
+    Ok(defaults)
+}
+
 #[tauri::command]
 pub async fn set_theme(
     manager: tauri::State<'_, SettingsManager>,
@@ -198,8 +729,35 @@ pub async fn remove_excluded_app(
 }
 
 #[tauri::command]
-pub async fn toggle_queue_mode(
+pub async fn set_paste_override(
+    manager: tauri::State<'_, SettingsManager>,
+    app_id: String,
+    paste_override: Option<PasteOverride>,
+) -> Result<Settings, String> {
+    if let Some(keystroke) = paste_override.as_ref().and_then(|o| o.keystroke.as_deref()) {
+        if crate::keyboard::Keystroke::parse(keystroke).is_none() {
+            return Err(format!("Invalid paste shortcut: {}", keystroke));
+        }
+    }
+    manager.update_field(|s| match paste_override {
+        Some(o) => {
+            s.paste_overrides.insert(app_id, o);
+        }
+        None => {
+            s.paste_overrides.remove(&app_id);
+        }
+    })
+}
+
+/// Turning queue mode off drops whatever was still queued
+#[tauri::command]
+pub async fn toggle_queue_mode<R: Runtime>(
+    app: AppHandle<R>,
     manager: tauri::State<'_, SettingsManager>,
 ) -> Result<Settings, String> {
-    manager.update_field(|s| s.queue_mode_enabled = !s.queue_mode_enabled)
+    let settings = manager.update_field(|s| s.queue_mode_enabled = !s.queue_mode_enabled)?;
+    if !settings.queue_mode_enabled {
+        crate::queue::clear(&app);
+    }
+    Ok(settings)
 }
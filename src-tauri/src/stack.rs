@@ -0,0 +1,180 @@
+//! Stack mode: collect copies in a small floating window, then paste them
+//! back newest first.
+//!
+//! While stack mode is on, every new capture is pushed onto the stack and
+//! the main hotkey pastes the top item instead of opening the panel. The
+//! stack window (`window::STACK_WINDOW_LABEL`) shows the stack and follows
+//! `stack-changed`, which carries the stacked items top first.
+
+use crate::clipboard::{self, ClipboardMonitor};
+use crate::database::{ClipboardItem, Database};
+use crate::paste_helper::{self, PasteOptions};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+#[derive(Default)]
+pub struct PasteStack {
+    active: AtomicBool,
+    /// Item ids, top of the stack last
+    ids: Mutex<Vec<String>>,
+}
+
+impl PasteStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    fn push(&self, id: String) {
+        let mut ids = self.ids.lock().unwrap();
+        // A re-copy moves to the top rather than being stacked twice
+        ids.retain(|stacked| *stacked != id);
+        ids.push(id);
+    }
+}
+
+/// Stacked items, top first. Items deleted from history (or moved to the
+/// trash) since they were stacked are dropped.
+fn stacked_items<R: Runtime>(app: &AppHandle<R>) -> Vec<ClipboardItem> {
+    let (Some(stack), Some(db)) = (app.try_state::<PasteStack>(), app.try_state::<Database>())
+    else {
+        return Vec::new();
+    };
+
+    let ids: Vec<String> = stack.ids.lock().unwrap().iter().rev().cloned().collect();
+    let mut items = Vec::with_capacity(ids.len());
+    for id in ids {
+        match db.get_item(&id) {
+            Ok(Some(item)) if item.deleted_at.is_none() => items.push(item),
+            _ => stack.ids.lock().unwrap().retain(|stacked| *stacked != id),
+        }
+    }
+    items
+}
+
+fn notify<R: Runtime>(app: &AppHandle<R>) {
+    let _ = app.emit("stack-changed", stacked_items(app));
+}
+
+/// Whether the main hotkey should pop the stack rather than open the panel
+pub fn is_active<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.try_state::<PasteStack>().is_some_and(|s| s.is_active())
+}
+
+/// Push freshly stored captures onto the stack, if stack mode is on
+pub fn push_captures<R: Runtime>(app: &AppHandle<R>, items: &[ClipboardItem]) {
+    let Some(stack) = app.try_state::<PasteStack>() else {
+        return;
+    };
+    if !stack.is_active() || items.is_empty() {
+        return;
+    }
+    for item in items {
+        stack.push(item.id.clone());
+    }
+    notify(app);
+}
+
+/// Paste the top of the stack into the frontmost app
+pub async fn paste_top<R: Runtime>(app: AppHandle<R>) -> Result<Option<ClipboardItem>, String> {
+    // Skip anything deleted or trashed since it was stacked
+    let stack = app.state::<PasteStack>();
+    let db = app.state::<Database>();
+    let item = loop {
+        let Some(id) = stack.ids.lock().unwrap().pop() else {
+            break None;
+        };
+        let item = db.get_item(&id).map_err(|e| e.to_string())?;
+        if let Some(item) = item.filter(|item| item.deleted_at.is_none()) {
+            break Some(item);
+        }
+    };
+    notify(&app);
+
+    let Some(item) = item else {
+        return Ok(None);
+    };
+
+    // Writing the item back to the clipboard must not stack it again
+    if let Some(monitor) = app.try_state::<ClipboardMonitor>() {
+        monitor.mark_seen(item.hash.clone());
+    }
+    paste_helper::capture_target(&app);
+    let options = PasteOptions {
+        keep_open: true,
+        ..Default::default()
+    };
+    clipboard::do_paste_and_simulate(app, item.id.clone(), options).await?;
+
+    Ok(Some(item))
+}
+
+/// Turn stack mode on or off, showing or closing the stack window. Turning
+/// it off empties the stack.
+#[tauri::command]
+pub async fn set_stack_mode<R: Runtime>(
+    app: AppHandle<R>,
+    stack: tauri::State<'_, PasteStack>,
+    enabled: bool,
+) -> Result<(), String> {
+    stack.active.store(enabled, Ordering::SeqCst);
+    if enabled {
+        crate::window::show_stack_window(&app)?;
+    } else {
+        stack.ids.lock().unwrap().clear();
+        crate::window::close_stack_window(&app);
+    }
+    let _ = app.emit("stack-mode-changed", enabled);
+    notify(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_stack<R: Runtime>(app: AppHandle<R>) -> Result<Vec<ClipboardItem>, String> {
+    Ok(stacked_items(&app))
+}
+
+/// Put a history item on top of the stack
+#[tauri::command]
+pub async fn push_to_stack<R: Runtime>(
+    app: AppHandle<R>,
+    stack: tauri::State<'_, PasteStack>,
+    id: String,
+) -> Result<(), String> {
+    stack.push(id);
+    notify(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_from_stack<R: Runtime>(
+    app: AppHandle<R>,
+    stack: tauri::State<'_, PasteStack>,
+    id: String,
+) -> Result<(), String> {
+    stack.ids.lock().unwrap().retain(|stacked| *stacked != id);
+    notify(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn clear_stack<R: Runtime>(
+    app: AppHandle<R>,
+    stack: tauri::State<'_, PasteStack>,
+) -> Result<(), String> {
+    stack.ids.lock().unwrap().clear();
+    notify(&app);
+    Ok(())
+}
+
+/// Paste and remove the top item, returning it (None once the stack is empty)
+#[tauri::command]
+pub async fn paste_from_stack<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Option<ClipboardItem>, String> {
+    paste_top(app).await
+}
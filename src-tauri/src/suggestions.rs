@@ -0,0 +1,100 @@
+//! Context-aware ranking: items pasted into the current app, around this time
+//! of day, float to the top (e.g. the standup template in Slack each morning).
+
+use crate::database::{ClipboardItem, Database};
+use crate::paste_helper;
+use chrono::{DateTime, Local, Timelike, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{AppHandle, Runtime};
+
+/// How far back paste history is considered
+const LOOKBACK_DAYS: i64 = 60;
+/// A paste loses half its weight after this many days
+const HALF_LIFE_DAYS: f64 = 14.0;
+
+const SAME_APP_WEIGHT: f64 = 1.0;
+const OTHER_APP_WEIGHT: f64 = 0.2;
+/// Flat boost for items recently copied from the current app
+const SOURCE_APP_BOOST: f64 = 0.5;
+const SOURCE_APP_CANDIDATES: u32 = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Suggestion {
+    pub item: ClipboardItem,
+    pub score: f64,
+}
+
+/// Pastes at a similar hour count more, wrapping around midnight
+fn time_of_day_weight(now_hour: u32, then_hour: u32) -> f64 {
+    let diff = (now_hour as i32 - then_hour as i32).unsigned_abs();
+    match diff.min(24 - diff) {
+        0..=1 => 1.0,
+        2..=3 => 0.5,
+        _ => 0.15,
+    }
+}
+
+fn recency_weight(now: DateTime<Utc>, then: DateTime<Utc>) -> f64 {
+    let days = (now - then).num_minutes().max(0) as f64 / (60.0 * 24.0);
+    0.5f64.powf(days / HALF_LIFE_DAYS)
+}
+
+pub fn rank(
+    db: &Database,
+    current_app: Option<&str>,
+    limit: usize,
+) -> Result<Vec<Suggestion>, String> {
+    let now = Utc::now();
+    let now_hour = Local::now().hour();
+
+    let usage = db
+        .get_usage_since(now - chrono::Duration::days(LOOKBACK_DAYS))
+        .map_err(|e| e.to_string())?;
+
+    let mut scores: HashMap<String, f64> = HashMap::new();
+    for (item_id, app_id, used_at) in usage {
+        let app_weight = match (current_app, app_id.as_deref()) {
+            (Some(current), Some(used)) if current == used => SAME_APP_WEIGHT,
+            _ => OTHER_APP_WEIGHT,
+        };
+        let hour = used_at.with_timezone(&Local).hour();
+        *scores.entry(item_id).or_default() +=
+            app_weight * time_of_day_weight(now_hour, hour) * recency_weight(now, used_at);
+    }
+
+    if let Some(current) = current_app {
+        let copied_here = db
+            .get_items_from_app(current, SOURCE_APP_CANDIDATES)
+            .map_err(|e| e.to_string())?;
+        for item in copied_here {
+            *scores.entry(item.id).or_default() +=
+                SOURCE_APP_BOOST * recency_weight(now, item.created_at);
+        }
+    }
+
+    let mut ranked: Vec<(String, f64)> = scores.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut suggestions = Vec::with_capacity(limit);
+    for (id, score) in ranked {
+        if suggestions.len() >= limit {
+            break;
+        }
+        if let Some(item) = db.get_item(&id).map_err(|e| e.to_string())? {
+            suggestions.push(Suggestion { item, score });
+        }
+    }
+
+    Ok(suggestions)
+}
+
+#[tauri::command]
+pub async fn get_suggestions<R: Runtime>(
+    app: AppHandle<R>,
+    db: tauri::State<'_, Database>,
+    limit: Option<u32>,
+) -> Result<Vec<Suggestion>, String> {
+    let current_app = paste_helper::target_app(&app);
+    rank(&db, current_app.as_deref(), limit.unwrap_or(5) as usize)
+}
@@ -0,0 +1,84 @@
+//! Frequently pasted emoji and special characters.
+//!
+//! Pastes of short symbol-only items are counted so the most used ones can
+//! be offered as a character picker, and selected in hotkey mode with the
+//! digit keys (1 picks the most used symbol, 2 the next, ...).
+
+use crate::database::{ClipboardItem, Database, FrequentSymbol};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Longest run of characters still treated as one symbol; emoji with skin
+/// tones, flags and ZWJ sequences are several chars each
+const MAX_SYMBOL_CHARS: usize = 8;
+
+/// Symbols reachable with the digit keys in hotkey mode
+pub const HOTKEY_SYMBOLS: usize = 9;
+
+/// Whether `text` is an emoji or special character rather than a word
+pub fn is_symbol(text: &str) -> bool {
+    let text = text.trim();
+    let count = text.chars().count();
+    (1..=MAX_SYMBOL_CHARS).contains(&count)
+        && !text.chars().any(|c| c.is_alphanumeric() || c.is_whitespace())
+        && text.chars().any(|c| !c.is_ascii())
+}
+
+/// Count a paste of `item` if it is a symbol
+pub fn record_paste(db: &Database, item: &ClipboardItem) {
+    if item.content_type == "image" || !is_symbol(&item.content) {
+        return;
+    }
+    if let Err(e) = db.record_symbol_use(item.content.trim(), &item.hash) {
+        log::warn!("Failed to record symbol use: {}", e);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+struct HotkeySymbolSelected {
+    index: usize,
+    symbol: String,
+    item_id: String,
+}
+
+/// Select the `index`th most used symbol for the paste that happens when the
+/// hotkey modifiers are released
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn select_for_hotkey<R: Runtime>(app: &AppHandle<R>, index: usize) {
+    let Some(db) = app.try_state::<Database>() else {
+        return;
+    };
+    let symbol = match db.get_frequent_symbols(HOTKEY_SYMBOLS as u32) {
+        Ok(symbols) => symbols.into_iter().nth(index),
+        Err(e) => {
+            log::warn!("Failed to load frequent symbols: {}", e);
+            return;
+        }
+    };
+
+    // Symbols whose item has left history can't be pasted from it
+    let Some((symbol, item_id)) = symbol.and_then(|s| Some((s.symbol, s.item_id?))) else {
+        return;
+    };
+    if let Some(selected) = app.try_state::<crate::window::SelectedItemState>() {
+        selected.set(item_id.clone());
+    }
+    let _ = app.emit(
+        "hotkey-symbol-selected",
+        HotkeySymbolSelected {
+            index,
+            symbol,
+            item_id,
+        },
+    );
+}
+
+#[tauri::command]
+pub async fn get_frequent_symbols(
+    db: tauri::State<'_, Database>,
+    limit: Option<u32>,
+) -> Result<Vec<FrequentSymbol>, String> {
+    db.get_frequent_symbols(limit.unwrap_or(HOTKEY_SYMBOLS as u32))
+        .map_err(|e| e.to_string())
+}
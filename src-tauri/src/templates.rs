@@ -0,0 +1,233 @@
+//! Template items with `{placeholder}` variables.
+//!
+//! Items in a collection marked as a template collection are filled in at
+//! paste time: the paste emits `template-values-required` with the
+//! placeholders to prompt for, and the UI pastes again with the values (or
+//! calls `resolve_template` to just get the text). Write `{{` and `}}` for
+//! literal braces.
+//!
+//! Built-in placeholders fill themselves in unless given a value: `{date}`,
+//! `{time}` (both take a strftime format, as in `{date:%d %B %Y}`), `{uuid}`
+//! and `{clipboard}`, the text on the clipboard when pasting. Pinned items
+//! get the built-ins expanded too, which makes them usable as snippets.
+
+use crate::database::Database;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Placeholders filled in automatically when no value is given
+const BUILTINS: &[&str] = &["date", "time", "uuid", "clipboard"];
+
+/// Built-ins that take a `:format` argument
+const FORMATTED_BUILTINS: &[&str] = &["date", "time"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateValuesRequest {
+    pub item_id: String,
+    pub variables: Vec<String>,
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    /// `{{` or `}}`
+    Escape(&'a str),
+    /// The whole `{...}` source, the name and a built-in's format argument
+    Placeholder {
+        source: &'a str,
+        name: &'a str,
+        arg: Option<&'a str>,
+    },
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Split a template into literal text and placeholders. Braces that don't
+/// wrap a valid name are kept as text, so code and JSON snippets survive.
+fn parse(template: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find(['{', '}']) {
+        let (before, from_brace) = rest.split_at(start);
+        if !before.is_empty() {
+            segments.push(Segment::Text(before));
+        }
+
+        if from_brace.starts_with("{{") || from_brace.starts_with("}}") {
+            segments.push(Segment::Escape(&from_brace[..2]));
+            rest = &from_brace[2..];
+            continue;
+        }
+
+        if from_brace.starts_with('{') {
+            if let Some(end) = from_brace[1..].find('}') {
+                let inner = &from_brace[1..end + 1];
+                // Only built-ins take an argument, so `{a:1}` stays text
+                let (name, arg) = match inner.split_once(':') {
+                    Some((name, arg)) if FORMATTED_BUILTINS.contains(&name) => (name, Some(arg)),
+                    _ => (inner, None),
+                };
+                if !name.is_empty() && name.chars().all(is_name_char) {
+                    segments.push(Segment::Placeholder {
+                        source: &from_brace[..end + 2],
+                        name,
+                        arg,
+                    });
+                    rest = &from_brace[end + 2..];
+                    continue;
+                }
+            }
+        }
+
+        segments.push(Segment::Text(&from_brace[..1]));
+        rest = &from_brace[1..];
+    }
+
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    segments
+}
+
+/// Distinct placeholder names in order of first appearance
+pub fn placeholders(template: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for segment in parse(template) {
+        if let Segment::Placeholder { name, .. } = segment {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Placeholders the user has to be asked for
+pub fn prompted_placeholders(template: &str) -> Vec<String> {
+    placeholders(template)
+        .into_iter()
+        .filter(|name| !BUILTINS.contains(&name.as_str()))
+        .collect()
+}
+
+/// Value of a built-in placeholder. `clipboard` is the clipboard text at
+/// paste time, if there is any; an invalid date format gives None.
+fn builtin_value(name: &str, arg: Option<&str>, clipboard: Option<&str>) -> Option<String> {
+    let now = chrono::Local::now();
+    let formatted = |default: &str| {
+        let mut out = String::new();
+        write!(out, "{}", now.format(arg.unwrap_or(default))).ok()?;
+        Some(out)
+    };
+    match name {
+        "date" => formatted("%Y-%m-%d"),
+        "time" => formatted("%H:%M"),
+        "uuid" => Some(uuid::Uuid::new_v4().to_string()),
+        "clipboard" => Some(clipboard.unwrap_or_default().to_string()),
+        _ => None,
+    }
+}
+
+/// Whether expanding `text` needs the current clipboard text
+fn uses_clipboard(text: &str) -> bool {
+    placeholders(text).iter().any(|name| name == "clipboard")
+}
+
+/// The clipboard text for a `{clipboard}` placeholder, read only when
+/// `text` has one
+pub fn clipboard_text<R: Runtime>(app: &AppHandle<R>, text: &str) -> Option<String> {
+    if !uses_clipboard(text) {
+        return None;
+    }
+    app.clipboard().read_text().ok()
+}
+
+/// Fill in the built-in placeholders only, leaving everything else,
+/// escaped braces included, exactly as it was
+pub fn expand_builtins(text: &str, clipboard: Option<&str>) -> String {
+    let mut out = String::with_capacity(text.len());
+    for segment in parse(text) {
+        match segment {
+            Segment::Text(text) | Segment::Escape(text) => out.push_str(text),
+            Segment::Placeholder { source, name, arg } => {
+                match builtin_value(name, arg, clipboard) {
+                    Some(value) => out.push_str(&value),
+                    None => out.push_str(source),
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Fill in every placeholder, failing if any has no value
+pub fn resolve(
+    template: &str,
+    values: &HashMap<String, String>,
+    clipboard: Option<&str>,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut missing: Vec<&str> = Vec::new();
+
+    for segment in parse(template) {
+        match segment {
+            Segment::Text(text) => out.push_str(text),
+            Segment::Escape(escape) => out.push_str(&escape[..1]),
+            Segment::Placeholder { name, arg, .. } => {
+                let value = values
+                    .get(name)
+                    .cloned()
+                    .or_else(|| builtin_value(name, arg, clipboard));
+                match value {
+                    Some(value) => out.push_str(&value),
+                    None if !missing.contains(&name) => missing.push(name),
+                    None => {}
+                }
+            }
+        }
+    }
+
+    if !missing.is_empty() {
+        return Err(format!("Missing values for: {}", missing.join(", ")));
+    }
+    Ok(out)
+}
+
+/// Placeholders to prompt for before pasting item `id` (empty if the item
+/// isn't a template)
+#[tauri::command]
+pub async fn get_template_variables(
+    db: tauri::State<'_, Database>,
+    id: String,
+) -> Result<Vec<String>, String> {
+    if !db.is_template_item(&id).map_err(|e| e.to_string())? {
+        return Ok(Vec::new());
+    }
+    let content = db
+        .get_item_content(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+
+    Ok(prompted_placeholders(&content))
+}
+
+/// Fill in template item `id` with `values`
+#[tauri::command]
+pub async fn resolve_template<R: Runtime>(
+    app: AppHandle<R>,
+    db: tauri::State<'_, Database>,
+    id: String,
+    values: HashMap<String, String>,
+) -> Result<String, String> {
+    let content = db
+        .get_item_content(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+
+    resolve(&content, &values, clipboard_text(&app, &content).as_deref())
+}
@@ -0,0 +1,123 @@
+use crate::database::Database;
+use std::collections::HashMap;
+
+/// Texts shorter than this (and with few lines) keep their preview as label
+const MIN_TITLE_CHARS: usize = 200;
+const MIN_TITLE_LINES: usize = 4;
+
+const MAX_TITLE_CHARS: usize = 60;
+const MAX_KEYWORDS: usize = 5;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "has", "have", "i",
+    "if", "in", "is", "it", "its", "of", "on", "or", "our", "so", "that", "the", "this", "to",
+    "was", "we", "were", "will", "with", "you", "your",
+];
+
+/// Produces a short label for long text items. The heuristic is the only
+/// implementation for now; an LLM-backed one can slot in behind this trait.
+pub trait Summarizer: Send + Sync {
+    fn title(&self, text: &str) -> Option<String>;
+}
+
+/// First meaningful line if it reads like a title, otherwise top keywords
+pub struct HeuristicSummarizer;
+
+impl Summarizer for HeuristicSummarizer {
+    fn title(&self, text: &str) -> Option<String> {
+        if text.chars().count() < MIN_TITLE_CHARS && text.lines().count() < MIN_TITLE_LINES {
+            return None;
+        }
+
+        let first_line = text
+            .lines()
+            .map(|l| l.trim())
+            .find(|l| !l.is_empty())?;
+
+        // Email drafts and markdown documents carry their own title
+        let first_line = first_line
+            .strip_prefix("Subject:")
+            .unwrap_or(first_line)
+            .trim_start_matches('#')
+            .trim();
+
+        let words = first_line.split_whitespace().count();
+        if (2..=12).contains(&words) && first_line.chars().count() <= MAX_TITLE_CHARS {
+            return Some(first_line.to_string());
+        }
+
+        // First sentence of the first line, if it's short enough
+        if let Some(end) = first_line.find(['.', '!', '?']) {
+            let sentence = first_line[..end].trim();
+            if sentence.split_whitespace().count() >= 2 && sentence.chars().count() <= MAX_TITLE_CHARS {
+                return Some(sentence.to_string());
+            }
+        }
+
+        keywords(text)
+    }
+}
+
+fn keywords(text: &str) -> Option<String> {
+    let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+    for (position, word) in text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() > 2)
+        .enumerate()
+    {
+        let word = word.to_lowercase();
+        if STOPWORDS.contains(&word.as_str()) || word.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        counts.entry(word).or_insert((0, position)).0 += 1;
+    }
+
+    // Most frequent first, ties broken by first appearance
+    let mut ranked: Vec<(String, (usize, usize))> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then(a.1 .1.cmp(&b.1 .1)));
+
+    let title = ranked
+        .into_iter()
+        .take(MAX_KEYWORDS)
+        .map(|(word, _)| word)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    (!title.is_empty()).then_some(title)
+}
+
+pub fn summarizer() -> Box<dyn Summarizer> {
+    Box::new(HeuristicSummarizer)
+}
+
+#[tauri::command]
+pub async fn set_item_title(
+    db: tauri::State<'_, Database>,
+    id: String,
+    title: Option<String>,
+) -> Result<(), String> {
+    db.set_item_title(&id, title.as_deref().filter(|t| !t.trim().is_empty()))
+        .map_err(|e| e.to_string())
+}
+
+/// Re-run the summarizer on an item, replacing any existing title
+#[tauri::command]
+pub async fn generate_item_title(
+    db: tauri::State<'_, Database>,
+    id: String,
+) -> Result<Option<String>, String> {
+    let item = db
+        .get_item(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())?;
+
+    if item.content_type == "image" {
+        return Ok(None);
+    }
+
+    let title = summarizer().title(&item.content);
+    db.set_item_title(&id, title.as_deref())
+        .map_err(|e| e.to_string())?;
+
+    Ok(title)
+}
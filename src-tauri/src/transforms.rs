@@ -0,0 +1,278 @@
+//! Text transformations applied at paste time ("Paste as…").
+//!
+//! Each transform has a stable id the frontend passes back; a paste can chain
+//! several, applied in order. Only text is transformed: images and file
+//! lists are pasted unchanged.
+//!
+//! Structured items also have derived formats, offered next to the
+//! transforms as paste variants: a URL as a Markdown link, JSON minified, an
+//! image as a base64 data URI, a file as its path.
+
+use crate::database::ClipboardItem;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Serialize;
+
+struct Transform {
+    id: &'static str,
+    name: &'static str,
+    apply: fn(&str) -> String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransformInfo {
+    pub id: &'static str,
+    pub name: &'static str,
+}
+
+/// Every transform, in the order menus list them
+const TRANSFORMS: &[Transform] = &[
+    Transform {
+        id: "trim",
+        name: "Trim whitespace",
+        apply: |text| text.trim().to_string(),
+    },
+    Transform {
+        id: "uppercase",
+        name: "UPPERCASE",
+        apply: |text| text.to_uppercase(),
+    },
+    Transform {
+        id: "lowercase",
+        name: "lowercase",
+        apply: |text| text.to_lowercase(),
+    },
+    Transform {
+        id: "title_case",
+        name: "Title Case",
+        apply: title_case,
+    },
+    Transform {
+        id: "snake_case",
+        name: "snake_case",
+        apply: |text| words(text).join("_"),
+    },
+    Transform {
+        id: "kebab_case",
+        name: "kebab-case",
+        apply: |text| words(text).join("-"),
+    },
+    Transform {
+        id: "camel_case",
+        name: "camelCase",
+        apply: camel_case,
+    },
+    Transform {
+        id: "strip_newlines",
+        name: "Join lines",
+        apply: |text| {
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        },
+    },
+    Transform {
+        id: "collapse_whitespace",
+        name: "Collapse whitespace",
+        apply: |text| text.split_whitespace().collect::<Vec<_>>().join(" "),
+    },
+];
+
+/// A text format derived from a whole item, rather than from its text
+struct Derived {
+    id: &'static str,
+    name: &'static str,
+    applies: fn(&ClipboardItem) -> bool,
+    derive: fn(&ClipboardItem) -> Result<String, String>,
+}
+
+const DERIVED: &[Derived] = &[
+    Derived {
+        id: "markdown_link",
+        name: "Markdown link",
+        applies: |item| item.content_type == "url",
+        derive: markdown_link,
+    },
+    Derived {
+        id: "json_minified",
+        name: "Minified JSON",
+        applies: |item| is_text(item) && parse_json(&item.content).is_some(),
+        derive: |item| {
+            parse_json(&item.content)
+                .map(|json| json.to_string())
+                .ok_or_else(|| "Not valid JSON".to_string())
+        },
+    },
+    Derived {
+        id: "data_uri",
+        name: "Base64 data URI",
+        applies: |item| item.content_type == "image",
+        derive: data_uri,
+    },
+    Derived {
+        id: "file_path",
+        name: "File path",
+        applies: |item| matches!(item.content_type.as_str(), "file" | "files"),
+        derive: |item| Ok(crate::clipboard::file_paths(&item.content).join("\n")),
+    },
+];
+
+fn is_text(item: &ClipboardItem) -> bool {
+    !matches!(item.content_type.as_str(), "image" | "file" | "files")
+}
+
+/// `text` as a JSON object or array; bare strings and numbers don't count
+pub(crate) fn parse_json(text: &str) -> Option<serde_json::Value> {
+    let text = text.trim();
+    if !(text.starts_with('{') || text.starts_with('[')) {
+        return None;
+    }
+    serde_json::from_str(text).ok()
+}
+
+/// `[title](url)`, titled with the page title when enrichment found one
+fn markdown_link(item: &ClipboardItem) -> Result<String, String> {
+    let url = item.content.trim();
+    let title = item
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("title"))
+        .and_then(|t| t.as_str())
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or(url);
+    let title = title.trim().replace('[', "\\[").replace(']', "\\]");
+    Ok(format!("[{}]({})", title, url.replace(' ', "%20")))
+}
+
+fn data_uri(item: &ClipboardItem) -> Result<String, String> {
+    let png = crate::clipboard::image_png(item)?;
+    Ok(format!("data:image/png;base64,{}", STANDARD.encode(png)))
+}
+
+/// Lowercase words of `text`, split on anything that isn't a letter or
+/// digit and on camelCase humps
+fn words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+
+    for c in text.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn title_case(text: &str) -> String {
+    // Keeps the original spacing and punctuation; only word starts change
+    let mut out = String::with_capacity(text.len());
+    let mut at_word_start = true;
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            if at_word_start {
+                out.extend(c.to_uppercase());
+            } else {
+                out.extend(c.to_lowercase());
+            }
+            at_word_start = false;
+        } else {
+            out.push(c);
+            at_word_start = !matches!(c, '\'' | '’');
+        }
+    }
+    out
+}
+
+fn camel_case(text: &str) -> String {
+    words(text)
+        .iter()
+        .enumerate()
+        .map(|(i, word)| if i == 0 { word.clone() } else { capitalize(word) })
+        .collect()
+}
+
+fn find(id: &str) -> Option<&'static Transform> {
+    TRANSFORMS.iter().find(|t| t.id == id)
+}
+
+/// Run `transforms` over `text` in order, failing on an unknown id before
+/// anything is applied
+pub fn apply_all(text: &str, transforms: &[String]) -> Result<String, String> {
+    let pipeline = transforms
+        .iter()
+        .map(|id| find(id).ok_or_else(|| format!("Unknown transform: {}", id)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(pipeline
+        .into_iter()
+        .fold(text.to_string(), |text, transform| (transform.apply)(&text)))
+}
+
+/// Ids and names of what `item` can be pasted as: its derived formats, then
+/// for text, every transform
+pub fn variants(item: &ClipboardItem) -> Vec<TransformInfo> {
+    let derived = DERIVED.iter().filter(|d| (d.applies)(item)).map(|d| TransformInfo {
+        id: d.id,
+        name: d.name,
+    });
+    let transforms = TRANSFORMS
+        .iter()
+        .filter(|_| is_text(item))
+        .map(|t| TransformInfo {
+            id: t.id,
+            name: t.name,
+        });
+    derived.chain(transforms).collect()
+}
+
+/// The text of derived format `id` for `item`, or None if `id` isn't a
+/// derived format (it may still be a transform)
+pub fn derive(item: &ClipboardItem, id: &str) -> Result<Option<String>, String> {
+    let Some(derived) = DERIVED.iter().find(|d| d.id == id) else {
+        return Ok(None);
+    };
+    if !(derived.applies)(item) {
+        return Err(format!("{} doesn't apply to this item", derived.name));
+    }
+    (derived.derive)(item).map(Some)
+}
+
+/// Transforms the "Paste as…" menu can offer
+#[tauri::command]
+pub async fn get_transforms() -> Result<Vec<TransformInfo>, String> {
+    Ok(TRANSFORMS
+        .iter()
+        .map(|t| TransformInfo {
+            id: t.id,
+            name: t.name,
+        })
+        .collect())
+}
+
+/// Preview the result of a pipeline without pasting
+#[tauri::command]
+pub async fn apply_transforms(text: String, transforms: Vec<String>) -> Result<String, String> {
+    apply_all(&text, &transforms)
+}
@@ -1,11 +1,12 @@
-use tauri::{Manager, Runtime, WebviewWindow};
+use crate::layout;
+use tauri::{Emitter, Manager, Runtime, WebviewWindow};
 use std::sync::atomic::{AtomicBool, Ordering};
 
 #[cfg(target_os = "macos")]
-use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
 
 #[cfg(target_os = "macos")]
-use tauri::Emitter;
+use std::sync::Mutex;
 
 #[cfg(target_os = "macos")]
 use tauri_nspanel::{
@@ -23,6 +24,15 @@ use cocoa::base::id;
 
 pub const MAIN_WINDOW_LABEL: &str = "main";
 
+/// Floating window listing the paste stack (see `stack.rs`)
+pub const STACK_WINDOW_LABEL: &str = "stack";
+
+/// Full-content preview beside the main panel (see `preview.rs`)
+pub const PREVIEW_WINDOW_LABEL: &str = "preview";
+
+/// Always-visible grid of pinned items (see `pinned_board.rs`)
+pub const PINNED_BOARD_WINDOW_LABEL: &str = "pinned";
+
 /// Guards against re-entrant panel hide (order_out triggers windowDidResignKey)
 pub struct PanelHideGuard {
     is_hiding: AtomicBool,
@@ -48,6 +58,64 @@ impl PanelHideGuard {
     }
 }
 
+/// Pending focus-loss hide for `AutoHide::AfterDelay`. Every focus change
+/// bumps the generation, so a timer that wakes to a newer one does nothing.
+#[cfg(target_os = "macos")]
+pub struct AutoHideTimer {
+    generation: AtomicU64,
+}
+
+#[cfg(target_os = "macos")]
+impl AutoHideTimer {
+    pub fn new() -> Self {
+        Self {
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Cancel any pending hide, returning the generation a new one should
+    /// check against
+    fn cancel(&self) -> u64 {
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn is_current(&self, generation: u64) -> bool {
+        self.generation.load(Ordering::SeqCst) == generation
+    }
+}
+
+/// Where the tray icon is, from its latest event, and whether the next
+/// show was asked for from the tray (for `tray_anchored_panel`)
+pub struct TrayAnchor {
+    icon: std::sync::Mutex<Option<tauri::Rect>>,
+    pending: AtomicBool,
+}
+
+impl TrayAnchor {
+    pub fn new() -> Self {
+        Self {
+            icon: std::sync::Mutex::new(None),
+            pending: AtomicBool::new(false),
+        }
+    }
+
+    pub fn record(&self, icon: tauri::Rect) {
+        *self.icon.lock().unwrap() = Some(icon);
+    }
+
+    /// Anchor the next show to the tray icon
+    pub fn request(&self) {
+        self.pending.store(true, Ordering::SeqCst);
+    }
+
+    fn take(&self) -> Option<tauri::Rect> {
+        if !self.pending.swap(false, Ordering::SeqCst) {
+            return None;
+        }
+        *self.icon.lock().unwrap()
+    }
+}
+
 /// Tracks whether we're in hotkey mode (modifiers held after Cmd+Shift+V)
 /// When active, the panel should NOT auto-hide on focus loss
 pub struct HotkeyModeState {
@@ -131,6 +199,28 @@ impl PreviousAppState {
         }
     }
 
+    /// Bundle identifier of the captured application, without releasing it
+    pub fn bundle_id(&self) -> Option<String> {
+        use objc::{msg_send, sel, sel_impl};
+        let guard = self.app.lock().unwrap();
+        let prev_app = (*guard)?;
+        unsafe {
+            let bundle_id: id = msg_send![prev_app, bundleIdentifier];
+            crate::pasteboard::nsstring_to_string(bundle_id)
+        }
+    }
+
+    /// Activate the previously captured application but keep tracking it
+    pub fn activate(&self) {
+        use objc::{msg_send, sel, sel_impl};
+        let guard = self.app.lock().unwrap();
+        if let Some(prev_app) = *guard {
+            unsafe {
+                let _: () = msg_send![prev_app, activateWithOptions: 1u64];
+            }
+        }
+    }
+
     /// Restore focus to the previously captured application
     pub fn restore(&self) {
         use objc::{msg_send, sel, sel_impl};
@@ -145,15 +235,254 @@ impl PreviousAppState {
     }
 }
 
+/// Bundle identifier of the frontmost application
+#[cfg(target_os = "macos")]
+pub fn frontmost_bundle_id() -> Option<String> {
+    use objc::{class, msg_send, sel, sel_impl};
+    unsafe {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let frontmost: id = msg_send![workspace, frontmostApplication];
+        if frontmost.is_null() {
+            return None;
+        }
+        let bundle_id: id = msg_send![frontmost, bundleIdentifier];
+        crate::pasteboard::nsstring_to_string(bundle_id)
+    }
+}
+
+/// Center of the frontmost application's front window, in global display
+/// points (top-left origin, like Tauri's logical coordinates)
+#[cfg(target_os = "macos")]
+pub fn frontmost_window_center() -> Option<(f64, f64)> {
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGWindowListCopyWindowInfo(option: u32, relative_to: u32) -> id;
+    }
+    /// kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements
+    const ON_SCREEN_WINDOWS: u32 = (1 << 0) | (1 << 4);
+
+    unsafe fn number(dict: id, key: &str) -> Option<f64> {
+        let ns_key = NSString::alloc(cocoa::base::nil).init_str(key);
+        let value: id = msg_send![dict, objectForKey: ns_key];
+        let _: () = msg_send![ns_key, release];
+        if value.is_null() {
+            return None;
+        }
+        Some(msg_send![value, doubleValue])
+    }
+
+    unsafe {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let frontmost: id = msg_send![workspace, frontmostApplication];
+        if frontmost.is_null() {
+            return None;
+        }
+        let pid: i32 = msg_send![frontmost, processIdentifier];
+
+        // Front to back, so the first normal-level window of the app is its
+        // front window
+        let windows = CGWindowListCopyWindowInfo(ON_SCREEN_WINDOWS, 0);
+        if windows.is_null() {
+            return None;
+        }
+        let count: usize = msg_send![windows, count];
+        let mut center = None;
+        for i in 0..count {
+            let info: id = msg_send![windows, objectAtIndex: i];
+            if number(info, "kCGWindowOwnerPID") != Some(pid as f64)
+                || number(info, "kCGWindowLayer") != Some(0.0)
+            {
+                continue;
+            }
+            let bounds_key = NSString::alloc(cocoa::base::nil).init_str("kCGWindowBounds");
+            let bounds: id = msg_send![info, objectForKey: bounds_key];
+            let _: () = msg_send![bounds_key, release];
+            if bounds.is_null() {
+                continue;
+            }
+            if let (Some(x), Some(y), Some(w), Some(h)) = (
+                number(bounds, "X"),
+                number(bounds, "Y"),
+                number(bounds, "Width"),
+                number(bounds, "Height"),
+            ) {
+                center = Some((x + w / 2.0, y + h / 2.0));
+                break;
+            }
+        }
+        let _: () = msg_send![windows, release];
+        center
+    }
+}
+
+/// Bounds of the text caret (or selection) in the frontmost app's focused
+/// text field, in global display points (top-left origin), as `(x, y,
+/// width, height)`. None when the app doesn't report it over Accessibility,
+/// or we aren't trusted for Accessibility.
+#[cfg(target_os = "macos")]
+pub fn text_caret_bounds() -> Option<(f64, f64, f64, f64)> {
+    use cocoa::foundation::NSString;
+    use core_graphics::geometry::{CGPoint, CGRect, CGSize};
+    use objc::{msg_send, sel, sel_impl};
+    use std::ffi::c_void;
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXUIElementCreateSystemWide() -> id;
+        fn AXUIElementCopyAttributeValue(element: id, attribute: id, value: *mut id) -> i32;
+        fn AXUIElementCopyParameterizedAttributeValue(
+            element: id,
+            attribute: id,
+            parameter: id,
+            value: *mut id,
+        ) -> i32;
+        fn AXValueGetValue(value: id, value_type: u32, value_ptr: *mut c_void) -> bool;
+        fn CFRelease(cf: id);
+    }
+    /// kAXErrorSuccess
+    const AX_SUCCESS: i32 = 0;
+    /// kAXValueCGRectType
+    const AX_VALUE_CG_RECT: u32 = 3;
+
+    /// `element`'s `attribute`, retained, or null
+    unsafe fn copy_attribute(element: id, attribute: &str, parameter: Option<id>) -> id {
+        let name = NSString::alloc(cocoa::base::nil).init_str(attribute);
+        let mut value: id = cocoa::base::nil;
+        let error = match parameter {
+            Some(parameter) => {
+                AXUIElementCopyParameterizedAttributeValue(element, name, parameter, &mut value)
+            }
+            None => AXUIElementCopyAttributeValue(element, name, &mut value),
+        };
+        let _: () = msg_send![name, release];
+        if error == AX_SUCCESS {
+            value
+        } else {
+            cocoa::base::nil
+        }
+    }
+
+    unsafe {
+        let system = AXUIElementCreateSystemWide();
+        if system.is_null() {
+            return None;
+        }
+        let focused = copy_attribute(system, "AXFocusedUIElement", None);
+        CFRelease(system);
+        if focused.is_null() {
+            return None;
+        }
+
+        let range = copy_attribute(focused, "AXSelectedTextRange", None);
+        let bounds = if range.is_null() {
+            cocoa::base::nil
+        } else {
+            let bounds = copy_attribute(focused, "AXBoundsForRange", Some(range));
+            CFRelease(range);
+            bounds
+        };
+        CFRelease(focused);
+        if bounds.is_null() {
+            return None;
+        }
+
+        let mut rect = CGRect::new(&CGPoint::new(0.0, 0.0), &CGSize::new(0.0, 0.0));
+        let ok = AXValueGetValue(
+            bounds,
+            AX_VALUE_CG_RECT,
+            &mut rect as *mut CGRect as *mut c_void,
+        );
+        CFRelease(bounds);
+        // Some apps answer with an empty rect at the origin rather than an error
+        let empty = rect.origin.x == 0.0 && rect.origin.y == 0.0 && rect.size.height == 0.0;
+        if !ok || empty {
+            return None;
+        }
+        Some((rect.origin.x, rect.origin.y, rect.size.width, rect.size.height))
+    }
+}
+
+/// Bring the running application with `bundle_id` to the front. Returns
+/// false if no such application is running.
+#[cfg(target_os = "macos")]
+pub fn activate_bundle_id(bundle_id: &str) -> bool {
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+    unsafe {
+        let ns_bundle_id = NSString::alloc(cocoa::base::nil).init_str(bundle_id);
+        let apps: id = msg_send![
+            class!(NSRunningApplication),
+            runningApplicationsWithBundleIdentifier: ns_bundle_id
+        ];
+        let _: () = msg_send![ns_bundle_id, release];
+        let target: id = msg_send![apps, firstObject];
+        if target.is_null() {
+            return false;
+        }
+        let _: () = msg_send![target, activateWithOptions: 1u64];
+        true
+    }
+}
+
 #[cfg(target_os = "macos")]
 unsafe impl Send for PreviousAppState {}
 #[cfg(target_os = "macos")]
 unsafe impl Sync for PreviousAppState {}
 
+/// NSWindowLevel for `settings.panel_level`
+#[cfg(target_os = "macos")]
+fn panel_window_level(settings: &crate::settings::Settings) -> i32 {
+    use crate::settings::PanelLevel;
+    match settings.panel_level {
+        // NSFloatingWindowLevel
+        PanelLevel::Floating => 5,
+        // NSStatusWindowLevel
+        PanelLevel::Status => 25,
+        // NSScreenSaverWindowLevel
+        PanelLevel::ScreenSaver => 1000,
+    }
+}
+
+/// How the panel behaves across spaces and full-screen apps
+#[cfg(target_os = "macos")]
+fn panel_collection_behavior(settings: &crate::settings::Settings) -> NSWindowCollectionBehavior {
+    use crate::settings::PanelSpaces;
+    let spaces = match settings.panel_spaces {
+        PanelSpaces::AllSpaces => {
+            NSWindowCollectionBehavior::NSWindowCollectionBehaviorCanJoinAllSpaces
+        }
+        // Pulled into the active space each time it's shown
+        PanelSpaces::CurrentSpace => {
+            NSWindowCollectionBehavior::NSWindowCollectionBehaviorMoveToActiveSpace
+        }
+    };
+    let mut behavior = spaces | NSWindowCollectionBehavior::NSWindowCollectionBehaviorTransient;
+    if settings.show_over_full_screen {
+        behavior |= NSWindowCollectionBehavior::NSWindowCollectionBehaviorFullScreenAuxiliary;
+    }
+    behavior
+}
+
+/// Re-apply the level and collection behavior settings to the open panel
+#[cfg(target_os = "macos")]
+fn apply_panel_behavior<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<(), String> {
+    let Ok(panel) = app.get_webview_panel(MAIN_WINDOW_LABEL) else {
+        return Ok(());
+    };
+    let settings = current_settings(app);
+    app.run_on_main_thread(move || {
+        panel.set_level(panel_window_level(&settings));
+        panel.set_collection_behaviour(panel_collection_behavior(&settings));
+    })
+    .map_err(|e| e.to_string())
+}
+
 #[cfg(target_os = "macos")]
 pub trait WebviewWindowExt {
     fn to_yoink_panel(&self) -> tauri::Result<ShareId<RawNSPanel>>;
-    fn center_at_cursor_monitor(&self) -> Result<(), String>;
 }
 
 #[cfg(target_os = "macos")]
@@ -161,14 +490,10 @@ impl<R: Runtime> WebviewWindowExt for WebviewWindow<R> {
     fn to_yoink_panel(&self) -> tauri::Result<ShareId<RawNSPanel>> {
         let panel = self.to_panel()?;
 
-        // Set panel level to floating (NSFloatingWindowLevel = 5)
-        panel.set_level(5);
-
-        // Set collection behavior for proper space handling
-        let behavior = NSWindowCollectionBehavior::NSWindowCollectionBehaviorCanJoinAllSpaces
-            | NSWindowCollectionBehavior::NSWindowCollectionBehaviorFullScreenAuxiliary
-            | NSWindowCollectionBehavior::NSWindowCollectionBehaviorTransient;
-        panel.set_collection_behaviour(behavior);
+        // Window level and collection behavior come from settings
+        let settings = current_settings(self.app_handle());
+        panel.set_level(panel_window_level(&settings));
+        panel.set_collection_behaviour(panel_collection_behavior(&settings));
 
         // Set as floating panel
         panel.set_floating_panel(true);
@@ -176,10 +501,29 @@ impl<R: Runtime> WebviewWindowExt for WebviewWindow<R> {
         // Setup delegate for event handling
         let app_handle = self.app_handle().clone();
         let delegate = panel_delegate!(YoinkPanelDelegate {
+            window_did_become_key,
             window_did_resign_key
         });
 
         delegate.set_listener(Box::new(move |delegate_name: String| {
+            // A palette never keeps key: hand the keyboard straight back to
+            // the app being pasted into
+            if delegate_name == "window_did_become_key" && is_palette_mode(&app_handle) {
+                if let Ok(panel) = app_handle.get_webview_panel(MAIN_WINDOW_LABEL) {
+                    panel.resign_key_window();
+                }
+                focus_previous_app(&app_handle);
+                return;
+            }
+
+            // Focus came back before a delayed hide fired
+            if delegate_name == "window_did_become_key" {
+                if let Some(timer) = app_handle.try_state::<AutoHideTimer>() {
+                    timer.cancel();
+                }
+                return;
+            }
+
             if delegate_name == "window_did_resign_key" {
                 log::info!("panel resigned key window");
 
@@ -218,26 +562,43 @@ impl<R: Runtime> WebviewWindowExt for WebviewWindow<R> {
                     return;
                 }
 
-                // Check if sticky mode is enabled
-                let sticky_mode = if let Some(settings_manager) =
-                    app_handle.try_state::<crate::settings::SettingsManager>()
-                {
-                    settings_manager.get().sticky_mode
-                } else {
-                    false
-                };
+                // Safe mode's default settings always auto-hide
+                let settings = current_settings(&app_handle);
 
                 // In sticky mode, don't auto-hide on focus loss
-                if sticky_mode {
+                if settings.sticky_mode {
                     log::info!("Sticky mode enabled, not hiding panel");
                     return;
                 }
 
-                // Hide panel when it loses focus
-                if let Ok(panel) = app_handle.get_webview_panel(MAIN_WINDOW_LABEL) {
-                    if panel.is_visible() {
-                        panel.order_out(None);
-                        let _ = app_handle.emit("panel-hidden", ());
+                // A palette stays up until it's closed explicitly
+                if is_palette_mode(&app_handle) {
+                    return;
+                }
+
+                match settings.auto_hide {
+                    crate::settings::AutoHide::Never => {
+                        log::info!("Auto-hide off, not hiding panel");
+                    }
+                    crate::settings::AutoHide::Immediately => hide_on_focus_loss(&app_handle),
+                    crate::settings::AutoHide::AfterDelay => {
+                        let Some(timer) = app_handle.try_state::<AutoHideTimer>() else {
+                            hide_on_focus_loss(&app_handle);
+                            return;
+                        };
+                        let generation = timer.cancel();
+                        let delay = std::time::Duration::from_secs(settings.auto_hide_delay_secs.into());
+                        let app_handle = app_handle.clone();
+                        tauri::async_runtime::spawn(async move {
+                            tokio::time::sleep(delay).await;
+                            let still_pending = app_handle
+                                .try_state::<AutoHideTimer>()
+                                .is_some_and(|timer| timer.is_current(generation));
+                            if still_pending {
+                                let app = app_handle.clone();
+                                let _ = app_handle.run_on_main_thread(move || hide_on_focus_loss(&app));
+                            }
+                        });
                     }
                 }
             }
@@ -247,29 +608,19 @@ impl<R: Runtime> WebviewWindowExt for WebviewWindow<R> {
 
         Ok(panel)
     }
+}
 
-    fn center_at_cursor_monitor(&self) -> Result<(), String> {
-        // Get monitor with cursor
-        let monitor = monitor::get_monitor_with_cursor()
-            .ok_or_else(|| "Monitor with cursor not found".to_string())?;
-
-        let scale = monitor.scale_factor();
-        let monitor_size = monitor.size().to_logical::<f64>(scale);
-        let monitor_pos = monitor.position().to_logical::<f64>(scale);
-
-        // Get window size
-        let window_size = self.outer_size()
-            .map_err(|e| e.to_string())?
-            .to_logical::<f64>(scale);
-
-        // Calculate centered position (slightly above center)
-        let x = monitor_pos.x + (monitor_size.width - window_size.width) / 2.0;
-        let y = monitor_pos.y + (monitor_size.height - window_size.height) / 2.0 - 50.0;
-
-        self.set_position(tauri::Position::Logical(tauri::LogicalPosition::new(x, y)))
-            .map_err(|e| e.to_string())?;
-
-        Ok(())
+/// Hide the panel after it lost focus. Focus has already gone to another
+/// app, so unlike `hide_window` this doesn't hand it back. Must run on the
+/// main thread.
+#[cfg(target_os = "macos")]
+fn hide_on_focus_loss<R: Runtime>(app: &tauri::AppHandle<R>) {
+    if let Ok(panel) = app.get_webview_panel(MAIN_WINDOW_LABEL) {
+        if panel.is_visible() {
+            panel.order_out(None);
+            close_preview_window(app);
+            let _ = app.emit("panel-hidden", ());
+        }
     }
 }
 
@@ -370,50 +721,446 @@ pub fn set_window_blur<R: Runtime>(window: &WebviewWindow<R>, _enabled: bool) ->
     Ok(())
 }
 
+/// Tint behind the panel where the system blur can't be used, matching the
+/// dark HUD material on macOS
 #[cfg(not(target_os = "macos"))]
-#[allow(dead_code)]
-pub fn set_window_blur<R: Runtime>(_window: &WebviewWindow<R>, _enabled: bool) -> Result<(), String> {
+const TRANSLUCENT_TINT: (u8, u8, u8, u8) = (24, 24, 24, 217);
+
+/// Apply Mica (Windows 11) or, on Windows 10, acrylic blur behind the panel
+#[cfg(windows)]
+pub fn set_window_blur<R: Runtime>(window: &WebviewWindow<R>, enabled: bool) -> Result<(), String> {
+    use window_vibrancy::{apply_acrylic, apply_mica, clear_acrylic, clear_mica};
+
+    if !enabled {
+        let _ = clear_mica(window);
+        let _ = clear_acrylic(window);
+        return Ok(());
+    }
+
+    match apply_mica(window, Some(true)) {
+        Ok(()) => log::info!("Mica backdrop applied"),
+        Err(mica_err) => {
+            // Mica needs Windows 11; acrylic works back to Windows 10 1809
+            apply_acrylic(window, Some(TRANSLUCENT_TINT)).map_err(|e| {
+                format!("Neither mica ({}) nor acrylic ({}) is available", mica_err, e)
+            })?;
+            log::info!("Acrylic backdrop applied");
+        }
+    }
+    Ok(())
+}
+
+/// Linux has no portable blur API (it's up to the compositor), so the panel
+/// gets a translucent tint instead. Needs a compositing window manager to
+/// show through.
+#[cfg(all(not(target_os = "macos"), not(windows)))]
+pub fn set_window_blur<R: Runtime>(window: &WebviewWindow<R>, enabled: bool) -> Result<(), String> {
+    use tauri::window::Color;
+
+    let color = if enabled {
+        let (r, g, b, a) = TRANSLUCENT_TINT;
+        Color(r, g, b, a)
+    } else {
+        Color(0, 0, 0, 0)
+    };
+    window
+        .set_background_color(Some(color))
+        .map_err(|e| e.to_string())
+}
+
+/// Give focus back to the app that was frontmost before the panel opened,
+/// without hiding the panel
+pub fn focus_previous_app<R: Runtime>(app: &tauri::AppHandle<R>) {
+    #[cfg(target_os = "macos")]
+    if let Some(state) = app.try_state::<PreviousAppState>() {
+        state.activate();
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = app;
+}
+
+/// Open the stack window, or bring it back if it's already open. It floats
+/// above other apps without taking focus from them.
+pub fn show_stack_window<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(STACK_WINDOW_LABEL) {
+        return window.show().map_err(|e| e.to_string());
+    }
+
+    tauri::WebviewWindowBuilder::new(
+        app,
+        STACK_WINDOW_LABEL,
+        tauri::WebviewUrl::App("index.html?view=stack".into()),
+    )
+    .title("Yoink Stack")
+    .inner_size(260.0, 340.0)
+    .resizable(true)
+    .decorations(false)
+    .transparent(true)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .focused(false)
+    .visible_on_all_workspaces(true)
+    .build()
+    .map_err(|e| e.to_string())?;
+
     Ok(())
 }
 
+pub fn close_stack_window<R: Runtime>(app: &tauri::AppHandle<R>) {
+    if let Some(window) = app.get_webview_window(STACK_WINDOW_LABEL) {
+        let _ = window.close();
+    }
+}
+
+/// Open the pinned board, or bring it back if it's already open. Like the
+/// stack window it floats above other apps, but it can't take focus at all,
+/// so a click pastes into the app you were in.
+pub fn show_pinned_board_window<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(PINNED_BOARD_WINDOW_LABEL) {
+        return window.show().map_err(|e| e.to_string());
+    }
+
+    tauri::WebviewWindowBuilder::new(
+        app,
+        PINNED_BOARD_WINDOW_LABEL,
+        tauri::WebviewUrl::App("index.html?view=pinned".into()),
+    )
+    .title("Yoink Pinned")
+    .inner_size(300.0, 220.0)
+    .resizable(true)
+    .decorations(false)
+    .transparent(true)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .focused(false)
+    .focusable(false)
+    .visible_on_all_workspaces(true)
+    .build()
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+pub fn close_pinned_board_window<R: Runtime>(app: &tauri::AppHandle<R>) {
+    if let Some(window) = app.get_webview_window(PINNED_BOARD_WINDOW_LABEL) {
+        let _ = window.close();
+    }
+}
+
+/// Open the preview window beside the main panel, or move an open one back
+/// beside it. It never takes focus, so the panel stays key.
+pub fn show_preview_window<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<(), String> {
+    let position = app
+        .get_webview_window(MAIN_WINDOW_LABEL)
+        .and_then(|panel| layout::beside(&panel).ok());
+
+    if let Some(window) = app.get_webview_window(PREVIEW_WINDOW_LABEL) {
+        if let Some(position) = position {
+            window.set_position(position).map_err(|e| e.to_string())?;
+        }
+        return window.show().map_err(|e| e.to_string());
+    }
+
+    let (width, height) = layout::PREVIEW_SIZE;
+    let mut builder = tauri::WebviewWindowBuilder::new(
+        app,
+        PREVIEW_WINDOW_LABEL,
+        tauri::WebviewUrl::App("index.html?view=preview".into()),
+    )
+    .title("Yoink Preview")
+    .inner_size(width, height)
+    .resizable(true)
+    .decorations(false)
+    .transparent(true)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .focused(false)
+    .focusable(false)
+    .visible_on_all_workspaces(true);
+    if let Some(position) = position {
+        builder = builder.position(position.x, position.y);
+    }
+    builder.build().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+pub fn close_preview_window<R: Runtime>(app: &tauri::AppHandle<R>) {
+    crate::preview::clear(app);
+    if let Some(window) = app.get_webview_window(PREVIEW_WINDOW_LABEL) {
+        let _ = window.close();
+    }
+}
+
+/// Settings that shape the panel and its windows. Safe mode uses the
+/// defaults, since the saved ones may be what broke the window.
+pub(crate) fn current_settings<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> crate::settings::Settings {
+    if crate::safe_mode::is_active(app) {
+        return crate::settings::Settings::default();
+    }
+    app.try_state::<crate::settings::SettingsManager>()
+        .map(|s| s.get())
+        .unwrap_or_default()
+}
+
+/// Size and position the main window for the current layout, just before
+/// it's shown
+fn place_main_window<R: Runtime>(app: &tauri::AppHandle<R>) -> Option<layout::Slide> {
+    let window = app.get_webview_window(MAIN_WINDOW_LABEL)?;
+    let settings = current_settings(app);
+    let animated = animations_enabled(app);
+    let tray_icon = app
+        .try_state::<TrayAnchor>()
+        .and_then(|anchor| anchor.take())
+        .filter(|_| settings.tray_anchored_panel);
+    let placed = match tray_icon {
+        Some(icon) => layout::place_at_tray(&window, &settings, icon, animated),
+        None => layout::place(&window, &settings, animated),
+    };
+    match placed {
+        Ok(slide) => slide,
+        Err(e) => {
+            log::warn!("Failed to place panel: {}", e);
+            None
+        }
+    }
+}
+
+async fn slide_in_main_window<R: Runtime>(app: &tauri::AppHandle<R>, slide: Option<layout::Slide>) {
+    if let (Some(slide), Some(window)) = (slide, app.get_webview_window(MAIN_WINDOW_LABEL)) {
+        layout::slide_in(&window, slide).await;
+    }
+}
+
+async fn slide_out_main_window<R: Runtime>(app: &tauri::AppHandle<R>) {
+    if !animations_enabled(app) {
+        return;
+    }
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+        layout::slide_out(&window, &current_settings(app)).await;
+    }
+}
+
+/// Whether the panel should animate: `panel_animations` is on and the
+/// system isn't asking for reduced motion
+fn animations_enabled<R: Runtime>(app: &tauri::AppHandle<R>) -> bool {
+    current_settings(app).panel_animations && !reduce_motion()
+}
+
+#[cfg(target_os = "macos")]
+fn reduce_motion() -> bool {
+    use objc::{class, msg_send, sel, sel_impl};
+    unsafe {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let reduce: bool = msg_send![workspace, accessibilityDisplayShouldReduceMotion];
+        reduce
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn reduce_motion() -> bool {
+    false
+}
+
+/// Length of the panel's fade in and out
+#[cfg(target_os = "macos")]
+const FADE_DURATION: std::time::Duration = std::time::Duration::from_millis(120);
+
+/// How far below its place the panel starts as it fades in, in points
+#[cfg(target_os = "macos")]
+const FADE_RISE: f64 = 10.0;
+
+/// Whether showing and hiding the main panel should fade it. Bars slide
+/// instead.
+#[cfg(target_os = "macos")]
+fn fades<R: Runtime>(app: &tauri::AppHandle<R>) -> bool {
+    animations_enabled(app)
+        && current_settings(app).panel_layout == crate::settings::PanelLayout::Panel
+}
+
+/// Make `ns_window` transparent and drop it slightly, ready for `fade_in`.
+/// Returns where it belongs. Must run on the main thread.
+#[cfg(target_os = "macos")]
+unsafe fn prepare_fade_in(ns_window: id) -> cocoa::foundation::NSPoint {
+    use cocoa::foundation::{NSPoint, NSRect};
+    use objc::{msg_send, sel, sel_impl};
+
+    let frame: NSRect = msg_send![ns_window, frame];
+    let _: () = msg_send![ns_window, setAlphaValue: 0.0_f64];
+    // Cocoa's y axis points up, so lower on screen is a smaller y
+    let start = NSPoint::new(frame.origin.x, frame.origin.y - FADE_RISE);
+    let _: () = msg_send![ns_window, setFrameOrigin: start];
+    frame.origin
+}
+
+/// Fade `ns_window` in while it rises back to `origin`. Must run on the
+/// main thread, after the window is shown.
+#[cfg(target_os = "macos")]
+unsafe fn fade_in(ns_window: id, origin: cocoa::foundation::NSPoint) {
+    use objc::{class, msg_send, sel, sel_impl};
+
+    let _: () = msg_send![class!(NSAnimationContext), beginGrouping];
+    let context: id = msg_send![class!(NSAnimationContext), currentContext];
+    let _: () = msg_send![context, setDuration: FADE_DURATION.as_secs_f64()];
+    let animator: id = msg_send![ns_window, animator];
+    let _: () = msg_send![animator, setAlphaValue: 1.0_f64];
+    let _: () = msg_send![animator, setFrameOrigin: origin];
+    let _: () = msg_send![class!(NSAnimationContext), endGrouping];
+}
+
+/// Fade the main panel out, returning once it's invisible. The caller
+/// orders it out and then restores its alpha with `reset_alpha`.
+#[cfg(target_os = "macos")]
+async fn fade_out<R: Runtime>(app: &tauri::AppHandle<R>) {
+    use objc::{class, msg_send, sel, sel_impl};
+
+    if !fades(app) {
+        return;
+    }
+    let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) else {
+        return;
+    };
+    let started = app.run_on_main_thread(move || {
+        let Ok(ns_window) = window.ns_window() else {
+            return;
+        };
+        let ns_window = ns_window as id;
+        unsafe {
+            let _: () = msg_send![class!(NSAnimationContext), beginGrouping];
+            let context: id = msg_send![class!(NSAnimationContext), currentContext];
+            let _: () = msg_send![context, setDuration: FADE_DURATION.as_secs_f64()];
+            let animator: id = msg_send![ns_window, animator];
+            let _: () = msg_send![animator, setAlphaValue: 0.0_f64];
+            let _: () = msg_send![class!(NSAnimationContext), endGrouping];
+        }
+    });
+    if started.is_ok() {
+        tokio::time::sleep(FADE_DURATION).await;
+    }
+}
+
+/// Undo `fade_out` once the panel is ordered out, so it's opaque next time.
+/// Must run on the main thread.
+#[cfg(target_os = "macos")]
+fn reset_alpha<R: Runtime>(window: &WebviewWindow<R>) {
+    use objc::{msg_send, sel, sel_impl};
+    if let Ok(ns_window) = window.ns_window() {
+        unsafe {
+            let _: () = msg_send![ns_window as id, setAlphaValue: 1.0_f64];
+        }
+    }
+}
+
+/// Palette mode: the panel floats over other apps without ever activating
+/// Yoink or becoming key, so clicks paste straight into the frontmost app.
+/// Only macOS panels can do this, and safe mode always uses the regular panel.
+pub fn is_palette_mode<R: Runtime>(app: &tauri::AppHandle<R>) -> bool {
+    cfg!(target_os = "macos") && current_settings(app).palette_mode
+}
+
+/// Switch the main panel between a regular panel and a non-activating
+/// palette. Must run on the main thread.
+#[cfg(target_os = "macos")]
+fn set_palette_style<R: Runtime>(window: &WebviewWindow<R>, palette: bool) {
+    use objc::{msg_send, sel, sel_impl};
+
+    /// NSWindowStyleMaskNonactivatingPanel
+    const NONACTIVATING_PANEL: u64 = 1 << 7;
+
+    let Ok(ns_window) = window.ns_window() else {
+        return;
+    };
+    let ns_window = ns_window as id;
+    if ns_window.is_null() {
+        return;
+    }
+    unsafe {
+        let mask: u64 = msg_send![ns_window, styleMask];
+        let mask = if palette {
+            mask | NONACTIVATING_PANEL
+        } else {
+            mask & !NONACTIVATING_PANEL
+        };
+        let _: () = msg_send![ns_window, setStyleMask: mask];
+        // A click on the webview would otherwise still make the panel key
+        let _: () = msg_send![ns_window, setBecomesKeyOnlyIfNeeded: palette];
+    }
+}
+
+/// Bring the main panel up: activated and key for keyboard navigation, or
+/// in palette mode ordered front without touching focus
+#[cfg(target_os = "macos")]
+fn present_panel<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    panel: ShareId<RawNSPanel>,
+) -> Result<(), String> {
+    let palette = is_palette_mode(app);
+    let fading = fades(app);
+    let window = app.get_webview_window(MAIN_WINDOW_LABEL);
+
+    // AppKit operations must run on the main thread
+    app.run_on_main_thread(move || {
+        use objc::{msg_send, sel, sel_impl, class};
+
+        let ns_window = window
+            .as_ref()
+            .and_then(|w| w.ns_window().ok())
+            .map(|w| w as id)
+            .filter(|w| !w.is_null());
+        if let Some(window) = &window {
+            set_palette_style(window, palette);
+        }
+        let fade = ns_window
+            .filter(|_| fading)
+            .map(|ns_window| unsafe { (ns_window, prepare_fade_in(ns_window)) });
+
+        if palette {
+            panel.order_front_regardless();
+        } else {
+            // Activate the application to receive focus (required for accessory apps)
+            unsafe {
+                let ns_app: cocoa::base::id = msg_send![class!(NSApplication), sharedApplication];
+                let _: () = msg_send![ns_app, activateIgnoringOtherApps: true];
+            }
+
+            panel.show();
+            panel.make_key_window();
+        }
+
+        if let Some((ns_window, origin)) = fade {
+            unsafe { fade_in(ns_window, origin) };
+        }
+    }).map_err(|e| e.to_string())
+}
+
 // Tauri commands
 
 #[tauri::command]
 pub async fn show_window<R: Runtime>(app: tauri::AppHandle<R>) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
-        use crate::window::WebviewWindowExt;
-
         // Capture the previous frontmost app before we take focus
         if let Some(prev_app_state) = app.try_state::<PreviousAppState>() {
             prev_app_state.capture();
         }
 
         if let Ok(panel) = app.get_webview_panel(MAIN_WINDOW_LABEL) {
-            if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
-                let _ = window.center_at_cursor_monitor();
-            }
-            // AppKit operations must run on the main thread
-            app.run_on_main_thread(move || {
-                use objc::{msg_send, sel, sel_impl, class};
-
-                // Activate the application to receive focus (required for accessory apps)
-                unsafe {
-                    let ns_app: cocoa::base::id = msg_send![class!(NSApplication), sharedApplication];
-                    let _: () = msg_send![ns_app, activateIgnoringOtherApps: true];
-                }
-
-                panel.show();
-                panel.make_key_window();
-            }).map_err(|e| e.to_string())?;
+            let slide = place_main_window(&app);
+            present_panel(&app, panel)?;
+            slide_in_main_window(&app, slide).await;
             return Ok(());
         }
     }
 
+    let slide = place_main_window(&app);
     if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
         window.show().map_err(|e| e.to_string())?;
         window.set_focus().map_err(|e| e.to_string())?;
     }
+    slide_in_main_window(&app, slide).await;
 
     Ok(())
 }
@@ -424,6 +1171,7 @@ pub async fn hide_window<R: Runtime>(app: tauri::AppHandle<R>) -> Result<(), Str
     if let Some(hotkey_state) = app.try_state::<HotkeyModeState>() {
         hotkey_state.exit();
     }
+    close_preview_window(&app);
 
     #[cfg(target_os = "macos")]
     {
@@ -431,6 +1179,11 @@ pub async fn hide_window<R: Runtime>(app: tauri::AppHandle<R>) -> Result<(), Str
         let prev_app_state = app.try_state::<PreviousAppState>();
 
         if let Ok(panel) = app.get_webview_panel(MAIN_WINDOW_LABEL) {
+            if panel.is_visible() {
+                slide_out_main_window(&app).await;
+                fade_out(&app).await;
+            }
+
             // Set guard to prevent delegate from re-entering order_out
             let hide_guard = app.try_state::<PanelHideGuard>();
             if let Some(ref guard) = hide_guard {
@@ -438,8 +1191,12 @@ pub async fn hide_window<R: Runtime>(app: tauri::AppHandle<R>) -> Result<(), Str
             }
 
             // AppKit operations must run on the main thread
+            let window = app.get_webview_window(MAIN_WINDOW_LABEL);
             app.run_on_main_thread(move || {
                 panel.order_out(None);
+                if let Some(window) = &window {
+                    reset_alpha(window);
+                }
             }).map_err(|e| e.to_string())?;
 
             if let Some(ref guard) = hide_guard {
@@ -456,6 +1213,9 @@ pub async fn hide_window<R: Runtime>(app: tauri::AppHandle<R>) -> Result<(), Str
     }
 
     if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+        if window.is_visible().unwrap_or(false) {
+            slide_out_main_window(&app).await;
+        }
         window.hide().map_err(|e| e.to_string())?;
     }
 
@@ -466,7 +1226,6 @@ pub async fn hide_window<R: Runtime>(app: tauri::AppHandle<R>) -> Result<(), Str
 pub async fn toggle_window<R: Runtime>(app: tauri::AppHandle<R>) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
-        use crate::window::WebviewWindowExt;
         if let Ok(panel) = app.get_webview_panel(MAIN_WINDOW_LABEL) {
             // Check visibility before running on main thread
             let is_visible = panel.is_visible();
@@ -476,6 +1235,9 @@ pub async fn toggle_window<R: Runtime>(app: tauri::AppHandle<R>) -> Result<(), S
                 if let Some(hotkey_state) = app.try_state::<HotkeyModeState>() {
                     hotkey_state.exit();
                 }
+                close_preview_window(&app);
+                slide_out_main_window(&app).await;
+                fade_out(&app).await;
 
                 // Closing - get previous app state for restoration
                 let prev_app_state = app.try_state::<PreviousAppState>();
@@ -486,8 +1248,12 @@ pub async fn toggle_window<R: Runtime>(app: tauri::AppHandle<R>) -> Result<(), S
                     guard.set_hiding();
                 }
 
+                let window = app.get_webview_window(MAIN_WINDOW_LABEL);
                 app.run_on_main_thread(move || {
                     panel.order_out(None);
+                    if let Some(window) = &window {
+                        reset_alpha(window);
+                    }
                 }).map_err(|e| e.to_string())?;
 
                 if let Some(ref guard) = hide_guard {
@@ -504,22 +1270,9 @@ pub async fn toggle_window<R: Runtime>(app: tauri::AppHandle<R>) -> Result<(), S
                     prev_app_state.capture();
                 }
 
-                if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
-                    let _ = window.center_at_cursor_monitor();
-                }
-
-                app.run_on_main_thread(move || {
-                    use objc::{msg_send, sel, sel_impl, class};
-
-                    // Activate the application to receive focus (required for accessory apps)
-                    unsafe {
-                        let ns_app: cocoa::base::id = msg_send![class!(NSApplication), sharedApplication];
-                        let _: () = msg_send![ns_app, activateIgnoringOtherApps: true];
-                    }
-
-                    panel.show();
-                    panel.make_key_window();
-                }).map_err(|e| e.to_string())?;
+                let slide = place_main_window(&app);
+                present_panel(&app, panel)?;
+                slide_in_main_window(&app, slide).await;
             }
 
             return Ok(());
@@ -529,10 +1282,14 @@ pub async fn toggle_window<R: Runtime>(app: tauri::AppHandle<R>) -> Result<(), S
     if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
         let is_visible = window.is_visible().map_err(|e| e.to_string())?;
         if is_visible {
+            close_preview_window(&app);
+            slide_out_main_window(&app).await;
             window.hide().map_err(|e| e.to_string())?;
         } else {
+            let slide = place_main_window(&app);
             window.show().map_err(|e| e.to_string())?;
             window.set_focus().map_err(|e| e.to_string())?;
+            slide_in_main_window(&app, slide).await;
         }
     }
 
@@ -579,3 +1336,133 @@ pub fn set_selected_item(state: tauri::State<'_, SelectedItemState>, id: String)
 pub fn is_hotkey_mode_active(hotkey_state: tauri::State<'_, HotkeyModeState>) -> bool {
     hotkey_state.is_active()
 }
+
+/// Save where the panel is now and open it there from now on
+#[tauri::command]
+pub async fn save_window_position<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    manager: tauri::State<'_, crate::settings::SettingsManager>,
+) -> Result<crate::settings::Settings, String> {
+    let window = app
+        .get_webview_window(MAIN_WINDOW_LABEL)
+        .ok_or_else(|| "Main window not found".to_string())?;
+    let position = layout::current_position(&window)?;
+    manager.update_field(|s| {
+        s.window_position = crate::settings::WindowPosition::Fixed;
+        s.fixed_window_position = Some(position);
+    })
+}
+
+/// Switch between the comfortable panel and a compact strip, resizing the
+/// panel now if it's open
+#[tauri::command]
+pub async fn set_layout_mode<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    manager: tauri::State<'_, crate::settings::SettingsManager>,
+    mode: crate::settings::LayoutMode,
+) -> Result<crate::settings::Settings, String> {
+    let settings = manager.update_field(|s| s.layout_mode = mode)?;
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+        if window.is_visible().unwrap_or(false) {
+            layout::resize(&window, &current_settings(&app))?;
+        }
+    }
+    let _ = app.emit("layout-mode-changed", mode);
+    Ok(settings)
+}
+
+/// Keep the panel's current size as the preset for the display it's on
+#[tauri::command]
+pub async fn save_display_panel_size<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    manager: tauri::State<'_, crate::settings::SettingsManager>,
+) -> Result<crate::settings::Settings, String> {
+    let window = app
+        .get_webview_window(MAIN_WINDOW_LABEL)
+        .ok_or_else(|| "Main window not found".to_string())?;
+    let (display, size) = layout::current_display_size(&window)?;
+    manager.update_field(|s| {
+        s.display_panel_sizes.insert(display, size);
+    })
+}
+
+/// Forget the size preset for `display`, going back to the layout mode size
+#[tauri::command]
+pub async fn clear_display_panel_size<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    manager: tauri::State<'_, crate::settings::SettingsManager>,
+    display: String,
+) -> Result<crate::settings::Settings, String> {
+    let settings = manager.update_field(|s| {
+        s.display_panel_sizes.remove(&display);
+    })?;
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+        if window.is_visible().unwrap_or(false) {
+            layout::resize(&window, &current_settings(&app))?;
+        }
+    }
+    Ok(settings)
+}
+
+/// Set how high the panel floats and whether it appears over full-screen
+/// apps, applying both to the panel right away
+#[tauri::command]
+pub async fn set_panel_level<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    manager: tauri::State<'_, crate::settings::SettingsManager>,
+    level: crate::settings::PanelLevel,
+    show_over_full_screen: bool,
+) -> Result<crate::settings::Settings, String> {
+    let settings = manager.update_field(|s| {
+        s.panel_level = level;
+        s.show_over_full_screen = show_over_full_screen;
+    })?;
+    #[cfg(target_os = "macos")]
+    apply_panel_behavior(&app)?;
+    #[cfg(not(target_os = "macos"))]
+    let _ = app;
+    Ok(settings)
+}
+
+/// Set whether the panel joins every space or only the current one,
+/// applying it to the panel right away
+#[tauri::command]
+pub async fn set_panel_spaces<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    manager: tauri::State<'_, crate::settings::SettingsManager>,
+    spaces: crate::settings::PanelSpaces,
+) -> Result<crate::settings::Settings, String> {
+    let settings = manager.update_field(|s| s.panel_spaces = spaces)?;
+    #[cfg(target_os = "macos")]
+    apply_panel_behavior(&app)?;
+    #[cfg(not(target_os = "macos"))]
+    let _ = app;
+    Ok(settings)
+}
+
+/// Id of the menu bar / system tray icon
+pub const TRAY_ID: &str = "main";
+
+/// Toggle the panel from a tray icon click, anchored to the icon
+pub async fn toggle_window_from_tray<R: Runtime>(app: tauri::AppHandle<R>) -> Result<(), String> {
+    if let Some(anchor) = app.try_state::<TrayAnchor>() {
+        anchor.request();
+    }
+    toggle_window(app).await
+}
+
+/// Turn the tray popover on or off. While on, a left click on the tray icon
+/// toggles the panel and the tray menu moves to the right click.
+#[tauri::command]
+pub async fn set_tray_anchored_panel<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    manager: tauri::State<'_, crate::settings::SettingsManager>,
+    enabled: bool,
+) -> Result<crate::settings::Settings, String> {
+    let settings = manager.update_field(|s| s.tray_anchored_panel = enabled)?;
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        tray.set_show_menu_on_left_click(!current_settings(&app).tray_anchored_panel)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(settings)
+}
@@ -14,6 +14,8 @@ import { useProStore } from '@/stores/proStore';
 import { useHotkeyModeStore } from '@/stores/hotkeyModeStore';
 import clsx from 'clsx';
 
+const PRO_VALIDATION_CHECK_MS = 60 * 60 * 1000;
+
 export default function App() {
   // Initialize hooks
   useClipboardMonitor();
@@ -22,7 +24,7 @@ export default function App() {
 
   const { settings, loadSettings, setupListeners: setupSettingsListeners, applyTheme } =
     useSettingsStore();
-  const { checkAuth } = useProStore();
+  const { checkAuth, validateProStatus } = useProStore();
   const { isHotkeyMode, setupListeners: setupHotkeyModeListeners } = useHotkeyModeStore();
 
   // Load initial data
@@ -44,11 +46,23 @@ export default function App() {
     // Apply theme immediately
     applyTheme();
 
+    // Keep Pro validated while the app stays open (the store skips checks
+    // made within its validation interval)
+    const validation = setInterval(validateProStatus, PRO_VALIDATION_CHECK_MS);
+
     return () => {
       cleanupSettings?.();
       cleanupHotkeyMode?.();
+      clearInterval(validation);
     };
-  }, [loadSettings, checkAuth, setupSettingsListeners, setupHotkeyModeListeners, applyTheme]);
+  }, [
+    loadSettings,
+    checkAuth,
+    validateProStatus,
+    setupSettingsListeners,
+    setupHotkeyModeListeners,
+    applyTheme,
+  ]);
 
   return (
     <div
@@ -1,6 +1,7 @@
 import React from 'react';
 import { formatDistanceToNow } from 'date-fns';
 import clsx from 'clsx';
+import { invoke } from '@tauri-apps/api/core';
 import type { ClipboardItem as ClipboardItemType } from '@/stores/clipboardStore';
 import { useSettingsStore } from '@/stores/settingsStore';
 import { useQueueStore } from '@/stores/queueStore';
@@ -60,6 +61,15 @@ export function ClipboardItem({ item, index, isSelected, onSelect, onPaste }: Cl
 
   const timestamp = formatDistanceToNow(new Date(item.created_at), { addSuffix: true });
 
+  // Native menu, so it isn't clipped at the panel's edges
+  const handleContextMenu = (e: React.MouseEvent) => {
+    e.preventDefault();
+    onSelect();
+    invoke('show_item_context_menu', { id: item.id, x: e.clientX, y: e.clientY }).catch(
+      console.error
+    );
+  };
+
   const handleQueueToggle = (e: React.MouseEvent) => {
     e.stopPropagation();
     if (isInQueue) {
@@ -80,6 +90,7 @@ export function ClipboardItem({ item, index, isSelected, onSelect, onPaste }: Cl
       )}
       onClick={onSelect}
       onDoubleClick={onPaste}
+      onContextMenu={handleContextMenu}
     >
       <div className="flex items-start gap-3">
         {/* Icon */}
@@ -0,0 +1,58 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import type { ClipboardItem } from '@/stores/clipboardStore';
+
+/** Contents of the always-visible pinned board: one tile per pinned item */
+export function PinnedBoardWindow() {
+  const [pinned, setPinned] = useState<ClipboardItem[]>([]);
+
+  useEffect(() => {
+    invoke<ClipboardItem[]>('get_pinned_items').then(setPinned).catch(console.error);
+    const unlisten = listen<ClipboardItem[]>('pinned-changed', (event) => setPinned(event.payload));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return (
+    <div
+      data-tauri-drag-region
+      className="h-screen flex flex-col rounded-lg bg-[var(--bg-primary)] border border-[var(--border-color)]"
+    >
+      <div data-tauri-drag-region className="flex items-center justify-between px-3 py-2">
+        <span className="text-xs font-medium text-[var(--text-secondary)]">
+          Pinned ({pinned.length})
+        </span>
+        <button
+          onClick={() => invoke('set_pinned_board', { enabled: false })}
+          className="text-xs text-[var(--text-tertiary)] hover:text-[var(--text-primary)]"
+        >
+          Close
+        </button>
+      </div>
+
+      <div className="flex-1 overflow-y-auto px-2 pb-2">
+        {pinned.length === 0 && (
+          <p className="text-xs text-[var(--text-tertiary)] px-1">
+            Pin items in the main panel to keep them here
+          </p>
+        )}
+        <div className="grid grid-cols-3 gap-1">
+          {pinned.map((item) => (
+            <button
+              key={item.id}
+              onClick={() => invoke('paste_from_board', { id: item.id }).catch(console.error)}
+              title={item.preview}
+              className="h-14 px-2 py-1 rounded text-left text-xs bg-[var(--bg-secondary)] hover:ring-2 hover:ring-accent-500 overflow-hidden"
+            >
+              <span className="line-clamp-3 break-words text-[var(--text-primary)]">
+                {item.preview}
+              </span>
+            </button>
+          ))}
+        </div>
+      </div>
+    </div>
+  );
+}
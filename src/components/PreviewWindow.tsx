@@ -0,0 +1,131 @@
+import { useEffect, useState } from 'react';
+import { convertFileSrc, invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+interface FileEntry {
+  path: string;
+  name: string;
+  extension: string | null;
+  exists: boolean;
+  is_dir: boolean;
+  size: number | null;
+}
+
+type Preview =
+  | {
+      kind: 'text';
+      id: string;
+      content_type: string;
+      format: 'plain' | 'json';
+      text: string;
+      offset: number;
+      next_offset: number | null;
+      total_chars: number;
+    }
+  | { kind: 'image'; id: string; path: string; width: number; height: number }
+  | { kind: 'files'; id: string; entries: FileEntry[] };
+
+function formatSize(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+/** Contents of the detachable preview window beside the main panel */
+export function PreviewWindow() {
+  const [preview, setPreview] = useState<Preview | null>(null);
+
+  // Huge text arrives in chunks; append the next one on request
+  const loadMore = async () => {
+    if (preview?.kind !== 'text' || preview.next_offset === null) return;
+    const next = await invoke<Preview>('render_quicklook', {
+      id: preview.id,
+      offset: preview.next_offset,
+    });
+    if (next.kind === 'text') {
+      setPreview({ ...next, offset: preview.offset, text: preview.text + next.text });
+    }
+  };
+
+  useEffect(() => {
+    invoke<Preview | null>('get_preview').then(setPreview).catch(console.error);
+    const unlisten = listen<Preview>('preview-changed', (event) => setPreview(event.payload));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return (
+    <div
+      data-tauri-drag-region
+      className="h-screen flex flex-col rounded-lg bg-[var(--bg-primary)] border border-[var(--border-color)]"
+    >
+      <div data-tauri-drag-region className="flex items-center justify-between px-3 py-2">
+        <span className="text-xs font-medium text-[var(--text-secondary)]">
+          {preview?.kind === 'image'
+            ? `Image (${preview.width}×${preview.height})`
+            : preview?.kind === 'files'
+              ? `${preview.entries.length} file${preview.entries.length === 1 ? '' : 's'}`
+              : 'Preview'}
+        </span>
+        <button
+          onClick={() => invoke('close_preview')}
+          className="text-xs text-[var(--text-tertiary)] hover:text-[var(--text-primary)]"
+        >
+          Close
+        </button>
+      </div>
+
+      <div className="flex-1 overflow-auto px-3 pb-3">
+        {preview?.kind === 'text' && (
+          <>
+            <pre className="text-sm text-[var(--text-primary)] whitespace-pre-wrap break-words font-mono">
+              {preview.text}
+            </pre>
+            {preview.next_offset !== null && (
+              <button
+                onClick={() => loadMore().catch(console.error)}
+                className="mt-2 text-xs text-accent-500 hover:text-accent-600"
+              >
+                Load more ({(preview.total_chars - preview.next_offset).toLocaleString()} characters
+                left)
+              </button>
+            )}
+          </>
+        )}
+        {preview?.kind === 'image' && (
+          <img
+            src={convertFileSrc(preview.path)}
+            alt=""
+            className="max-w-full h-auto mx-auto rounded"
+          />
+        )}
+        {preview?.kind === 'files' && (
+          <ul className="space-y-1">
+            {preview.entries.map((entry) => (
+              <li key={entry.path} className="text-xs">
+                <div className="flex items-center justify-between gap-2">
+                  <span
+                    className={
+                      entry.exists
+                        ? 'truncate text-[var(--text-primary)]'
+                        : 'truncate text-[var(--text-tertiary)] line-through'
+                    }
+                  >
+                    {entry.is_dir ? `${entry.name}/` : entry.name}
+                  </span>
+                  {entry.size !== null && (
+                    <span className="shrink-0 text-[var(--text-tertiary)]">
+                      {formatSize(entry.size)}
+                    </span>
+                  )}
+                </div>
+                <div className="truncate text-[var(--text-tertiary)]">{entry.path}</div>
+              </li>
+            ))}
+          </ul>
+        )}
+      </div>
+    </div>
+  );
+}
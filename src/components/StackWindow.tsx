@@ -0,0 +1,78 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import clsx from 'clsx';
+import type { ClipboardItem } from '@/stores/clipboardStore';
+
+/** Contents of the floating stack window, top of the stack first */
+export function StackWindow() {
+  const [stack, setStack] = useState<ClipboardItem[]>([]);
+
+  useEffect(() => {
+    invoke<ClipboardItem[]>('get_stack').then(setStack).catch(console.error);
+    const unlisten = listen<ClipboardItem[]>('stack-changed', (event) => setStack(event.payload));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return (
+    <div
+      data-tauri-drag-region
+      className="h-screen flex flex-col rounded-lg bg-[var(--bg-primary)] border border-[var(--border-color)]"
+    >
+      <div data-tauri-drag-region className="flex items-center justify-between px-3 py-2">
+        <span className="text-xs font-medium text-[var(--text-secondary)]">
+          Stack ({stack.length})
+        </span>
+        <div className="flex items-center gap-2">
+          <button
+            onClick={() => invoke('clear_stack')}
+            className="text-xs text-red-500 hover:underline"
+          >
+            Clear
+          </button>
+          <button
+            onClick={() => invoke('set_stack_mode', { enabled: false })}
+            className="text-xs text-[var(--text-tertiary)] hover:text-[var(--text-primary)]"
+          >
+            Done
+          </button>
+        </div>
+      </div>
+
+      <div className="flex-1 overflow-y-auto px-2 pb-2 space-y-1">
+        {stack.length === 0 && (
+          <p className="text-xs text-[var(--text-tertiary)] px-1">
+            Copy something to add it to the stack
+          </p>
+        )}
+        {stack.map((item, index) => (
+          <div
+            key={item.id}
+            className={clsx(
+              'flex items-center gap-2 px-2 py-1 rounded text-xs',
+              'bg-[var(--bg-secondary)]',
+              index === 0 && 'ring-2 ring-accent-500'
+            )}
+          >
+            <span className="flex-1 truncate text-[var(--text-primary)]">{item.preview}</span>
+            <button
+              onClick={() => invoke('remove_from_stack', { id: item.id })}
+              className="text-[var(--text-tertiary)] hover:text-red-500"
+            >
+              <svg className="w-3 h-3" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                <path
+                  strokeLinecap="round"
+                  strokeLinejoin="round"
+                  strokeWidth={2}
+                  d="M6 18L18 6M6 6l12 12"
+                />
+              </svg>
+            </button>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
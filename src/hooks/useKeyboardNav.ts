@@ -71,24 +71,24 @@ export function useKeyboardNav() {
       if (e.key === 'ArrowDown') {
         e.preventDefault();
         selectNext();
+        const state = useClipboardStore.getState();
+        const item = state.items[state.selectedIndex];
         // Sync selection to backend for hotkey mode paste on modifier release
-        if (isHotkeyMode) {
-          const state = useClipboardStore.getState();
-          const item = state.items[state.selectedIndex];
-          if (item) invoke('set_selected_item', { id: item.id });
-        }
+        if (isHotkeyMode && item) invoke('set_selected_item', { id: item.id });
+        // An open preview window follows the highlight
+        if (item) invoke('set_preview_item', { id: item.id });
         return;
       }
 
       if (e.key === 'ArrowUp') {
         e.preventDefault();
         selectPrevious();
+        const state = useClipboardStore.getState();
+        const item = state.items[state.selectedIndex];
         // Sync selection to backend for hotkey mode paste on modifier release
-        if (isHotkeyMode) {
-          const state = useClipboardStore.getState();
-          const item = state.items[state.selectedIndex];
-          if (item) invoke('set_selected_item', { id: item.id });
-        }
+        if (isHotkeyMode && item) invoke('set_selected_item', { id: item.id });
+        // An open preview window follows the highlight
+        if (item) invoke('set_preview_item', { id: item.id });
         return;
       }
 
@@ -113,6 +113,14 @@ export function useKeyboardNav() {
         return;
       }
 
+      // Space toggles the preview window for the selected item
+      if (e.key === ' ') {
+        e.preventDefault();
+        const item = items[useClipboardStore.getState().selectedIndex];
+        if (item) await invoke('show_preview', { id: item.id });
+        return;
+      }
+
       // Cmd/Ctrl+P to toggle pin
       if ((e.metaKey || e.ctrlKey) && e.key === 'p') {
         e.preventDefault();
@@ -114,26 +114,6 @@ export async function signOut(): Promise<void> {
   await saveSession(null);
 }
 
-// Check if user has Pro status
-export async function checkProStatus(userId: string): Promise<boolean> {
-  try {
-    const { data, error } = await supabase
-      .from('users')
-      .select('is_pro')
-      .eq('id', userId)
-      .single();
-
-    if (error) {
-      console.error('Error checking pro status:', error);
-      return false;
-    }
-
-    return data?.is_pro || false;
-  } catch {
-    return false;
-  }
-}
-
 // Listen for auth state changes
 export function onAuthStateChange(
   callback: (event: string, session: Session | null) => void
@@ -1,10 +1,24 @@
 import React from 'react';
 import ReactDOM from 'react-dom/client';
 import App from './components/App';
+import { StackWindow } from './components/StackWindow';
+import { PreviewWindow } from './components/PreviewWindow';
+import { PinnedBoardWindow } from './components/PinnedBoardWindow';
 import './index.css';
 
+// Secondary windows load the same bundle with a `view` query parameter
+const view = new URLSearchParams(window.location.search).get('view');
+
 ReactDOM.createRoot(document.getElementById('root')!).render(
   <React.StrictMode>
-    <App />
+    {view === 'stack' ? (
+      <StackWindow />
+    ) : view === 'preview' ? (
+      <PreviewWindow />
+    ) : view === 'pinned' ? (
+      <PinnedBoardWindow />
+    ) : (
+      <App />
+    )}
   </React.StrictMode>
 );
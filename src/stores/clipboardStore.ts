@@ -81,9 +81,9 @@ export const useClipboardStore = create<ClipboardState>((set, get) => ({
     set({ isLoading: true, error: null });
     try {
       const { search, selectedCollectionId } = get();
-      const items = await invoke<ClipboardItem[]>('get_clipboard_items', {
+      const { items } = await invoke<{ items: ClipboardItem[] }>('get_clipboard_items', {
         limit: 100,
-        offset: 0,
+        cursor: null,
         search: search || null,
         collectionId: selectedCollectionId,
       });
@@ -1,4 +1,5 @@
 import { create } from 'zustand';
+import { invoke } from '@tauri-apps/api/core';
 
 export interface User {
   id: string;
@@ -6,6 +7,15 @@ export interface User {
   is_pro: boolean;
 }
 
+/** The backend's record, which is what actually gates Pro commands */
+interface EntitlementStatus {
+  tier: 'free' | 'pro';
+  trial_ends_at: string | null;
+  trial_active: boolean;
+  in_grace_period: boolean;
+  pro_commands: string[];
+}
+
 interface ProState {
   user: User | null;
   isAuthenticated: boolean;
@@ -51,7 +61,9 @@ export const useProStore = create<ProState>((set, get) => ({
         // Validate pro status
         await get().validateProStatus();
       } else {
-        set({ user: null, isAuthenticated: false, isPro: false });
+        // Signed out, Pro can still come from the trial
+        const status = await invoke<EntitlementStatus>('get_entitlements');
+        set({ user: null, isAuthenticated: false, isPro: status.tier === 'pro' });
       }
     } catch (error) {
       set({ error: String(error) });
@@ -79,7 +91,13 @@ export const useProStore = create<ProState>((set, get) => ({
     try {
       const { signOut } = await import('@/lib/supabase');
       await signOut();
-      set({ user: null, isAuthenticated: false, isPro: false, lastValidation: null });
+      const status = await invoke<EntitlementStatus>('clear_entitlements');
+      set({
+        user: null,
+        isAuthenticated: false,
+        isPro: status.tier === 'pro',
+        lastValidation: null,
+      });
     } catch (error) {
       set({ error: String(error) });
     } finally {
@@ -99,8 +117,16 @@ export const useProStore = create<ProState>((set, get) => ({
     }
 
     try {
-      const { checkProStatus } = await import('@/lib/supabase');
-      const isPro = await checkProStatus(user.id);
+      const { getSession } = await import('@/lib/supabase');
+      const session = await getSession();
+      if (!session) return;
+
+      // Validate through the backend so its command gate sees the result
+      const status = await invoke<EntitlementStatus>('refresh_entitlements', {
+        accessToken: session.access_token,
+        userId: user.id,
+      });
+      const isPro = status.tier === 'pro';
       set({
         isPro,
         user: { ...user, is_pro: isPro },